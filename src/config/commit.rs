@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use deltalake::ensure_table_uri;
+use url::Url;
+
+use crate::delta::idempotency::DEFAULT_IDEMPOTENCY_LOOKBACK;
+use crate::delta::schema_evolution::SchemaEvolutionPolicy;
+use crate::grpc::commit_stream::CommitStreamLimits;
+use crate::grpc::staging::StagingLimits;
+
+/// Reads `DELTA_TXN_SCHEMA_EVOLUTION` (`additive`, `widening` or `any`,
+/// case-insensitive; see SchemaEvolutionPolicy for what each allows).
+/// Unset or blank is the default, `additive`. An unrecognized value fails
+/// startup rather than falling back to a default an operator didn't pick
+/// -- same reasoning as load_grpc_config's half-set TLS pair.
+pub fn load_schema_evolution_policy() -> Result<SchemaEvolutionPolicy, Box<dyn std::error::Error>> {
+    match std::env::var("DELTA_TXN_SCHEMA_EVOLUTION") {
+        Ok(value) if !value.trim().is_empty() => Ok(value.parse()?),
+        _ => Ok(SchemaEvolutionPolicy::default()),
+    }
+}
+
+/// Reads `DELTA_TXN_STAGED_TXN_IDLE_TTL_SECS` (how long an open
+/// BeginTransaction transaction survives without a StageActions) and
+/// `DELTA_TXN_STAGED_TXN_MAX_BYTES` (the cap on everything staged across
+/// all open transactions), each defaulting to StagingLimits's own when
+/// unset or blank. A value that isn't a positive whole number fails
+/// startup, same as load_schema_evolution_policy.
+pub fn load_staging_limits() -> Result<StagingLimits, Box<dyn std::error::Error>> {
+    let defaults = StagingLimits::default();
+    Ok(StagingLimits {
+        idle_ttl: positive_env("DELTA_TXN_STAGED_TXN_IDLE_TTL_SECS")?
+            .map_or(defaults.idle_ttl, Duration::from_secs),
+        max_bytes: positive_env("DELTA_TXN_STAGED_TXN_MAX_BYTES")?
+            .map_or(defaults.max_bytes, |bytes| bytes as usize),
+    })
+}
+
+/// Reads `DELTA_TXN_COMMIT_STREAM_IDLE_TIMEOUT_SECS` (how long a
+/// CommitStream may go without a message) and
+/// `DELTA_TXN_COMMIT_STREAM_MAX_BYTES` (the cap on everything buffered
+/// across in-flight CommitStreams), each defaulting to
+/// CommitStreamLimits's own when unset or blank; same validation as
+/// load_staging_limits.
+pub fn load_commit_stream_limits() -> Result<CommitStreamLimits, Box<dyn std::error::Error>> {
+    let defaults = CommitStreamLimits::default();
+    Ok(CommitStreamLimits {
+        idle_timeout: positive_env("DELTA_TXN_COMMIT_STREAM_IDLE_TIMEOUT_SECS")?
+            .map_or(defaults.idle_timeout, Duration::from_secs),
+        max_bytes: positive_env("DELTA_TXN_COMMIT_STREAM_MAX_BYTES")?
+            .map_or(defaults.max_bytes, |bytes| bytes as usize),
+    })
+}
+
+/// Reads `DELTA_TXN_IDEMPOTENCY_LOOKBACK_VERSIONS`: how many of a table's
+/// newest versions a keyed Commit searches for its original at most (see delta::idempotency::idempotency_search_start). Unset or
+/// blank is DEFAULT_IDEMPOTENCY_LOOKBACK; anything but a positive whole
+/// number fails startup, same as load_staging_limits.
+pub fn load_idempotency_lookback() -> Result<u64, Box<dyn std::error::Error>> {
+    Ok(positive_env("DELTA_TXN_IDEMPOTENCY_LOOKBACK_VERSIONS")?
+        .unwrap_or(DEFAULT_IDEMPOTENCY_LOOKBACK))
+}
+
+/// Reads `DELTA_TXN_COORDINATOR_URI`, where MultiTableCommit keeps its
+/// coordinator log (see delta::multi_table::CoordinatorLog): any location a
+/// table could live at, reached with the same storage credentials. Replicas
+/// share one -- each record is leased to whichever server is applying it
+/// (see CoordinatorLog::claim), so a record another server left is only
+/// taken over once its lease expires. Unset or blank leaves
+/// MultiTableCommit disabled. A local path is accepted the way
+/// a table_uri is; a value that isn't a usable location fails startup.
+pub fn load_coordinator_uri() -> Result<Option<Url>, Box<dyn std::error::Error>> {
+    match std::env::var("DELTA_TXN_COORDINATOR_URI") {
+        Ok(value) if !value.trim().is_empty() => Ok(Some(ensure_table_uri(value.trim())?)),
+        _ => Ok(None),
+    }
+}
+
+fn positive_env(name: &str) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => match value.trim().parse() {
+            Ok(0) | Err(_) => {
+                Err(format!("{name} must be a positive whole number, got {value:?}").into())
+            }
+            Ok(n) => Ok(Some(n)),
+        },
+        _ => Ok(None),
+    }
+}
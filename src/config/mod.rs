@@ -2,7 +2,8 @@
 //! main() before the gRPC server starts listening. `grpc` covers the
 //! server's own listen address/TLS/auth; `storage` covers the object-store
 //! credentials handed to delta-rs per request and the optional
-//! table_uri allowlist.
+//! table_uri allowlist; `commit` covers what Commit will accept.
 
+pub mod commit;
 pub mod grpc;
 pub mod storage;
@@ -1,10 +1,23 @@
 use super::errors::DeltaTxnError;
-use deltalake::kernel::transaction::{CommitBuilder, TableReference};
-use deltalake::kernel::{Action, CommitInfo, Metadata, Protocol};
+use super::row_tracking::{
+    assign_row_ids, row_id_high_water_mark, row_tracking_supported, SELF_WRITTEN_FEATURES,
+};
+use bytes::Bytes;
+use delta_kernel::table_properties::TableProperties;
+use delta_kernel::Engine;
+use deltalake::kernel::transaction::{
+    CommitBuilder, CommitConflictError, CommitData, TableReference, TransactionError, PROTOCOL,
+};
+use deltalake::kernel::{Action, CommitInfo, Metadata, Protocol, Version};
+use deltalake::logstore::{CommitOrBytes, LogStore, LogStoreConfig, LogStoreRef};
 use deltalake::protocol::{DeltaOperation, SaveMode};
-use deltalake::DeltaTableBuilder;
+use deltalake::table::state::DeltaTableState;
+use deltalake::{DeltaResult, DeltaTableBuilder, DeltaTableError, ObjectStore};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 use url::Url;
+use uuid::Uuid;
 
 /// The single place that scans an action list for its `Protocol` action --
 /// shared by grpc::server::commit() (deciding whether a create-table
@@ -130,8 +143,10 @@ fn build_operation(actions: &[Action]) -> DeltaOperation {
             version: uint_param(params, "version"),
             datetime: int_param(params, "datetime"),
         },
-        // "WRITE", CONVERT (no DeltaOperation equivalent), an unrecognized
-        // string, or no CommitInfo/operation at all.
+        // "WRITE", CONVERT (no DeltaOperation equivalent), ADD FEATURE
+        // (whose AddFeature needs the feature list only EnableTableFeature
+        // has, see commit_with_operation), an unrecognized string, or no
+        // CommitInfo/operation at all.
         _ => default_write_operation(),
     }
 }
@@ -144,29 +159,361 @@ fn build_operation(actions: &[Action]) -> DeltaOperation {
 /// gRPC handler, before this function was called) and this write actually
 /// landing. See build_operation()'s own doc comment for how `actions`'
 /// own CommitInfo (if any) determines the DeltaOperation passed to
-/// CommitBuilder.
+/// CommitBuilder. `app_metadata` is merged into the commit's CommitInfo
+/// (e.g. idempotency::idempotency_metadata's key).
 pub async fn commit_actions(
     table: deltalake::DeltaTable,
     actions: Vec<Action>,
+    app_metadata: HashMap<String, serde_json::Value>,
+) -> Result<i64, DeltaTxnError> {
+    let operation = build_operation(&actions);
+    commit_with_operation(table, actions, operation, app_metadata).await
+}
+
+/// `commit_actions` for a commit the service itself composes, whose
+/// operation is known up front rather than inferred from a client
+/// CommitInfo -- e.g. EnableTableFeature's `DeltaOperation::AddFeature`,
+/// which CommitBuilder records as the commit's "ADD FEATURE" CommitInfo.
+pub async fn commit_with_operation(
+    table: deltalake::DeltaTable,
+    actions: Vec<Action>,
+    operation: DeltaOperation,
+    app_metadata: HashMap<String, serde_json::Value>,
 ) -> Result<i64, DeltaTxnError> {
     let table_state = table
         .snapshot()
         .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
-    let operation = build_operation(&actions);
 
+    let protocol = find_protocol(&actions).unwrap_or(table_state.protocol());
+    if row_tracking_supported(protocol) {
+        return commit_with_row_ids(table, actions, operation, app_metadata).await;
+    }
     let result = CommitBuilder::default()
         .with_actions(actions)
+        .with_app_metadata(app_metadata)
         .build(
-            Some(table_state as &dyn TableReference),
+            Some(&CheckedTable::new(table_state) as &dyn TableReference),
             table.log_store(),
             operation,
         )
         .await
-        .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
+        .map_err(map_commit_error)?;
 
     Ok(result.version() as i64)
 }
 
+/// How many times commit_with_row_ids assigns row IDs afresh after another
+/// commit beats it to the version they were assigned for.
+const ROW_ID_COMMIT_ATTEMPTS: usize = 15;
+
+/// commit_with_operation on a row-tracking table. Row IDs are assigned
+/// for the version a commit lands at (row_tracking::assign_row_ids), so
+/// CommitBuilder can't rebase it onto later versions itself: instead the
+/// commit is conflict-checked from `table`'s snapshot to the latest
+/// version by a dry run, given IDs against the latest snapshot, and
+/// written there with no retries. A commit landing in between starts
+/// another round.
+async fn commit_with_row_ids(
+    table: deltalake::DeltaTable,
+    mut actions: Vec<Action>,
+    operation: DeltaOperation,
+    app_metadata: HashMap<String, serde_json::Value>,
+) -> Result<i64, DeltaTxnError> {
+    let read_state = table
+        .snapshot()
+        .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
+    let mut latest = table.clone();
+    for _ in 0..ROW_ID_COMMIT_ATTEMPTS {
+        let newest = table
+            .log_store()
+            .get_latest_version(read_state.version())
+            .await
+            .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
+        if Some(newest) > latest.version() {
+            (_, actions) = dry_run(
+                read_state,
+                table.log_store(),
+                actions,
+                operation.clone(),
+                app_metadata.clone(),
+            )
+            .await?;
+            latest
+                .update_incremental(Some(newest))
+                .await
+                .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
+        }
+        let latest_state = latest
+            .snapshot()
+            .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
+
+        let mut attempt = actions.clone();
+        assign_row_ids(
+            &mut attempt,
+            row_id_high_water_mark(&latest).await?,
+            latest_state.version() as i64 + 1,
+        )?;
+        match CommitBuilder::default()
+            .with_max_retries(0)
+            .with_actions(attempt)
+            .with_app_metadata(app_metadata.clone())
+            .build(
+                Some(&CheckedTable::new(latest_state) as &dyn TableReference),
+                latest.log_store(),
+                operation.clone(),
+            )
+            .await
+        {
+            Ok(result) => return Ok(result.version() as i64),
+            Err(DeltaTableError::Transaction {
+                source: TransactionError::MaxCommitAttempts(0),
+            }) => continue,
+            Err(e) => return Err(map_commit_error(e)),
+        }
+    }
+    Err(DeltaTxnError::CommitFailed(format!(
+        "row IDs went stale {ROW_ID_COMMIT_ATTEMPTS} times running"
+    )))
+}
+
+/// A snapshot as CommitBuilder's protocol check sees it: its protocol
+/// without the features this service writes itself
+/// (row_tracking::SELF_WRITTEN_FEATURES), which delta-rs's checker would
+/// refuse the table for. Everything else, the snapshot the conflict check
+/// starts from included, is the snapshot's own.
+struct CheckedTable<'a> {
+    snapshot: &'a DeltaTableState,
+    protocol: Cow<'a, Protocol>,
+}
+
+impl<'a> CheckedTable<'a> {
+    fn new(snapshot: &'a DeltaTableState) -> Self {
+        Self::with_protocol(snapshot, snapshot.protocol())
+    }
+
+    /// `snapshot` as if its protocol were `protocol` -- see
+    /// check_writable_protocol.
+    fn with_protocol(snapshot: &'a DeltaTableState, protocol: &'a Protocol) -> Self {
+        let hidden = protocol.writer_features().is_some_and(|features| {
+            features
+                .iter()
+                .any(|feature| SELF_WRITTEN_FEATURES.contains(feature))
+        });
+        if !hidden {
+            return Self {
+                snapshot,
+                protocol: Cow::Borrowed(protocol),
+            };
+        }
+        // Round-tripped through its JSON form, the one way to build a
+        // Protocol from parts (see table_features::protocol_with_feature).
+        let mut value = serde_json::to_value(protocol).expect("a protocol serializes");
+        if let Some(features) = value["writerFeatures"].as_array_mut() {
+            features.retain(|feature| {
+                !SELF_WRITTEN_FEATURES
+                    .iter()
+                    .any(|hidden| feature.as_str() == Some(hidden.as_ref()))
+            });
+        }
+        Self {
+            snapshot,
+            protocol: Cow::Owned(
+                serde_json::from_value(value)
+                    .expect("a protocol with fewer features is still valid"),
+            ),
+        }
+    }
+}
+
+impl TableReference for CheckedTable<'_> {
+    fn config(&self) -> &TableProperties {
+        TableReference::config(self.snapshot)
+    }
+
+    fn protocol(&self) -> &Protocol {
+        &self.protocol
+    }
+
+    fn metadata(&self) -> &Metadata {
+        TableReference::metadata(self.snapshot)
+    }
+
+    fn eager_snapshot(&self) -> &deltalake::kernel::EagerSnapshot {
+        self.snapshot.eager_snapshot()
+    }
+}
+
+/// Whether CommitBuilder will write to `snapshot`'s table once its
+/// protocol is `protocol`: delta-rs's own protocol checker, asked ahead of
+/// committing that protocol (EnableTableFeature), so what it supports is
+/// never copied out of it to drift from it. The features this service
+/// writes itself are hidden from it, as on every commit.
+pub fn check_writable_protocol(
+    snapshot: &DeltaTableState,
+    protocol: &Protocol,
+) -> Result<(), DeltaTxnError> {
+    PROTOCOL
+        .can_write_to(&CheckedTable::with_protocol(snapshot, protocol))
+        .map_err(|e| map_commit_error(e.into()))
+}
+
+/// Runs CommitBuilder on `snapshot` all the way through its conflict check
+/// against every version since, without writing anything. Returns the
+/// version the commit would land at, and `actions` back as CommitBuilder
+/// leaves them, so a caller going on to commit them never holds two
+/// copies.
+async fn dry_run(
+    snapshot: &DeltaTableState,
+    log_store: LogStoreRef,
+    actions: Vec<Action>,
+    operation: DeltaOperation,
+    app_metadata: HashMap<String, serde_json::Value>,
+) -> Result<(i64, Vec<Action>), DeltaTxnError> {
+    let had_commit_info = actions
+        .iter()
+        .any(|action| matches!(action, Action::CommitInfo(_)));
+    let checked = CheckedTable::new(snapshot);
+    let prepared = CommitBuilder::default()
+        .with_actions(actions)
+        .with_app_metadata(app_metadata)
+        .build(
+            Some(&checked as &dyn TableReference),
+            Arc::new(DryRunLogStore(log_store)),
+            operation,
+        )
+        .into_prepared_commit_future()
+        .await
+        .map_err(map_commit_error)?;
+    // Awaiting the PostCommit would run the post-commit hooks against a
+    // version that was never written.
+    let post_commit = prepared.await.map_err(map_commit_error)?;
+    let mut actions = post_commit.data.actions;
+    // CommitBuilder puts a CommitInfo of its own first unless there was
+    // one already; the real commit builds it afresh.
+    if !had_commit_info {
+        actions.remove(0);
+    }
+    Ok((post_commit.version as i64, actions))
+}
+
+/// A LogStore whose commit writes succeed without writing: everything
+/// else, the reads CommitBuilder's conflict check makes included, goes to
+/// the table's own. Named as the default store so CommitBuilder hands it
+/// the commit's bytes rather than staging a temporary file first.
+struct DryRunLogStore(LogStoreRef);
+
+#[async_trait::async_trait]
+impl LogStore for DryRunLogStore {
+    fn name(&self) -> String {
+        "DefaultLogStore".to_string()
+    }
+
+    async fn refresh(&self) -> DeltaResult<()> {
+        self.0.refresh().await
+    }
+
+    async fn read_commit_entry(&self, version: Version) -> DeltaResult<Option<Bytes>> {
+        self.0.read_commit_entry(version).await
+    }
+
+    async fn write_commit_entry(
+        &self,
+        _version: Version,
+        _commit_or_bytes: CommitOrBytes,
+        _operation_id: Uuid,
+    ) -> Result<(), TransactionError> {
+        Ok(())
+    }
+
+    async fn abort_commit_entry(
+        &self,
+        _version: Version,
+        _commit_or_bytes: CommitOrBytes,
+        _operation_id: Uuid,
+    ) -> Result<(), TransactionError> {
+        Ok(())
+    }
+
+    async fn get_latest_version(&self, start_version: Version) -> DeltaResult<Version> {
+        self.0.get_latest_version(start_version).await
+    }
+
+    fn object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.0.object_store(operation_id)
+    }
+
+    fn root_object_store(&self, operation_id: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.0.root_object_store(operation_id)
+    }
+
+    fn engine(&self, operation_id: Option<Uuid>) -> Arc<dyn Engine> {
+        self.0.engine(operation_id)
+    }
+
+    fn config(&self) -> &LogStoreConfig {
+        self.0.config()
+    }
+}
+
+/// `commit_actions` up to, but not including, the log write -- what a
+/// validate_only Commit runs: delta-rs's protocol check, building the
+/// commit, and its conflict check against every version since `table`'s
+/// snapshot (see dry_run). Returns the version the commit would land at,
+/// and `actions` back for a caller that goes on to commit them.
+/// A commit landing after the dry run is still the real one's to detect.
+pub async fn validate_actions(
+    table: &deltalake::DeltaTable,
+    actions: Vec<Action>,
+    app_metadata: HashMap<String, serde_json::Value>,
+) -> Result<(i64, Vec<Action>), DeltaTxnError> {
+    let snapshot = table
+        .snapshot()
+        .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
+    let operation = build_operation(&actions);
+    dry_run(
+        snapshot,
+        table.log_store(),
+        actions,
+        operation,
+        app_metadata,
+    )
+    .await
+}
+
+/// CommitBuilder's error as a DeltaTxnError. Its refusal to write to a
+/// table whose protocol needs features it doesn't support is the client's
+/// business (nothing retrying or fixing the request will change), so it
+/// comes back as its own variant, as does a conflict with a concurrent
+/// commit; everything else stays internal.
+fn map_commit_error(err: DeltaTableError) -> DeltaTxnError {
+    match err {
+        DeltaTableError::Transaction {
+            source: TransactionError::UnsupportedTableFeatures(features),
+        } => DeltaTxnError::UnsupportedTableFeatures(
+            features.iter().map(ToString::to_string).collect(),
+        ),
+        DeltaTableError::Transaction {
+            source: TransactionError::CommitConflict(conflict),
+        } if is_concurrent_change(&conflict) => DeltaTxnError::CommitConflict(conflict.to_string()),
+        other => DeltaTxnError::CommitFailed(other.to_string()),
+    }
+}
+
+/// The conflicts that are another commit's doing, as opposed to the
+/// checker failing to read a snapshot or evaluate a predicate (internal
+/// errors, whose messages can carry storage detail).
+fn is_concurrent_change(conflict: &CommitConflictError) -> bool {
+    matches!(
+        conflict,
+        CommitConflictError::ConcurrentAppend
+            | CommitConflictError::ConcurrentDeleteRead
+            | CommitConflictError::ConcurrentDeleteDelete
+            | CommitConflictError::MetadataChanged
+            | CommitConflictError::ConcurrentTransaction
+            | CommitConflictError::ProtocolChanged(_)
+    )
+}
+
 /// Bootstraps a brand-new Delta table at `table_url` by committing
 /// `actions` as its version-0 commit -- the create-path counterpart to
 /// `commit_actions` above, used by grpc::server::commit() when
@@ -208,12 +555,15 @@ pub async fn commit_actions(
 /// is always version 0, so the check below turns "landed at some other
 /// version" into an explicit conflict error instead of a silent, corrupt-
 /// looking success.
+///
+/// `app_metadata` as for `commit_actions`.
 pub async fn create_table(
     table_url: Url,
     storage_options: HashMap<String, String>,
     actions: Vec<Action>,
     protocol: Protocol,
     metadata: Metadata,
+    app_metadata: HashMap<String, serde_json::Value>,
 ) -> Result<i64, DeltaTxnError> {
     let table = DeltaTableBuilder::from_url(table_url.clone())
         .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?
@@ -230,9 +580,10 @@ pub async fn create_table(
 
     let result = CommitBuilder::default()
         .with_actions(actions)
+        .with_app_metadata(app_metadata)
         .build(None, table.log_store(), operation)
         .await
-        .map_err(|e| DeltaTxnError::CommitFailed(e.to_string()))?;
+        .map_err(map_commit_error)?;
 
     let version = result.version() as i64;
     if version != 0 {
@@ -245,6 +596,29 @@ pub async fn create_table(
     Ok(version)
 }
 
+/// `create_table` without the log write -- see `validate_actions`. A
+/// table with no snapshot yet has no protocol checker to run either
+/// (CommitBuilder skips it for a create too), which leaves building the
+/// commit; the version is always 0.
+pub fn validate_create(
+    table_url: Url,
+    actions: Vec<Action>,
+    protocol: Protocol,
+    metadata: Metadata,
+    app_metadata: HashMap<String, serde_json::Value>,
+) -> Result<i64, DeltaTxnError> {
+    let operation = DeltaOperation::Create {
+        mode: SaveMode::ErrorIfExists,
+        location: table_url,
+        protocol,
+        metadata,
+    };
+    CommitData::new(actions, operation, app_metadata, Vec::new())
+        .get_bytes()
+        .map_err(|e| map_commit_error(e.into()))?;
+    Ok(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
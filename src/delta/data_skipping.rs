@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use deltalake::datafusion::arrow::datatypes::DataType as ArrowDataType;
+use deltalake::datafusion::common::DFSchema;
+use deltalake::datafusion::logical_expr::ExprSchemable;
+use deltalake::datafusion::physical_optimizer::pruning::PruningPredicate;
+use deltalake::datafusion::prelude::SessionContext;
+use deltalake::delta_datafusion::{create_session, DataFusionMixins};
+use deltalake::kernel::EagerSnapshot;
+
+use super::errors::DeltaTxnError;
+
+/// Which of `snapshot`'s active files could contain rows matching the SQL
+/// boolean expression `predicate` (e.g. `id > 100 AND region = 'US'`), as
+/// one flag per file in `snapshot.try_log_data()` order -- `false` means the
+/// file provably has no matching row and can be skipped.
+///
+/// The predicate is parsed against the table's own schema (partition
+/// columns included) by DataFusion's SQL parser, then evaluated by
+/// DataFusion's `PruningPredicate` against each file's statistics: a
+/// partition column's value, and for every other column the min/max/
+/// nullCount stats its AddFile recorded. That makes the answer
+/// conservative, never exact: a file whose stats are missing, or don't
+/// cover a referenced column, is always kept, and a kept file may still
+/// have no matching row -- the reader applies the predicate to the data
+/// itself. Parsing failures (bad syntax, an unknown column, a non-boolean
+/// expression) are `DeltaTxnError::InvalidPredicate`.
+pub fn files_matching_predicate(
+    snapshot: &EagerSnapshot,
+    predicate: &str,
+) -> Result<Vec<bool>, DeltaTxnError> {
+    let log_data = snapshot
+        .try_log_data()
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+    let session: SessionContext = create_session().into();
+
+    let expr = log_data
+        .parse_predicate_expression(predicate, &session.state())
+        .map_err(|e| DeltaTxnError::InvalidPredicate(e.to_string()))?;
+
+    let schema = log_data.read_schema();
+    let df_schema = DFSchema::try_from(schema.as_ref().clone())
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+    match expr.get_type(&df_schema) {
+        Ok(ArrowDataType::Boolean) => {}
+        Ok(other) => {
+            return Err(DeltaTxnError::InvalidPredicate(format!(
+                "predicate must be a boolean expression, got one of type {other}"
+            )))
+        }
+        Err(e) => return Err(DeltaTxnError::InvalidPredicate(e.to_string())),
+    }
+
+    // Type coercion (e.g. `day = '2026-10-17'` against a date column)
+    // happens here, on the way to the physical expression.
+    let physical = session
+        .create_physical_expr(expr, &df_schema)
+        .map_err(|e| DeltaTxnError::InvalidPredicate(e.to_string()))?;
+    let pruning = PruningPredicate::try_new(physical, Arc::clone(&schema))
+        .map_err(|e| DeltaTxnError::InvalidPredicate(e.to_string()))?;
+
+    pruning
+        .prune(&log_data)
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))
+}
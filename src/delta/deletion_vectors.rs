@@ -0,0 +1,81 @@
+use delta_kernel::table_features::TableFeature;
+use deltalake::kernel::{Action, Protocol};
+
+use super::errors::DeltaTxnError;
+
+/// Rejects a Commit whose Add or Remove actions carry a deletion vector
+/// unless `protocol` -- the commit's own Protocol action if it has one,
+/// else the table's current one -- lists the `deletionVectors` writer
+/// feature.
+///
+/// delta-rs writes a descriptor through on any table, but a reader only
+/// applies deletion vectors on a table whose protocol says it has them: on
+/// any other, the rows a DV marks deleted would silently reappear.
+pub fn check_deletion_vectors(
+    actions: &[Action],
+    protocol: &Protocol,
+) -> Result<(), DeltaTxnError> {
+    let Some(path) = actions.iter().find_map(|action| match action {
+        Action::Add(add) if add.deletion_vector.is_some() => Some(&add.path),
+        Action::Remove(remove) if remove.deletion_vector.is_some() => Some(&remove.path),
+        _ => None,
+    }) else {
+        return Ok(());
+    };
+    let enabled = protocol
+        .writer_features()
+        .is_some_and(|features| features.contains(&TableFeature::DeletionVectors));
+    if enabled {
+        Ok(())
+    } else {
+        Err(DeltaTxnError::TableFeatureRequired {
+            feature: TableFeature::DeletionVectors.to_string(),
+            reason: format!("'{path}' carries a deletion vector"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake::kernel::{Add, DeletionVectorDescriptor, StorageType};
+
+    fn protocol(value: serde_json::Value) -> Protocol {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn add_with_dv() -> Action {
+        Action::Add(Add {
+            path: "part-0.parquet".to_string(),
+            deletion_vector: Some(DeletionVectorDescriptor {
+                storage_type: StorageType::Inline,
+                path_or_inline_dv: "wi5b=000010000siXQKl0rr91000f55c8Xg0@@D72lkbi5=-{L".to_string(),
+                offset: None,
+                size_in_bytes: 40,
+                cardinality: 6,
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn a_deletion_vector_needs_the_writer_feature() {
+        let legacy = protocol(serde_json::json!({"minReaderVersion": 1, "minWriterVersion": 2}));
+        let err = check_deletion_vectors(&[add_with_dv()], &legacy).unwrap_err();
+        assert!(err.to_string().contains("deletionVectors"), "{err}");
+
+        let enabled = protocol(serde_json::json!({
+            "minReaderVersion": 3,
+            "minWriterVersion": 7,
+            "readerFeatures": ["deletionVectors"],
+            "writerFeatures": ["deletionVectors"]
+        }));
+        check_deletion_vectors(&[add_with_dv()], &enabled).unwrap();
+    }
+
+    #[test]
+    fn actions_without_deletion_vectors_pass_on_any_table() {
+        let legacy = protocol(serde_json::json!({"minReaderVersion": 1, "minWriterVersion": 2}));
+        check_deletion_vectors(&[Action::Add(Add::default())], &legacy).unwrap();
+    }
+}
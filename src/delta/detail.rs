@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use delta_kernel::expressions::StructData;
+use deltalake::kernel::scalars::ScalarExt;
+use deltalake::kernel::{EagerSnapshot, Protocol};
+
+use super::errors::DeltaTxnError;
+use super::partition_filter::PartitionFilters;
+
+/// Aggregates over a snapshot's active files -- what DescribeDetail and
+/// ListPartitions report instead of the files themselves.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileSummary {
+    pub num_files: u64,
+    pub size_in_bytes: u64,
+    /// One entry per distinct combination of partition values, ordered by
+    /// those values as serialized (nulls first); empty for an unpartitioned
+    /// table.
+    pub partitions: Vec<PartitionSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionSummary {
+    /// `(column, value)` in the table's partition-column order, `None`
+    /// being a null partition value.
+    pub values: Vec<(String, Option<String>)>,
+    pub num_files: u64,
+    pub size_in_bytes: u64,
+    /// Sum of `numRecords` over the files whose stats record it; the rest
+    /// are counted in `num_files_without_record_count` instead, so a
+    /// caller can tell an exact total from a lower bound.
+    pub num_records: u64,
+    pub num_files_without_record_count: u64,
+}
+
+// Running totals for one partition while summarize_files walks the files.
+#[derive(Default)]
+struct PartitionTotals {
+    num_files: u64,
+    size_in_bytes: u64,
+    num_records: u64,
+    num_files_without_record_count: u64,
+}
+
+/// Walks the snapshot's already-loaded file list once -- the same list
+/// ListActiveFiles streams -- counting files and bytes overall and per
+/// partition, skipping files `partition_filters` rules out. Memory is
+/// proportional to the number of distinct partitions, not files.
+pub fn summarize_files(
+    snapshot: &EagerSnapshot,
+    partition_filters: &PartitionFilters,
+) -> Result<FileSummary, DeltaTxnError> {
+    let log_data = snapshot
+        .try_log_data()
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+    let partition_columns = snapshot.metadata().partition_columns();
+
+    let mut summary = FileSummary::default();
+    let mut partitions: BTreeMap<Vec<Option<String>>, PartitionTotals> = BTreeMap::new();
+    for file_view in log_data.iter() {
+        let partition_data = file_view.partition_values();
+        if !partition_filters.matches(partition_data.as_ref()) {
+            continue;
+        }
+
+        // A file's size is never negative; i64 is just the log's encoding.
+        let size = u64::try_from(file_view.size()).unwrap_or_default();
+        summary.num_files += 1;
+        summary.size_in_bytes += size;
+        if partition_columns.is_empty() {
+            continue;
+        }
+
+        let values = partition_data
+            .map(|data| serialize_partition_values(&data))
+            .unwrap_or_default();
+        let key = partition_columns
+            .iter()
+            .map(|column| {
+                values
+                    .iter()
+                    .find(|(name, _)| name == column)
+                    .and_then(|(_, value)| value.clone())
+            })
+            .collect();
+        let totals = partitions.entry(key).or_default();
+        totals.num_files += 1;
+        totals.size_in_bytes += size;
+        match file_view.num_records() {
+            Some(records) => totals.num_records += records as u64,
+            None => totals.num_files_without_record_count += 1,
+        }
+    }
+
+    summary.partitions = partitions
+        .into_iter()
+        .map(|(values, totals)| PartitionSummary {
+            values: partition_columns.iter().cloned().zip(values).collect(),
+            num_files: totals.num_files,
+            size_in_bytes: totals.size_in_bytes,
+            num_records: totals.num_records,
+            num_files_without_record_count: totals.num_files_without_record_count,
+        })
+        .collect();
+    Ok(summary)
+}
+
+/// A file's parsed partition values back in Delta's string serialization
+/// (`None` for null), in field order.
+///
+/// LogicalFileView::partition_values() -> Option<StructData> has no
+/// convenient accessor of its own (deltalake-core's maintainers say as
+/// much in their own internal StructDataExt doc comment) -- walking
+/// .fields()/.values() in lockstep and serializing each non-null Scalar is
+/// exactly what deltalake-core's own (private)
+/// LogicalFileView::partition_values_map() helper does internally;
+/// replicated here since that helper isn't public.
+pub fn serialize_partition_values(data: &StructData) -> Vec<(String, Option<String>)> {
+    data.fields()
+        .iter()
+        .zip(data.values().iter())
+        .map(|(field, value)| {
+            (
+                field.name().to_string(),
+                if value.is_null() {
+                    None
+                } else {
+                    Some(value.serialize())
+                },
+            )
+        })
+        .collect()
+}
+
+/// The table features `protocol` names, reader and writer lists merged,
+/// sorted and deduplicated. Empty for a legacy protocol (reader version
+/// below 3 / writer version below 7), whose features are implied by its
+/// version numbers rather than listed.
+///
+/// Read through the protocol's own log JSON (`readerFeatures`/
+/// `writerFeatures`) because the kernel keeps its feature accessors
+/// crate-private.
+pub fn table_features(protocol: &Protocol) -> Vec<String> {
+    let Ok(value) = serde_json::to_value(protocol) else {
+        return Vec::new();
+    };
+    ["readerFeatures", "writerFeatures"]
+        .iter()
+        .filter_map(|key| value.get(key).and_then(|list| list.as_array()))
+        .flatten()
+        .filter_map(|feature| feature.as_str().map(str::to_string))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_features_merges_reader_and_writer_lists() {
+        let protocol: Protocol = serde_json::from_value(serde_json::json!({
+            "minReaderVersion": 3,
+            "minWriterVersion": 7,
+            "readerFeatures": ["deletionVectors"],
+            "writerFeatures": ["deletionVectors", "appendOnly"]
+        }))
+        .unwrap();
+        assert_eq!(
+            table_features(&protocol),
+            vec!["appendOnly", "deletionVectors"]
+        );
+
+        let legacy: Protocol = serde_json::from_value(serde_json::json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 2
+        }))
+        .unwrap();
+        assert!(table_features(&legacy).is_empty());
+    }
+}
@@ -1,4 +1,16 @@
 use thiserror::Error;
+use tonic_types::{ErrorDetails, FieldViolation, StatusExt};
+
+use super::partition_values::PartitionValueViolation;
+
+/// How many field violations an INVALID_ARGUMENT's BadRequest detail
+/// carries at most, and how many bytes of field paths and descriptions
+/// between them -- whichever runs out first. Sized so the encoded detail
+/// stays well inside hyper's default 16 KiB header-list limit on the
+/// client side; see the InvalidPartitionValues arm of
+/// `From<DeltaTxnError> for tonic::Status`.
+pub const MAX_REPORTED_VIOLATIONS: usize = 100;
+pub const MAX_REPORTED_VIOLATION_BYTES: usize = 8 * 1024;
 
 /// Every failure mode this service's own Delta-table logic (as opposed to
 /// gRPC/transport-level failures, which use tonic::Status directly) can
@@ -24,6 +36,13 @@ pub enum DeltaTxnError {
     #[error("Delta commit failed: {0}")]
     CommitFailed(String),
 
+    /// Reading or writing MultiTableCommit's coordinator log
+    /// (multi_table::CoordinatorLog) failed -- an object-store error or an
+    /// unreadable record. Internal, like OpenFailed: the detail names
+    /// storage locations.
+    #[error("Coordinator log failed: {0}")]
+    CoordinatorFailed(String),
+
     /// This service's *own* pre-commit optimistic-concurrency check (see
     /// grpc::server::DeltaTxnGrpcServer::commit()): the caller's
     /// CommitRequest.expected_version didn't match the table's actual
@@ -33,6 +52,114 @@ pub enum DeltaTxnError {
     /// CommitBuilder at all.
     #[error("Version conflict: expected {expected}, found {actual}")]
     VersionConflict { expected: i64, actual: i64 },
+
+    /// A time-travel read (table::open_table_at_version) asked for a
+    /// version newer than anything the table's log has reached yet.
+    #[error("Version {requested} does not exist yet (latest is {latest})")]
+    VersionNotFound { requested: i64, latest: i64 },
+
+    /// A time-travel read asked for a version the table *did* once have,
+    /// but that can no longer be reconstructed -- its commit files (and any
+    /// checkpoint that could have stood in for them) already removed by
+    /// log retention cleanup. `reason` is delta-rs's own load error,
+    /// internal detail only (see the From impl below).
+    #[error("Version {requested} is no longer available: {reason}")]
+    VersionUnavailable { requested: i64, reason: String },
+
+    /// A timestamp-based time-travel read (history::version_at_timestamp)
+    /// asked for a moment before the oldest version the table can still be
+    /// loaded at -- either before the table existed at all, or before what
+    /// log retention cleanup has left behind.
+    #[error(
+        "Timestamp {requested} is before the earliest available version {earliest_version} \
+         (committed at {earliest_timestamp})"
+    )]
+    TimestampBeforeHistory {
+        requested: i64,
+        earliest_version: i64,
+        earliest_timestamp: i64,
+    },
+
+    /// A partition filter (partition_filter::PartitionFilters::bind) that
+    /// doesn't fit the table it was applied to: an unknown or
+    /// non-partition column, or a value that doesn't parse as the
+    /// column's type. Only checkable once the table's schema is loaded,
+    /// which is why it isn't a plain up-front INVALID_ARGUMENT.
+    #[error("Invalid partition filter: {0}")]
+    InvalidPartitionFilter(String),
+
+    /// A ListActiveFiles predicate (data_skipping::files_matching_predicate)
+    /// that doesn't parse as a boolean SQL expression over the table's
+    /// columns. Like InvalidPartitionFilter, only checkable once the
+    /// schema is loaded. Wraps DataFusion's own parse/plan error string.
+    #[error("Invalid predicate: {0}")]
+    InvalidPredicate(String),
+
+    /// A Commit's TableMetadata action whose schema the configured
+    /// schema_evolution::SchemaEvolutionPolicy doesn't allow in place of
+    /// the table's current one -- a dropped field, a type change, a new
+    /// non-nullable field. Names the offending field.
+    #[error("Incompatible schema change: {0}")]
+    IncompatibleSchema(String),
+
+    /// Commit AddFile actions whose partition_values don't fit the table's
+    /// partition columns (partition_values::check_add_partition_values) --
+    /// every violation across the whole request, not just the first.
+    #[error("Invalid partition values: {} violation(s)", .0.len())]
+    InvalidPartitionValues(Vec<PartitionValueViolation>),
+
+    /// A Commit using something (a deletion vector, say) that the table's
+    /// protocol doesn't enable the table feature for. `feature` is the
+    /// feature's log name (e.g. "deletionVectors"); `reason` says what in
+    /// the request needed it.
+    #[error("Table feature {feature} required: {reason}")]
+    TableFeatureRequired { feature: String, reason: String },
+
+    /// The table's protocol needs table features delta-rs's CommitBuilder
+    /// doesn't support writing (e.g. columnMapping) -- it refuses every
+    /// commit to such a table. Feature log names.
+    #[error("Unsupported table features: {0:?}")]
+    UnsupportedTableFeatures(Vec<String>),
+
+    /// A Commit AddFile to a row-tracking table whose stats don't record
+    /// num_records -- needed to assign its row IDs, or to account for
+    /// client-assigned ones in the high-water mark
+    /// (row_tracking::assign_row_ids).
+    #[error("Missing row count for {path}")]
+    MissingRowCount { path: String },
+
+    /// An EnableTableFeature feature name that isn't one of the features
+    /// table_features::parse_table_feature knows.
+    #[error("Unknown table feature: {0}")]
+    UnknownTableFeature(String),
+
+    /// A Protocol action that would lower the table's protocol versions or
+    /// drop a feature it supports (table_features::check_protocol_upgrade).
+    /// Says what it would take away.
+    #[error("Protocol downgrade: {0}")]
+    ProtocolDowngrade(String),
+
+    /// delta-rs's conflict checker found a commit that landed after the
+    /// snapshot this one was built on and that it can't be reconciled
+    /// with -- both deleting the same file, say, or a concurrent metadata
+    /// change. Most commits are built on the latest snapshot under the
+    /// table lock, leaving only another replica's commit to conflict
+    /// with; a staged transaction's is built on its Begin version. The
+    /// message is delta-rs's description of the conflict, which names no
+    /// paths.
+    #[error("Commit conflict: {0}")]
+    CommitConflict(String),
+
+    /// A Commit SetTransaction whose version isn't newer than the one the
+    /// table already records for its app_id
+    /// (set_transaction::check_transactions) -- most likely a retry of a
+    /// commit that landed.
+    #[error("Duplicate transaction for {app_id}: version {version}, recorded {recorded}")]
+    DuplicateTransaction {
+        app_id: String,
+        version: i64,
+        recorded: i64,
+    },
 }
 
 impl From<DeltaTxnError> for tonic::Status {
@@ -50,6 +177,152 @@ impl From<DeltaTxnError> for tonic::Status {
             DeltaTxnError::VersionConflict { expected, actual } => tonic::Status::aborted(format!(
                 "version conflict: expected {expected}, found {actual}"
             )),
+            // NOT_FOUND, same as a table_uri that doesn't exist at all: the
+            // thing asked for isn't there (yet). Both numbers are safe to
+            // echo back, same reasoning as VersionConflict above.
+            DeltaTxnError::VersionNotFound { requested, latest } => tonic::Status::not_found(
+                format!("version {requested} does not exist (latest version is {latest})"),
+            ),
+            // FAILED_PRECONDITION rather than NOT_FOUND: the version did
+            // exist, and no amount of retrying will bring it back -- the
+            // client needs to pick a different (newer) version instead.
+            // `reason` is delta-rs's own error string, logged, not sent.
+            DeltaTxnError::VersionUnavailable { requested, .. } => {
+                tracing::warn!(error = %err, "time-travel read of an unavailable version");
+                tonic::Status::failed_precondition(format!(
+                    "version {requested} is no longer available (its log files have been cleaned \
+                     up by log retention)"
+                ))
+            }
+            // FAILED_PRECONDITION, same reasoning as VersionUnavailable:
+            // there is no version to answer with, and retrying the same
+            // timestamp never will. The earliest version and its commit
+            // timestamp are exactly what the client needs to pick a valid
+            // timestamp instead, and are table metadata any GetTable
+            // caller can already see.
+            DeltaTxnError::TimestampBeforeHistory {
+                requested,
+                earliest_version,
+                earliest_timestamp,
+            } => tonic::Status::failed_precondition(format!(
+                "timestamp {requested} is before the earliest available version \
+                 {earliest_version} (committed at {earliest_timestamp})"
+            )),
+            // INVALID_ARGUMENT: the filter, not the table, is wrong. The
+            // message only names columns, types and values the client's
+            // own request and the table's (GetTable-visible) schema
+            // already contain.
+            DeltaTxnError::InvalidPartitionFilter(reason) => {
+                tonic::Status::invalid_argument(format!("invalid partition filter: {reason}"))
+            }
+            // INVALID_ARGUMENT, same as InvalidPartitionFilter. DataFusion's
+            // parse/plan errors describe the client's own expression and
+            // the table's column names (e.g. "No field named foo. Valid
+            // fields are ..."), which is exactly what the client needs to
+            // fix it and nothing GetTable wouldn't already show them.
+            DeltaTxnError::InvalidPredicate(reason) => {
+                tonic::Status::invalid_argument(format!("invalid predicate: {reason}"))
+            }
+            // INVALID_ARGUMENT: the new schema is the client's own, and
+            // the current one is what GetTable already shows them.
+            DeltaTxnError::IncompatibleSchema(reason) => {
+                tonic::Status::invalid_argument(format!("incompatible schema change: {reason}"))
+            }
+            // INVALID_ARGUMENT with a google.rpc.BadRequest detail listing
+            // each violation by its request field path, for clients that
+            // read rich error details; the message repeats the first one
+            // for those that don't. Only file paths, column names and
+            // values from the client's own request appear in either. The
+            // detail rides in the grpc-status-details-bin trailer, so it
+            // stops at MAX_REPORTED_VIOLATIONS (or _BYTES) -- a 100k-file
+            // commit with a violation on every add would otherwise overflow
+            // the header-list limit and reach the client as a transport
+            // error instead of INVALID_ARGUMENT. The message keeps the
+            // full count.
+            DeltaTxnError::InvalidPartitionValues(violations) => {
+                let first = violations
+                    .first()
+                    .map(|v| format!("; first: {}: {}", v.field, v.description))
+                    .unwrap_or_default();
+                let mut budget = MAX_REPORTED_VIOLATION_BYTES;
+                let details: Vec<_> = violations
+                    .iter()
+                    .take(MAX_REPORTED_VIOLATIONS)
+                    .take_while(|v| {
+                        let size = v.field.len() + v.description.len();
+                        budget = budget.saturating_sub(size);
+                        budget > 0
+                    })
+                    .map(|v| FieldViolation::new(&v.field, &v.description))
+                    .collect();
+                let reported = if details.len() < violations.len() {
+                    format!(" (details list the first {})", details.len())
+                } else {
+                    String::new()
+                };
+                tonic::Status::with_error_details(
+                    tonic::Code::InvalidArgument,
+                    format!(
+                        "{} invalid AddFile partition value(s){reported}{first}",
+                        violations.len()
+                    ),
+                    ErrorDetails::with_bad_request(details),
+                )
+            }
+            // FAILED_PRECONDITION: the request is well-formed, the table
+            // just isn't in a state to take it -- enabling the feature
+            // first (a Protocol upgrade) is what fixes it, not a different
+            // request. Both strings come from the request and the feature
+            // name GetTable's protocol already shows.
+            DeltaTxnError::TableFeatureRequired { feature, reason } => {
+                tonic::Status::failed_precondition(format!(
+                    "{reason}, which requires the table feature '{feature}' -- the table's \
+                     protocol doesn't enable it"
+                ))
+            }
+            // FAILED_PRECONDITION: nothing about the request can fix it; the
+            // table needs a writer that supports these features. The
+            // feature names are the table's own (GetTable-visible).
+            DeltaTxnError::UnsupportedTableFeatures(features) => {
+                tonic::Status::failed_precondition(format!(
+                    "this service can't write to tables using the table feature(s) {}",
+                    features.join(", ")
+                ))
+            }
+            // INVALID_ARGUMENT: the request left out what the table needs.
+            DeltaTxnError::MissingRowCount { path } => tonic::Status::invalid_argument(format!(
+                "'{path}' has no stats.num_records -- a table with row tracking needs each \
+                 added file's row count, to assign its row IDs or to account for the ones it \
+                 brings"
+            )),
+            // INVALID_ARGUMENT: the name is the client's own.
+            DeltaTxnError::UnknownTableFeature(name) => {
+                tonic::Status::invalid_argument(format!("unknown table feature '{name}'"))
+            }
+            // INVALID_ARGUMENT, like IncompatibleSchema: the new protocol
+            // is the client's own, and the current one is what GetTable
+            // already shows them.
+            DeltaTxnError::ProtocolDowngrade(reason) => {
+                tonic::Status::invalid_argument(format!("protocol change refused: it {reason}"))
+            }
+            // ALREADY_EXISTS: what this commit would record is already in
+            // the table, so the client's retry can stop here. Both versions
+            // and the app_id are the client's own (GetTransactionVersion
+            // shows the recorded one).
+            DeltaTxnError::DuplicateTransaction {
+                app_id,
+                version,
+                recorded,
+            } => tonic::Status::already_exists(format!(
+                "app_id '{app_id}' already committed transaction version {recorded}; version \
+                 {version} is not newer"
+            )),
+            // ABORTED, like VersionConflict: the table moved on in a way
+            // this commit can't be applied over; rebuilding it against the
+            // new state is the way forward.
+            DeltaTxnError::CommitConflict(reason) => tonic::Status::aborted(format!(
+                "commit conflicts with a concurrent commit: {reason}"
+            )),
             // OpenFailed/CommitFailed wrap delta-rs's own error strings,
             // which can include storage paths and other internal detail a
             // client has no legitimate need to see -- logged in full here
@@ -57,7 +330,9 @@ impl From<DeltaTxnError> for tonic::Status {
             // generic message. This is the one place either of these
             // errors' real detail is observable at all, so losing it here
             // means losing it entirely -- log before converting, not after.
-            DeltaTxnError::OpenFailed(_) | DeltaTxnError::CommitFailed(_) => {
+            DeltaTxnError::OpenFailed(_)
+            | DeltaTxnError::CommitFailed(_)
+            | DeltaTxnError::CoordinatorFailed(_) => {
                 tracing::error!(error = %err, "internal delta error");
                 tonic::Status::internal("internal error processing delta table")
             }
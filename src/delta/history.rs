@@ -0,0 +1,519 @@
+use super::errors::DeltaTxnError;
+use deltalake::kernel::{Action, CommitInfo};
+use deltalake::logstore::object_store::ObjectStoreExt;
+use deltalake::logstore::{get_actions, LogStoreRef};
+use deltalake::{DeltaTableBuilder, ObjectStoreError};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::RangeInclusive;
+use tokio_stream::StreamExt;
+use url::Url;
+
+/// What a single listing of a table's `_delta_log/` directory turned up:
+/// every commit file's version (with the file's own storage modification
+/// time, the last-resort timestamp source -- see `TableLog::read_actions`) and
+/// every checkpoint's version. Anything else in the directory
+/// (`_last_checkpoint`, `.crc` files, temp files a crashed writer left
+/// behind) is ignored.
+#[derive(Debug, Default)]
+pub(crate) struct LogListing {
+    commits: BTreeMap<u64, i64>,
+    checkpoints: BTreeSet<u64>,
+}
+
+impl LogListing {
+    fn record(&mut self, file_name: &str, modified_millis: i64) {
+        match parse_log_file_name(file_name) {
+            Some(LogFile::Commit(version)) => {
+                self.commits.insert(version, modified_millis);
+            }
+            Some(LogFile::Checkpoint(version)) => {
+                self.checkpoints.insert(version);
+            }
+            None => {}
+        }
+    }
+
+    /// The oldest and newest versions that both still have a commit file
+    /// *and* can actually be loaded, or `None` for a log with no commit
+    /// files at all.
+    ///
+    /// "Can be loaded" is the part a plain min/max over `commits` would get
+    /// wrong: log retention cleanup deletes commit files older than a
+    /// checkpoint, but can leave a few stragglers just below it (cleanup
+    /// only ever removes files older than the retention window, which
+    /// needn't line up with a checkpoint boundary). A straggler still has
+    /// a readable timestamp, but the table can't be reconstructed at its
+    /// version -- there's no checkpoint at or below it to start replay
+    /// from -- so resolving a timestamp to it would hand the client a
+    /// version the very next time-travel read rejects as unavailable. The
+    /// answer is the oldest version reachable by replaying commit files
+    /// forward, either from version 0 or from a checkpoint.
+    pub(crate) fn retained_range(&self) -> Option<(u64, u64)> {
+        let (&latest, _) = self.commits.last_key_value()?;
+
+        // Walk back from the latest commit for as long as the commit files
+        // are contiguous -- a gap means nothing below it can be replayed
+        // forward into the versions above it.
+        let mut run_start = latest;
+        while run_start > 0 && self.commits.contains_key(&(run_start - 1)) {
+            run_start -= 1;
+        }
+        if run_start == 0 {
+            return Some((0, latest));
+        }
+
+        // A checkpoint at `run_start - 1` (whose own commit file is gone)
+        // still makes `run_start` onward reachable; otherwise the oldest
+        // checkpoint inside the contiguous run is the floor.
+        let checkpoint = self.checkpoints.range(run_start - 1..=latest).next()?;
+        Some(((*checkpoint).max(run_start), latest))
+    }
+
+    /// The newest checkpoint at or before `version`, or `None` if there is
+    /// none -- the one a reader of `version` replays from. Taken from the
+    /// checkpoint files themselves rather than `_last_checkpoint`, which is
+    /// only ever a hint, and only about the newest checkpoint rather than
+    /// the one below a pinned older version.
+    pub(crate) fn latest_checkpoint(&self, version: u64) -> Option<u64> {
+        self.checkpoints.range(..=version).next_back().copied()
+    }
+}
+
+enum LogFile {
+    Commit(u64),
+    Checkpoint(u64),
+}
+
+// Delta's log file naming: a zero-padded 20-digit version, then either
+// `.json` (a commit) or `.checkpoint[...].parquet` (a single-part,
+// multi-part (`.checkpoint.0000000001.0000000003.parquet`) or v2/UUID-named
+// checkpoint -- all that matters here is which version it covers).
+fn parse_log_file_name(file_name: &str) -> Option<LogFile> {
+    let (prefix, rest) = file_name.split_at_checked(20)?;
+    if !prefix.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let version = prefix.parse().ok()?;
+    if rest == ".json" {
+        Some(LogFile::Commit(version))
+    } else if rest.starts_with(".checkpoint") && rest.ends_with(".parquet") {
+        Some(LogFile::Checkpoint(version))
+    } else {
+        None
+    }
+}
+
+/// A table's `_delta_log/` as seen by one listing, plus the log store to
+/// read individual commit files through -- the shared starting point for
+/// everything that walks a table's commit history rather than loading a
+/// snapshot (timestamp resolution, GetHistory). Never loads the table
+/// itself, so opening one costs a single LIST, however long the history.
+/// Same "call `table_exists` first" contract as table::open_table.
+pub struct TableLog {
+    log_store: LogStoreRef,
+    listing: LogListing,
+}
+
+/// One version's entry in a table's commit history: its commit timestamp
+/// (see `TableLog::read_actions`) and its commitInfo action, if the writer
+/// recorded one -- the Delta protocol doesn't require it, though every
+/// commit this service makes carries one.
+#[derive(Debug, Clone)]
+pub struct CommitEntry {
+    pub version: u64,
+    pub timestamp: i64,
+    pub commit_info: Option<CommitInfo>,
+}
+
+/// Every action one version's commit file holds, in log order, with the
+/// commit's timestamp (see `TableLog::read_actions`).
+#[derive(Debug, Clone)]
+pub struct CommitActions {
+    pub version: u64,
+    pub timestamp: i64,
+    pub actions: Vec<Action>,
+}
+
+impl TableLog {
+    /// Lists `_delta_log/` once. One paginated LIST is far cheaper than
+    /// probing commit files one at a time to find where retention cleanup
+    /// left the log starting, and the modification times come back with
+    /// it for free.
+    pub async fn open(
+        table_url: &Url,
+        storage_options: HashMap<String, String>,
+    ) -> Result<Self, DeltaTxnError> {
+        Self::open_from(table_url, storage_options, 0).await
+    }
+
+    /// `open`, but listing only the log's tail from `oldest` on, the way
+    /// `latest_version_after` does -- for a caller that only cares about
+    /// recent versions (an idempotency key search), so its cost doesn't
+    /// grow with the table's history. Nothing older is known to the
+    /// returned log: `retained_range` and `latest_checkpoint` describe
+    /// the tail, not the table.
+    pub async fn open_from(
+        table_url: &Url,
+        storage_options: HashMap<String, String>,
+        oldest: u64,
+    ) -> Result<Self, DeltaTxnError> {
+        let log_store = build_log_store(table_url, storage_options)?;
+
+        let object_store = log_store.object_store(None);
+        let log_path = log_store.log_path();
+        let mut entries = match oldest.checked_sub(1) {
+            Some(before) => object_store.list_with_offset(
+                Some(log_path),
+                &log_path.clone().join(format!("{before:020}.json")),
+            ),
+            None => object_store.list(Some(log_path)),
+        };
+
+        let mut listing = LogListing::default();
+        while let Some(entry) = entries.next().await {
+            let meta = entry.map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+            if let Some(file_name) = meta.location.filename() {
+                listing.record(file_name, meta.last_modified.timestamp_millis());
+            }
+        }
+        Ok(Self { log_store, listing })
+    }
+
+    /// See `LogListing::retained_range`.
+    pub fn retained_range(&self) -> Option<(u64, u64)> {
+        self.listing.retained_range()
+    }
+
+    /// Every version in `range` that still has a commit file, oldest
+    /// first. Unlike `retained_range`, this includes commit files that
+    /// survived retention cleanup below the oldest loadable version -- a
+    /// straggler can't be time-travelled to, but its commitInfo is still
+    /// perfectly good history.
+    pub fn commit_versions(
+        &self,
+        range: RangeInclusive<u64>,
+    ) -> impl DoubleEndedIterator<Item = u64> + '_ {
+        self.listing
+            .commits
+            .range(range)
+            .map(|(version, _)| *version)
+    }
+
+    /// Whether `version` still has a commit file.
+    pub fn has_commit(&self, version: u64) -> bool {
+        self.listing.commits.contains_key(&version)
+    }
+
+    /// The newest version with a commit file, or `None` for a log with no
+    /// commit files at all.
+    pub fn latest_version(&self) -> Option<u64> {
+        self.listing.commits.last_key_value().map(|(v, _)| *v)
+    }
+
+    /// See `LogListing::latest_checkpoint`.
+    pub fn latest_checkpoint(&self, version: u64) -> Option<u64> {
+        self.listing.latest_checkpoint(version)
+    }
+
+    /// Reads `version`'s commit file, or `None` if it isn't in this
+    /// listing or has been removed since -- retention cleanup racing the
+    /// read, which each caller decides how to treat (GetHistory skips it;
+    /// GetChanges, which can't silently drop changes, fails).
+    ///
+    /// The commit's timestamp follows Delta's own order of preference: the
+    /// commitInfo's `inCommitTimestamp` (written only by tables with the
+    /// inCommitTimestamp feature enabled, and the one timestamp the
+    /// protocol itself guarantees is monotonic), then the commitInfo's
+    /// ordinary `timestamp` (which delta-rs always writes), then the commit
+    /// file's own storage modification time for a commit with no
+    /// commitInfo at all -- what Delta readers used before
+    /// inCommitTimestamp existed.
+    pub async fn read_actions(&self, version: u64) -> Result<Option<CommitActions>, DeltaTxnError> {
+        let Some(&modified_millis) = self.listing.commits.get(&version) else {
+            return Ok(None);
+        };
+        let Some(bytes) = self
+            .log_store
+            .read_commit_entry(version)
+            .await
+            .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let actions =
+            get_actions(version, &bytes).map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+
+        let timestamp = actions
+            .iter()
+            .find_map(|action| match action {
+                Action::CommitInfo(ci) => ci
+                    .info
+                    .get("inCommitTimestamp")
+                    .and_then(|v| v.as_i64())
+                    .or(ci.timestamp),
+                _ => None,
+            })
+            .unwrap_or(modified_millis);
+
+        Ok(Some(CommitActions {
+            version,
+            timestamp,
+            actions,
+        }))
+    }
+
+    /// `read_actions`, keeping only the commitInfo -- what GetHistory and
+    /// timestamp resolution need.
+    pub async fn read_commit(&self, version: u64) -> Result<Option<CommitEntry>, DeltaTxnError> {
+        let Some(commit) = self.read_actions(version).await? else {
+            return Ok(None);
+        };
+        let commit_info = commit.actions.into_iter().find_map(|action| match action {
+            Action::CommitInfo(ci) => Some(ci),
+            _ => None,
+        });
+        Ok(Some(CommitEntry {
+            version: commit.version,
+            timestamp: commit.timestamp,
+            commit_info,
+        }))
+    }
+}
+
+// Also how delta::multi_table reaches its coordinator location: anywhere a
+// table could live, with the same storage options, whether or not one does.
+pub(crate) fn build_log_store(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+) -> Result<LogStoreRef, DeltaTxnError> {
+    Ok(DeltaTableBuilder::from_url(table_url.clone())
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?
+        .with_storage_options(storage_options)
+        .build()
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?
+        .log_store())
+}
+
+/// The checkpoint version `_last_checkpoint` points to, or `None` if there
+/// is no such file or it doesn't parse -- only ever a hint (see
+/// `LogListing::latest_checkpoint`), but one a listing can start from: it
+/// costs a GET where listing the whole log costs a LIST per thousand files.
+pub async fn last_checkpoint_hint(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+) -> Result<Option<u64>, DeltaTxnError> {
+    let log_store = build_log_store(table_url, storage_options)?;
+    let path = log_store.log_path().clone().join("_last_checkpoint");
+    let bytes = match log_store.object_store(None).get(&path).await {
+        Ok(result) => result
+            .bytes()
+            .await
+            .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?,
+        Err(ObjectStoreError::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(DeltaTxnError::OpenFailed(e.to_string())),
+    };
+    Ok(serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|hint| hint.get("version")?.as_u64()))
+}
+
+/// The newest version with a commit file strictly after `after`, or `None`
+/// if there isn't one (yet) -- the cheap "has anything new landed?" check a
+/// watcher polls with. Lists only the log's tail: with `after` set, the
+/// LIST starts past that version's commit file (a start-after listing on
+/// object stores that support one), so the cost stays proportional to
+/// what's new rather than to the table's whole history the way
+/// `TableLog::open` is. `after: None` lists the whole log once, to find
+/// the starting point. Same "call `table_exists` first" contract as
+/// table::open_table.
+pub async fn latest_version_after(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    after: Option<u64>,
+) -> Result<Option<u64>, DeltaTxnError> {
+    let log_store = build_log_store(table_url, storage_options)?;
+    let object_store = log_store.object_store(None);
+    let log_path = log_store.log_path();
+
+    let mut entries = match after {
+        Some(after) => object_store.list_with_offset(
+            Some(log_path),
+            &log_path.clone().join(format!("{after:020}.json")),
+        ),
+        None => object_store.list(Some(log_path)),
+    };
+
+    let mut latest = None;
+    while let Some(entry) = entries.next().await {
+        let meta = entry.map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+        if let Some(LogFile::Commit(version)) =
+            meta.location.filename().and_then(parse_log_file_name)
+        {
+            if after.is_none_or(|after| version > after) {
+                latest = latest.max(Some(version));
+            }
+        }
+    }
+    Ok(latest)
+}
+
+// Memoizes commit timestamps per version for one `version_at_timestamp`
+// call -- the binary search's final answer is always a version it already
+// probed, so without this the last step would re-read that commit file.
+struct CommitTimestamps<'a> {
+    log: &'a TableLog,
+    read: HashMap<u64, i64>,
+}
+
+impl CommitTimestamps<'_> {
+    async fn get(&mut self, version: u64) -> Result<i64, DeltaTxnError> {
+        if let Some(timestamp) = self.read.get(&version) {
+            return Ok(*timestamp);
+        }
+        // Gone between the listing and this read: fall back to the listed
+        // modification time rather than failing the whole search.
+        let timestamp = match self.log.read_commit(version).await? {
+            Some(entry) => entry.timestamp,
+            None => self.log.listing.commits[&version],
+        };
+        self.read.insert(version, timestamp);
+        Ok(timestamp)
+    }
+}
+
+/// A timestamp resolved to a version: the newest version committed at or
+/// before the requested time, and that version's own commit timestamp.
+#[derive(Debug, Clone, Copy)]
+pub struct VersionAtTimestamp {
+    pub version: u64,
+    pub commit_timestamp: i64,
+}
+
+/// Resolves `timestamp` (milliseconds since the Unix epoch) to the newest
+/// version whose commit timestamp is at or before it -- i.e. the version a
+/// reader would have seen as latest at that moment. A timestamp after the
+/// latest commit resolves to the latest version; one before the oldest
+/// still-loadable version (see `LogListing::retained_range`) is
+/// `DeltaTxnError::TimestampBeforeHistory`, whether that's because the
+/// table didn't exist yet or because retention cleanup has since removed
+/// the versions that would have answered it. Same "call `table_exists`
+/// first" contract as table::open_table.
+///
+/// Binary search over the retained range, reading one commit file per
+/// step, so the cost is logarithmic in the table's history rather than
+/// linear. That assumes commit timestamps increase with version, the same
+/// assumption delta-rs's own and Spark's timestamp-based time travel make;
+/// with writer clock skew between commits, the answer for a timestamp
+/// inside the skewed window is one of the neighbouring versions rather
+/// than a precise one.
+pub async fn version_at_timestamp(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    timestamp: i64,
+) -> Result<VersionAtTimestamp, DeltaTxnError> {
+    let log = TableLog::open(table_url, storage_options).await?;
+    let (earliest, latest) = log.retained_range().ok_or_else(|| {
+        DeltaTxnError::OpenFailed("no loadable commit files in _delta_log".to_string())
+    })?;
+
+    let mut timestamps = CommitTimestamps {
+        log: &log,
+        read: HashMap::new(),
+    };
+
+    let earliest_timestamp = timestamps.get(earliest).await?;
+    if timestamp < earliest_timestamp {
+        // u64 -> i64: see table::open_table_at_version's matching comment.
+        return Err(DeltaTxnError::TimestampBeforeHistory {
+            requested: timestamp,
+            earliest_version: earliest as i64,
+            earliest_timestamp,
+        });
+    }
+
+    // Invariant: `low` is always a version committed at or before
+    // `timestamp`; the answer is never below it or above `high`.
+    let (mut low, mut high) = (earliest, latest);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if timestamps.get(mid).await? <= timestamp {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Ok(VersionAtTimestamp {
+        version: low,
+        commit_timestamp: timestamps.get(low).await?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(commits: &[u64], checkpoints: &[u64]) -> LogListing {
+        let mut listing = LogListing::default();
+        for v in commits {
+            listing.record(&format!("{v:020}.json"), 0);
+        }
+        for v in checkpoints {
+            listing.record(&format!("{v:020}.checkpoint.parquet"), 0);
+        }
+        listing
+    }
+
+    #[test]
+    fn parses_commit_and_checkpoint_file_names() {
+        assert!(matches!(
+            parse_log_file_name("00000000000000000007.json"),
+            Some(LogFile::Commit(7))
+        ));
+        assert!(matches!(
+            parse_log_file_name("00000000000000000010.checkpoint.parquet"),
+            Some(LogFile::Checkpoint(10))
+        ));
+        assert!(matches!(
+            parse_log_file_name("00000000000000000010.checkpoint.0000000001.0000000002.parquet"),
+            Some(LogFile::Checkpoint(10))
+        ));
+        assert!(parse_log_file_name("_last_checkpoint").is_none());
+        assert!(parse_log_file_name("00000000000000000007.crc").is_none());
+        assert!(parse_log_file_name(".00000000000000000007.json.tmp").is_none());
+    }
+
+    #[test]
+    fn latest_checkpoint_is_the_newest_at_or_below_the_version() {
+        let log = listing(&[0, 1, 2, 3, 4, 5], &[2, 4]);
+        assert_eq!(log.latest_checkpoint(5), Some(4));
+        assert_eq!(log.latest_checkpoint(4), Some(4));
+        assert_eq!(log.latest_checkpoint(3), Some(2));
+        assert_eq!(log.latest_checkpoint(1), None);
+    }
+
+    #[test]
+    fn full_history_is_retained_from_version_zero() {
+        assert_eq!(listing(&[0, 1, 2], &[]).retained_range(), Some((0, 2)));
+        assert_eq!(listing(&[0, 1, 2], &[1]).retained_range(), Some((0, 2)));
+    }
+
+    #[test]
+    fn cleaned_up_history_starts_at_the_checkpoint() {
+        // Commit files 0-1 removed, checkpoint at 2 kept alongside its commit.
+        assert_eq!(listing(&[2, 3, 4], &[2]).retained_range(), Some((2, 4)));
+        // The checkpoint's own commit file removed too.
+        assert_eq!(listing(&[3, 4], &[2]).retained_range(), Some((3, 4)));
+    }
+
+    #[test]
+    fn stragglers_below_the_oldest_checkpoint_are_not_retained() {
+        // 1-2 survived cleanup but nothing below them can be replayed.
+        assert_eq!(listing(&[1, 2, 3, 4], &[3]).retained_range(), Some((3, 4)));
+    }
+
+    #[test]
+    fn empty_log_has_no_retained_range() {
+        assert_eq!(listing(&[], &[]).retained_range(), None);
+    }
+}
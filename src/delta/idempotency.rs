@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use deltalake::kernel::CommitInfo;
+use serde_json::Value;
+use url::Url;
+
+use super::errors::DeltaTxnError;
+use super::history::TableLog;
+
+/// The commitInfo key a Commit's idempotency key is recorded under. Not
+/// one of the fields delta-rs types (or reserves) on CommitInfo, so it
+/// lands in the flattened `info` map and round-trips through any Delta
+/// reader untouched.
+pub const IDEMPOTENCY_KEY_FIELD: &str = "idempotencyKey";
+
+/// The commit metadata recording `key`, for CommitBuilder's
+/// `with_app_metadata` -- which merges it into the commit's CommitInfo,
+/// whether that's the client's own or one CommitBuilder generates.
+pub fn idempotency_metadata(key: Option<&str>) -> HashMap<String, Value> {
+    key.map(|key| {
+        HashMap::from([(
+            IDEMPOTENCY_KEY_FIELD.to_string(),
+            Value::String(key.to_string()),
+        )])
+    })
+    .unwrap_or_default()
+}
+
+/// How many of a table's newest versions a keyed Commit searches for its
+/// original at most -- see idempotency_search_start. Each is one
+/// commit-file read under the table's lock, before the commit itself can
+/// go ahead, so this bounds what every attempt pays rather than letting it
+/// grow with the table's history. It's the retry horizon: a retry arriving
+/// after more commits than this have landed on top of its original doesn't
+/// find it -- and, without expected_version to fail it, commits again.
+pub const DEFAULT_IDEMPOTENCY_LOOKBACK: u64 = 100;
+
+/// The idempotency key a commit's CommitInfo records, if any.
+pub fn commit_idempotency_key(commit_info: &CommitInfo) -> Option<&str> {
+    commit_info.info.get(IDEMPOTENCY_KEY_FIELD)?.as_str()
+}
+
+/// The oldest version a keyed commit's original is searched for at: the
+/// oldest of the newest `lookback` versions up to `latest`, or the one
+/// after `expected_version` when that's newer -- the original can't have
+/// landed before it, which usually leaves just the newest version or two
+/// to read. A stale expected_version far behind `latest` doesn't widen the
+/// search past `lookback`: its commit fails the expected_version check
+/// instead, so an original older than that can't be applied twice.
+pub fn idempotency_search_start(expected_version: Option<i64>, latest: u64, lookback: u64) -> u64 {
+    let window = (latest + 1).saturating_sub(lookback);
+    match expected_version {
+        Some(expected) => ((expected.max(-1) + 1) as u64).max(window),
+        None => window,
+    }
+}
+
+/// The version of the commit recording `key` among those from `oldest` on,
+/// searching newest first, or `None` if no commit does. Read from the log
+/// rather than anything kept in memory, so a retry still finds its
+/// original after a server restart or when it reaches a different replica
+/// -- provided the original has landed by the time the retry looks: a
+/// retry racing an original still in flight on another replica finds
+/// nothing and commits again, since the table lock serializing the two is
+/// only per-process. Only the log's tail from `oldest` is listed (see
+/// TableLog::open_from), one commit file read per version in it; commits
+/// old enough for log retention cleanup to have deleted are forgotten
+/// along with them.
+pub async fn find_idempotent_commit(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    key: &str,
+    oldest: u64,
+) -> Result<Option<u64>, DeltaTxnError> {
+    let log = TableLog::open_from(table_url, storage_options, oldest).await?;
+    for version in log.commit_versions(oldest..=u64::MAX).rev() {
+        // Gone since the listing: retention cleanup only removes the oldest
+        // commits, so nothing older is left to find either.
+        let Some(entry) = log.read_commit(version).await? else {
+            break;
+        };
+        if entry.commit_info.as_ref().and_then(commit_idempotency_key) == Some(key) {
+            return Ok(Some(version));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_key_round_trips_through_commit_info_json() {
+        let commit_info = CommitInfo {
+            info: idempotency_metadata(Some("req-1")),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&commit_info).unwrap();
+        assert_eq!(json[IDEMPOTENCY_KEY_FIELD], "req-1");
+
+        let parsed: CommitInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(commit_idempotency_key(&parsed), Some("req-1"));
+        assert_eq!(commit_idempotency_key(&CommitInfo::default()), None);
+        assert!(idempotency_metadata(None).is_empty());
+    }
+
+    #[test]
+    fn the_search_starts_after_expected_version_or_lookback_versions_back() {
+        assert_eq!(idempotency_search_start(Some(37), 40, 10), 38);
+        assert_eq!(idempotency_search_start(Some(-1), 5, 10), 0);
+        assert_eq!(idempotency_search_start(None, 40, 10), 31);
+        assert_eq!(idempotency_search_start(None, 5, 10), 0);
+    }
+
+    #[test]
+    fn a_stale_expected_version_searches_no_further_back_than_lookback() {
+        assert_eq!(idempotency_search_start(Some(3), 5_000, 10), 4_991);
+        assert_eq!(idempotency_search_start(Some(-1), 5_000, 100), 4_901);
+    }
+}
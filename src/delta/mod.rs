@@ -1,3 +1,15 @@
 pub mod commit;
+pub mod data_skipping;
+pub mod deletion_vectors;
+pub mod detail;
 pub mod errors;
+pub mod history;
+pub mod idempotency;
+pub mod multi_table;
+pub mod partition_filter;
+pub mod partition_values;
+pub mod row_tracking;
+pub mod schema_evolution;
+pub mod set_transaction;
 pub mod table;
+pub mod table_features;
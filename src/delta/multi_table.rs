@@ -0,0 +1,698 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use deltalake::kernel::{Action, Add, CommitInfo, Transaction};
+use deltalake::logstore::object_store::{ObjectStoreExt, PutMode, PutOptions, PutPayload};
+use deltalake::{DeltaTable, ObjectStore, ObjectStoreError, Path};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_stream::StreamExt;
+use tracing::warn;
+use url::Url;
+
+use super::errors::DeltaTxnError;
+use super::history::{build_log_store, TableLog};
+use super::idempotency::IDEMPOTENCY_KEY_FIELD;
+
+/// The commitInfo key each part of a MultiTableCommit records the
+/// multi-table commit's id under; what landed_version() looks for.
+pub const MULTI_TABLE_COMMIT_ID_FIELD: &str = "multiTableCommitId";
+
+/// The commitInfo key the commit taking a rolled-back part back out of its
+/// table records the multi-table commit's id under; what undone_version()
+/// looks for.
+pub const MULTI_TABLE_ROLLBACK_FIELD: &str = "multiTableCommitRolledBack";
+
+/// How long a claim on a coordinator log record lasts unless renewed; it is
+/// renewed before each table's commit. Only liveness depends on it: a
+/// holder that overruns it is fenced off by MultiTableIntent::fence.
+pub const LEASE_TTL: Duration = Duration::from_secs(60);
+
+/// One decided MultiTableCommit, written to the coordinator log before any
+/// table is touched: enough to finish it, or take it back out, without the
+/// request. Each table's actions are final, so rolling forward is only
+/// ever committing them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiTableIntent {
+    pub id: String,
+    /// Recorded in every part's CommitInfo, and what the record is stored
+    /// under -- the client's idempotency key if it gave one, otherwise
+    /// `id`.
+    pub idempotency_key: String,
+    pub tables: Vec<TableIntent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableIntent {
+    pub table_uri: String,
+    /// The version the part was checked against. Its commit can only land
+    /// after it, so that's where landed_version() starts looking, and what
+    /// delta-rs's conflict check runs from if it hasn't landed.
+    pub read_version: u64,
+    pub actions: Vec<Action>,
+    /// The files `actions` removes, as they were at `read_version` (see
+    /// removed_files()) -- what rolling the part back adds again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restore: Vec<Add>,
+}
+
+impl MultiTableIntent {
+    /// The commit metadata each part records -- see
+    /// MULTI_TABLE_COMMIT_ID_FIELD and idempotency::idempotency_metadata.
+    pub fn commit_metadata(&self) -> HashMap<String, Value> {
+        HashMap::from([
+            (
+                IDEMPOTENCY_KEY_FIELD.to_string(),
+                Value::String(self.idempotency_key.clone()),
+            ),
+            (
+                MULTI_TABLE_COMMIT_ID_FIELD.to_string(),
+                Value::String(self.id.clone()),
+            ),
+        ])
+    }
+
+    /// The commit metadata the commit rolling a part back records -- see
+    /// MULTI_TABLE_ROLLBACK_FIELD.
+    pub fn rollback_metadata(&self) -> HashMap<String, Value> {
+        HashMap::from([(
+            MULTI_TABLE_ROLLBACK_FIELD.to_string(),
+            Value::String(self.id.clone()),
+        )])
+    }
+
+    /// The SetTransaction each part, and each commit rolling one back,
+    /// carries. delta-rs fails a commit whose app_id landed since its read
+    /// version, so only one server's commit of either can land, however
+    /// long another stalled holding a lapsed lease.
+    ///
+    /// Like any SetTransaction the app_id stays in the table's snapshot
+    /// until `delta.setTransactionRetentionDuration` expires it.
+    pub fn fence(&self, lease: &Lease) -> Action {
+        Action::Txn(Transaction {
+            app_id: format!("multiTableCommit-{}", self.id),
+            version: lease.generation as i64,
+            last_updated: Some(now_millis() as i64),
+        })
+    }
+}
+
+impl TableIntent {
+    /// The actions that take this part, landed as `landed`, back out of
+    /// `table` as of its latest snapshot: a Remove for each file it added
+    /// that's still there, and each file it removed added back. What a
+    /// later commit made of the part's files (compacted them, say) stays.
+    pub fn undo_actions(
+        &self,
+        table: &DeltaTable,
+        landed: &[Action],
+    ) -> Result<Vec<Action>, DeltaTxnError> {
+        let added: HashSet<&str> = landed
+            .iter()
+            .filter_map(|action| match action {
+                Action::Add(add) => Some(add.path.as_str()),
+                _ => None,
+            })
+            .collect();
+        let mut actions = Vec::with_capacity(added.len() + self.restore.len());
+        if !added.is_empty() {
+            let snapshot = table
+                .snapshot()
+                .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+            let log_data = snapshot
+                .snapshot()
+                .try_log_data()
+                .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+            actions.extend(
+                log_data
+                    .iter()
+                    .filter(|file| added.contains(file.path().as_ref()))
+                    .map(|file| Action::Remove(file.remove_action(true))),
+            );
+        }
+        actions.extend(self.restore.iter().cloned().map(Action::Add));
+        Ok(actions)
+    }
+}
+
+/// The files `actions` removes, read from `table`'s snapshot as Adds --
+/// TableIntent::restore. A Remove of a file the snapshot doesn't have
+/// removes nothing, so there's nothing to restore for it. Row IDs are
+/// left unset, to be assigned afresh like any other add's.
+pub fn removed_files(table: &DeltaTable, actions: &[Action]) -> Result<Vec<Add>, DeltaTxnError> {
+    let removed: HashSet<&str> = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Remove(remove) => Some(remove.path.as_str()),
+            _ => None,
+        })
+        .collect();
+    if removed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let snapshot = table
+        .snapshot()
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+    let log_data = snapshot
+        .snapshot()
+        .try_log_data()
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+    Ok(log_data
+        .iter()
+        .filter(|file| removed.contains(file.path().as_ref()))
+        .map(|file| {
+            let remove = file.remove_action(true);
+            Add {
+                path: remove.path,
+                partition_values: remove.partition_values.unwrap_or_default(),
+                size: file.size(),
+                modification_time: file.modification_time(),
+                data_change: true,
+                stats: file.stats(),
+                tags: None,
+                deletion_vector: remove.deletion_vector,
+                base_row_id: None,
+                default_row_commit_version: None,
+                clustering_provider: None,
+            }
+        })
+        .collect())
+}
+
+/// The version multi-table commit `id`'s part landed at on `table_url`,
+/// from the commits after `read_version`; `None` if it hasn't.
+pub async fn landed_version(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    id: &str,
+    read_version: u64,
+) -> Result<Option<u64>, DeltaTxnError> {
+    find_commit(
+        table_url,
+        storage_options,
+        MULTI_TABLE_COMMIT_ID_FIELD,
+        id,
+        read_version,
+    )
+    .await
+}
+
+/// The version the commit rolling back multi-table commit `id`'s part,
+/// landed at `landed`, itself landed at on `table_url`; `None` if the part
+/// hasn't been rolled back.
+pub async fn undone_version(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    id: &str,
+    landed: u64,
+) -> Result<Option<u64>, DeltaTxnError> {
+    find_commit(
+        table_url,
+        storage_options,
+        MULTI_TABLE_ROLLBACK_FIELD,
+        id,
+        landed,
+    )
+    .await
+}
+
+// The first commit after `after` whose commitInfo has `field` set to `id`.
+async fn find_commit(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    field: &str,
+    id: &str,
+    after: u64,
+) -> Result<Option<u64>, DeltaTxnError> {
+    let log = TableLog::open_from(table_url, storage_options, after + 1).await?;
+    for version in log.commit_versions(after + 1..=u64::MAX) {
+        let Some(entry) = log.read_commit(version).await? else {
+            continue;
+        };
+        let commit_id = entry
+            .commit_info
+            .as_ref()
+            .and_then(|info: &CommitInfo| info.info.get(field))
+            .and_then(Value::as_str);
+        if commit_id == Some(id) {
+            return Ok(Some(version));
+        }
+    }
+    Ok(None)
+}
+
+/// The actions multi-table commit `id`'s part committed at `version` on
+/// `table_url` -- what undo_actions() takes back out.
+pub async fn landed_actions(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    id: &str,
+    version: u64,
+) -> Result<Vec<Action>, DeltaTxnError> {
+    let log = TableLog::open_from(table_url, storage_options, version).await?;
+    log.read_actions(version)
+        .await?
+        .map(|commit| commit.actions)
+        .ok_or_else(|| {
+            coordinator_error(format!(
+                "multi-table commit {id}'s part at version {version} of '{table_url}' is no \
+                 longer in the log"
+            ))
+        })
+}
+
+/// The coordinator log at DELTA_TXN_COORDINATOR_URI, shared by every
+/// replica: `commits/<key>/intent.json`, `commits/<key>/failed.json` once
+/// marked failed, `commits/<key>/rolled_back.json` once every landed part
+/// is taken back out, and `commits/<key>/leases/<generation>.json`, one
+/// record per commit. Keys are hex-encoded path segments. Only
+/// MultiTableCommit and recovery read it; table reads never do.
+pub struct CoordinatorLog {
+    store: Arc<dyn ObjectStore>,
+}
+
+/// A claim on one record, from CoordinatorLog::record or claim.
+#[derive(Debug)]
+pub struct Lease {
+    key: String,
+    generation: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LeaseRecord {
+    /// Milliseconds since the Unix epoch.
+    expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Failure {
+    reason: String,
+}
+
+fn coordinator_error(e: impl std::fmt::Display) -> DeltaTxnError {
+    DeltaTxnError::CoordinatorFailed(e.to_string())
+}
+
+// One path segment for any string, and back -- keys contain characters
+// object stores treat specially.
+fn hex(s: &str) -> String {
+    s.bytes().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(s: &str) -> Option<String> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    let bytes = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn commit_dir(key: &str) -> Path {
+    Path::from(format!("commits/{}", hex(key)))
+}
+
+fn commit_file(key: &str, name: &str) -> Path {
+    commit_dir(key).join(name)
+}
+
+fn lease_dir(key: &str) -> Path {
+    commit_dir(key).join("leases")
+}
+
+fn lease_path(key: &str, generation: u64) -> Path {
+    lease_dir(key).join(format!("{generation}.json"))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+impl CoordinatorLog {
+    /// Nothing is read or created here.
+    pub fn open(
+        coordinator_url: &Url,
+        storage_options: HashMap<String, String>,
+    ) -> Result<Self, DeltaTxnError> {
+        let store = build_log_store(coordinator_url, storage_options)
+            .map_err(coordinator_error)?
+            .object_store(None);
+        Ok(Self { store })
+    }
+
+    /// Records `intent`, holding its lease for `ttl`. `None` means another
+    /// server recorded the same key first.
+    pub async fn record(
+        &self,
+        intent: &MultiTableIntent,
+        ttl: Duration,
+    ) -> Result<Option<Lease>, DeltaTxnError> {
+        let lease = Lease {
+            key: intent.idempotency_key.clone(),
+            generation: 0,
+        };
+        if !self.create_lease(&lease, ttl).await? {
+            return Ok(None);
+        }
+        let bytes = serde_json::to_vec(intent).map_err(coordinator_error)?;
+        match self
+            .create(&commit_file(&intent.idempotency_key, "intent.json"), bytes)
+            .await
+        {
+            Ok(()) => Ok(Some(lease)),
+            // Left by a record whose leases were cleared but not its intent
+            // (see complete()) -- not this commit's to overwrite.
+            Err(ObjectStoreError::AlreadyExists { .. }) => Ok(None),
+            Err(e) => Err(coordinator_error(e)),
+        }
+    }
+
+    /// The record stored under idempotency key `key`, if any: one GET.
+    pub async fn get(&self, key: &str) -> Result<Option<MultiTableIntent>, DeltaTxnError> {
+        self.read(&commit_file(key, "intent.json")).await
+    }
+
+    /// Marks `key`'s record failed for `reason`: a part was refused in a
+    /// way committing it again can't fix after others had landed. A failed
+    /// record is rolled back rather than forward -- every part that landed
+    /// is taken back out -- and then marked so by mark_rolled_back(). The
+    /// first reason recorded stands.
+    pub async fn mark_failed(&self, key: &str, reason: &str) -> Result<(), DeltaTxnError> {
+        let failure = Failure {
+            reason: reason.to_string(),
+        };
+        let bytes = serde_json::to_vec(&failure).map_err(coordinator_error)?;
+        match self.create(&commit_file(key, "failed.json"), bytes).await {
+            Ok(()) | Err(ObjectStoreError::AlreadyExists { .. }) => Ok(()),
+            Err(e) => Err(coordinator_error(e)),
+        }
+    }
+
+    /// Why `key`'s record was marked failed, if it was.
+    pub async fn failure(&self, key: &str) -> Result<Option<String>, DeltaTxnError> {
+        Ok(self
+            .read::<Failure>(&commit_file(key, "failed.json"))
+            .await?
+            .map(|failure| failure.reason))
+    }
+
+    /// Marks `key`'s failed record rolled back: nothing of it is left in
+    /// any table. The record itself stays, so a retry with the same key is
+    /// answered with the failure rather than committed afresh; an operator
+    /// may delete it once no such retry can still come.
+    pub async fn mark_rolled_back(&self, key: &str) -> Result<(), DeltaTxnError> {
+        match self
+            .create(&commit_file(key, "rolled_back.json"), b"{}".to_vec())
+            .await
+        {
+            Ok(()) | Err(ObjectStoreError::AlreadyExists { .. }) => Ok(()),
+            Err(e) => Err(coordinator_error(e)),
+        }
+    }
+
+    /// Whether `key`'s record has been marked rolled back.
+    pub async fn is_rolled_back(&self, key: &str) -> Result<bool, DeltaTxnError> {
+        Ok(self
+            .read::<Value>(&commit_file(key, "rolled_back.json"))
+            .await?
+            .is_some())
+    }
+
+    /// Takes the lease on record `key` if nobody holds it -- it was never
+    /// leased, or the latest generation has expired. `None` means another
+    /// server holds it, or won the race to take it.
+    pub async fn claim(&self, key: &str, ttl: Duration) -> Result<Option<Lease>, DeltaTxnError> {
+        let generation = match self.latest_lease(key).await? {
+            Some((_, lease)) if lease.expires_at > now_millis() => return Ok(None),
+            Some((generation, _)) => generation + 1,
+            None => 0,
+        };
+        let lease = Lease {
+            key: key.to_string(),
+            generation,
+        };
+        Ok(self.create_lease(&lease, ttl).await?.then_some(lease))
+    }
+
+    /// Extends `lease` by `ttl` from now, as long as it's still the latest
+    /// generation -- `false` means it expired and another server has taken
+    /// the record over, so the caller must stop applying it.
+    pub async fn renew(&self, lease: &mut Lease, ttl: Duration) -> Result<bool, DeltaTxnError> {
+        match self.latest_lease(&lease.key).await? {
+            Some((generation, _)) if generation == lease.generation => {}
+            _ => return Ok(false),
+        }
+        let next = Lease {
+            key: lease.key.clone(),
+            generation: lease.generation + 1,
+        };
+        if !self.create_lease(&next, ttl).await? {
+            return Ok(false);
+        }
+        *lease = next;
+        Ok(true)
+    }
+
+    async fn latest_lease(&self, key: &str) -> Result<Option<(u64, LeaseRecord)>, DeltaTxnError> {
+        let mut latest = None;
+        let mut entries = self.store.list(Some(&lease_dir(key)));
+        while let Some(entry) = entries.next().await {
+            let meta = entry.map_err(coordinator_error)?;
+            let generation = meta
+                .location
+                .filename()
+                .and_then(|name| name.strip_suffix(".json"))
+                .and_then(|generation| generation.parse::<u64>().ok());
+            if let Some(generation) = generation {
+                latest = latest.max(Some(generation));
+            }
+        }
+        let Some(generation) = latest else {
+            return Ok(None);
+        };
+        // `None`: the record was completed, and its leases removed, since
+        // the listing.
+        let lease = self.read(&lease_path(key, generation)).await?;
+        Ok(lease.map(|lease| (generation, lease)))
+    }
+
+    /// `false` if that generation already exists: someone else got it.
+    async fn create_lease(&self, lease: &Lease, ttl: Duration) -> Result<bool, DeltaTxnError> {
+        let record = LeaseRecord {
+            expires_at: now_millis() + ttl.as_millis() as u64,
+        };
+        let bytes = serde_json::to_vec(&record).map_err(coordinator_error)?;
+        match self
+            .create(&lease_path(&lease.key, lease.generation), bytes)
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(ObjectStoreError::AlreadyExists { .. }) => Ok(false),
+            Err(e) => Err(coordinator_error(e)),
+        }
+    }
+
+    async fn create(&self, path: &Path, bytes: Vec<u8>) -> Result<(), ObjectStoreError> {
+        self.store
+            .put_opts(
+                path,
+                PutPayload::from(bytes),
+                PutOptions {
+                    mode: PutMode::Create,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn read<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &Path,
+    ) -> Result<Option<T>, DeltaTxnError> {
+        let bytes = match self.store.get(path).await {
+            Ok(result) => result.bytes().await.map_err(coordinator_error)?,
+            Err(ObjectStoreError::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(coordinator_error(e)),
+        };
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| coordinator_error(format!("malformed {path}: {e}")))
+    }
+
+    /// The idempotency keys of every record still in the log, from the
+    /// `intent.json`s under `commits/`.
+    pub async fn pending(&self) -> Result<Vec<String>, DeltaTxnError> {
+        let mut keys = Vec::new();
+        let mut entries = self.store.list(Some(&Path::from("commits")));
+        while let Some(entry) = entries.next().await {
+            let location = entry.map_err(coordinator_error)?.location;
+            let parts: Vec<_> = location.parts().collect();
+            let [_, dir, file] = parts.as_slice() else {
+                continue;
+            };
+            if file.as_ref() != "intent.json" {
+                continue;
+            }
+            match unhex(dir.as_ref()) {
+                Some(key) => keys.push(key),
+                None => warn!(%location, "skipping a coordinator log entry that isn't a record"),
+            }
+        }
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    /// Removes `key`'s record once every part has landed, or once its
+    /// first part was refused before anything landed: leases first, intent
+    /// last so pending() lists the record until it's gone.
+    pub async fn complete(&self, key: &str) -> Result<(), DeltaTxnError> {
+        let mut leases = Vec::new();
+        let mut entries = self.store.list(Some(&lease_dir(key)));
+        while let Some(entry) = entries.next().await {
+            leases.push(entry.map_err(coordinator_error)?.location);
+        }
+        for lease in leases {
+            self.delete(&lease).await?;
+        }
+        self.delete(&commit_file(key, "intent.json")).await
+    }
+
+    async fn delete(&self, path: &Path) -> Result<(), DeltaTxnError> {
+        match self.store.delete(path).await {
+            Ok(()) | Err(ObjectStoreError::NotFound { .. }) => Ok(()),
+            Err(e) => Err(coordinator_error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake::kernel::Remove;
+
+    fn intent(id: &str, key: &str) -> MultiTableIntent {
+        MultiTableIntent {
+            id: id.to_string(),
+            idempotency_key: key.to_string(),
+            tables: vec![TableIntent {
+                table_uri: "file:///tables/facts/".to_string(),
+                read_version: 4,
+                actions: vec![Action::Remove(Remove {
+                    path: "part-0.parquet".to_string(),
+                    data_change: true,
+                    ..Default::default()
+                })],
+                restore: Vec::new(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn records_are_found_by_key_until_completed() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = Url::from_directory_path(dir.path()).unwrap();
+        let log = CoordinatorLog::open(&url, HashMap::new()).unwrap();
+        assert!(log.pending().await.unwrap().is_empty());
+
+        // Any string makes a key, slashes and all.
+        let intent = intent("mtc-1", "loads/2026-10-18");
+        assert!(log.record(&intent, LEASE_TTL).await.unwrap().is_some());
+        assert!(
+            log.record(&intent, LEASE_TTL).await.unwrap().is_none(),
+            "keys are create-only"
+        );
+        assert_eq!(
+            log.get("loads/2026-10-18").await.unwrap(),
+            Some(intent.clone())
+        );
+        assert_eq!(log.get("loads").await.unwrap(), None);
+        assert_eq!(log.pending().await.unwrap(), ["loads/2026-10-18"]);
+
+        log.complete("loads/2026-10-18").await.unwrap();
+        log.complete("loads/2026-10-18").await.unwrap();
+        assert!(log.pending().await.unwrap().is_empty());
+        assert_eq!(log.get("loads/2026-10-18").await.unwrap(), None);
+        assert_eq!(
+            intent.commit_metadata()[MULTI_TABLE_COMMIT_ID_FIELD],
+            "mtc-1"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_failed_record_keeps_its_first_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = Url::from_directory_path(dir.path()).unwrap();
+        let log = CoordinatorLog::open(&url, HashMap::new()).unwrap();
+        log.record(&intent("mtc-3", "mtc-3"), LEASE_TTL)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(log.failure("mtc-3").await.unwrap(), None);
+
+        log.mark_failed("mtc-3", "dims conflicted").await.unwrap();
+        log.mark_failed("mtc-3", "facts conflicted").await.unwrap();
+        assert_eq!(
+            log.failure("mtc-3").await.unwrap().as_deref(),
+            Some("dims conflicted")
+        );
+        assert!(!log.is_rolled_back("mtc-3").await.unwrap());
+        log.mark_rolled_back("mtc-3").await.unwrap();
+        log.mark_rolled_back("mtc-3").await.unwrap();
+        assert!(log.is_rolled_back("mtc-3").await.unwrap());
+        // Still pending: only an operator removes a failed record.
+        assert_eq!(log.pending().await.unwrap(), ["mtc-3"]);
+    }
+
+    #[tokio::test]
+    async fn a_lease_is_taken_over_only_once_it_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = Url::from_directory_path(dir.path()).unwrap();
+        let log = CoordinatorLog::open(&url, HashMap::new()).unwrap();
+        let intent = MultiTableIntent {
+            tables: Vec::new(),
+            ..intent("mtc-2", "mtc-2")
+        };
+
+        // Held by whoever recorded it, and renewable while it's theirs.
+        let mut recorder = log.record(&intent, LEASE_TTL).await.unwrap().unwrap();
+        assert!(log.claim("mtc-2", LEASE_TTL).await.unwrap().is_none());
+        assert!(log.renew(&mut recorder, Duration::ZERO).await.unwrap());
+
+        // Expired: exactly one server takes it over, and the old holder
+        // finds out at its next renewal -- its fence a generation behind.
+        let mut taker = log.claim("mtc-2", LEASE_TTL).await.unwrap().unwrap();
+        assert!(log.claim("mtc-2", LEASE_TTL).await.unwrap().is_none());
+        assert!(!log.renew(&mut recorder, LEASE_TTL).await.unwrap());
+        assert!(log.renew(&mut taker, LEASE_TTL).await.unwrap());
+        let generation = |lease: &Lease| match intent.fence(lease) {
+            Action::Txn(txn) => txn.version,
+            _ => unreachable!(),
+        };
+        assert!(generation(&recorder) < generation(&taker));
+
+        // Completing clears the record and its leases together.
+        log.complete("mtc-2").await.unwrap();
+        assert!(log.pending().await.unwrap().is_empty());
+        assert!(log.latest_lease("mtc-2").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn keys_round_trip_through_their_path_segment() {
+        for key in ["mtc-1", "loads/2026-10-18", "s3://bucket/t/", "ünïcode"] {
+            assert_eq!(unhex(&hex(key)).as_deref(), Some(key));
+        }
+        assert_eq!(unhex("abc"), None);
+        assert_eq!(unhex("zz"), None);
+    }
+}
@@ -0,0 +1,335 @@
+use delta_kernel::expressions::{Scalar, StructData};
+use deltalake::kernel::{DataType, StructType};
+use std::cmp::Ordering;
+
+use super::errors::DeltaTxnError;
+
+/// A filter on one partition column, as the client sent it: values are
+/// still raw strings in Delta's partition-value serialization (the same
+/// form `AddFile.partition_values` carries), since what they mean depends
+/// on the column's type, which only the table's schema knows -- see
+/// `PartitionFilters::bind`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionFilter {
+    pub column: String,
+    pub predicate: PartitionPredicate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartitionPredicate {
+    Equals(String),
+    In(Vec<String>),
+    /// At least one bound is always set.
+    Range {
+        lower: Option<RangeBound>,
+        upper: Option<RangeBound>,
+    },
+    /// `true` matches only null partition values, `false` only non-null
+    /// ones.
+    IsNull(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeBound {
+    pub value: String,
+    pub inclusive: bool,
+}
+
+/// A set of partition filters bound to a table's schema, ready to test
+/// files against: every value parsed into the column's own type, so
+/// comparisons are typed (`9 < 10` for an integer column, chronological
+/// for a date) rather than string comparisons of the serialized form.
+/// A file matches only if it matches every filter.
+///
+/// Comparisons follow SQL NULL semantics (kernel `Scalar::logical_partial_cmp`):
+/// a null partition value never satisfies an equality, IN or range filter,
+/// only `IsNull(true)`.
+#[derive(Debug, Clone, Default)]
+pub struct PartitionFilters {
+    filters: Vec<BoundFilter>,
+}
+
+#[derive(Debug, Clone)]
+struct BoundFilter {
+    column: String,
+    predicate: BoundPredicate,
+}
+
+#[derive(Debug, Clone)]
+enum BoundPredicate {
+    // Equals is just a one-value In once bound.
+    In(Vec<Scalar>),
+    Range {
+        lower: Option<(Scalar, bool)>,
+        upper: Option<(Scalar, bool)>,
+    },
+    IsNull(bool),
+}
+
+impl PartitionFilters {
+    /// Checks each filter against `schema`/`partition_columns` and parses
+    /// its values: a column that doesn't exist, isn't a partition column,
+    /// or a value that doesn't parse as the column's type is
+    /// `DeltaTxnError::InvalidPartitionFilter`. Column names match exactly
+    /// (case-sensitively), as they appear in the table's metadata.
+    pub fn bind(
+        filters: Vec<PartitionFilter>,
+        schema: &StructType,
+        partition_columns: &[String],
+    ) -> Result<Self, DeltaTxnError> {
+        let filters = filters
+            .into_iter()
+            .map(|filter| {
+                if !partition_columns.contains(&filter.column) {
+                    let reason = if schema.field(&filter.column).is_some() {
+                        "is not a partition column"
+                    } else {
+                        "does not exist"
+                    };
+                    return Err(DeltaTxnError::InvalidPartitionFilter(format!(
+                        "column '{}' {reason}",
+                        filter.column
+                    )));
+                }
+                let data_type = schema
+                    .field(&filter.column)
+                    .map(|field| field.data_type())
+                    .ok_or_else(|| {
+                        DeltaTxnError::InvalidPartitionFilter(format!(
+                            "partition column '{}' is missing from the table schema",
+                            filter.column
+                        ))
+                    })?;
+                let parse = |raw: &str| parse_value(&filter.column, data_type, raw);
+
+                let predicate = match filter.predicate {
+                    PartitionPredicate::Equals(value) => BoundPredicate::In(vec![parse(&value)?]),
+                    PartitionPredicate::In(values) => BoundPredicate::In(
+                        values.iter().map(|v| parse(v)).collect::<Result<_, _>>()?,
+                    ),
+                    PartitionPredicate::Range { lower, upper } => {
+                        let bound = |bound: Option<RangeBound>| {
+                            bound
+                                .map(|b| parse(&b.value).map(|value| (value, b.inclusive)))
+                                .transpose()
+                        };
+                        BoundPredicate::Range {
+                            lower: bound(lower)?,
+                            upper: bound(upper)?,
+                        }
+                    }
+                    PartitionPredicate::IsNull(is_null) => BoundPredicate::IsNull(is_null),
+                };
+                Ok(BoundFilter {
+                    column: filter.column,
+                    predicate,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { filters })
+    }
+
+    /// Whether a file with these (parsed) partition values passes every
+    /// filter. `None` -- a file with no partition values at all -- and a
+    /// column absent from `partition_values` both count as null.
+    pub fn matches(&self, partition_values: Option<&StructData>) -> bool {
+        self.filters.iter().all(|filter| {
+            let value = partition_values.and_then(|data| {
+                data.fields()
+                    .iter()
+                    .position(|field| field.name() == &filter.column)
+                    .map(|index| &data.values()[index])
+            });
+            filter.predicate.matches(value)
+        })
+    }
+}
+
+impl BoundPredicate {
+    fn matches(&self, value: Option<&Scalar>) -> bool {
+        let value = value.filter(|v| !v.is_null());
+        match self {
+            BoundPredicate::IsNull(is_null) => value.is_none() == *is_null,
+            BoundPredicate::In(candidates) => {
+                value.is_some_and(|value| candidates.iter().any(|c| value.logical_eq(c)))
+            }
+            BoundPredicate::Range { lower, upper } => value.is_some_and(|value| {
+                let above_lower = lower.as_ref().is_none_or(|(bound, inclusive)| {
+                    match value.logical_partial_cmp(bound) {
+                        Some(Ordering::Greater) => true,
+                        Some(Ordering::Equal) => *inclusive,
+                        _ => false,
+                    }
+                });
+                let below_upper = upper.as_ref().is_none_or(|(bound, inclusive)| {
+                    match value.logical_partial_cmp(bound) {
+                        Some(Ordering::Less) => true,
+                        Some(Ordering::Equal) => *inclusive,
+                        _ => false,
+                    }
+                });
+                above_lower && below_upper
+            }),
+        }
+    }
+}
+
+// Parses one filter value with the kernel's own partition-value parser --
+// the same one that turns the log's serialized partitionValues into the
+// typed values `matches` compares against, so a value written exactly the
+// way AddFile.partition_values reports it always compares equal. An empty
+// string would parse as null (Delta's own encoding of a null partition
+// value), which no comparison matches; rejected instead so a client asking
+// for nulls uses IsNull and gets what it meant.
+fn parse_value(column: &str, data_type: &DataType, raw: &str) -> Result<Scalar, DeltaTxnError> {
+    if raw.is_empty() {
+        return Err(DeltaTxnError::InvalidPartitionFilter(format!(
+            "empty value for column '{column}' -- use is_null to match null partition values"
+        )));
+    }
+    let DataType::Primitive(primitive) = data_type else {
+        return Err(DeltaTxnError::InvalidPartitionFilter(format!(
+            "partition column '{column}' has non-primitive type {data_type}"
+        )));
+    };
+    primitive.parse_scalar(raw).map_err(|_| {
+        DeltaTxnError::InvalidPartitionFilter(format!(
+            "'{raw}' is not a valid {primitive} value for column '{column}'"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake::kernel::{PrimitiveType, StructField};
+
+    fn schema() -> StructType {
+        StructType::try_new(vec![
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("day", DataType::DATE),
+            StructField::nullable("hour", DataType::INTEGER),
+            StructField::nullable("region", DataType::STRING),
+        ])
+        .unwrap()
+    }
+
+    fn partition_columns() -> Vec<String> {
+        vec!["day".to_string(), "hour".to_string(), "region".to_string()]
+    }
+
+    fn bind(filters: Vec<PartitionFilter>) -> Result<PartitionFilters, DeltaTxnError> {
+        PartitionFilters::bind(filters, &schema(), &partition_columns())
+    }
+
+    fn filter(column: &str, predicate: PartitionPredicate) -> PartitionFilter {
+        PartitionFilter {
+            column: column.to_string(),
+            predicate,
+        }
+    }
+
+    fn values(day: &str, hour: Option<i32>, region: Option<&str>) -> StructData {
+        let date = PrimitiveType::Date.parse_scalar(day).unwrap();
+        StructData::try_new(
+            vec![
+                StructField::nullable("day", DataType::DATE),
+                StructField::nullable("hour", DataType::INTEGER),
+                StructField::nullable("region", DataType::STRING),
+            ],
+            vec![
+                date,
+                hour.map_or(Scalar::Null(DataType::INTEGER), Scalar::Integer),
+                region.map_or(Scalar::Null(DataType::STRING), |r| {
+                    Scalar::String(r.to_string())
+                }),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn range_comparisons_use_the_columns_type_not_the_string_form() {
+        let filters = bind(vec![filter(
+            "hour",
+            PartitionPredicate::Range {
+                lower: Some(RangeBound {
+                    value: "9".to_string(),
+                    inclusive: false,
+                }),
+                upper: Some(RangeBound {
+                    value: "12".to_string(),
+                    inclusive: true,
+                }),
+            },
+        )])
+        .unwrap();
+
+        // As strings, "10" < "9" and "12" < "9" -- typed, both are in range.
+        assert!(filters.matches(Some(&values("2026-10-17", Some(10), None))));
+        assert!(filters.matches(Some(&values("2026-10-17", Some(12), None))));
+        assert!(!filters.matches(Some(&values("2026-10-17", Some(9), None))));
+        assert!(!filters.matches(Some(&values("2026-10-17", Some(13), None))));
+    }
+
+    #[test]
+    fn every_filter_must_match() {
+        let filters = bind(vec![
+            filter("day", PartitionPredicate::Equals("2026-10-17".to_string())),
+            filter(
+                "region",
+                PartitionPredicate::In(vec!["eu".to_string(), "us".to_string()]),
+            ),
+        ])
+        .unwrap();
+
+        assert!(filters.matches(Some(&values("2026-10-17", Some(1), Some("us")))));
+        assert!(!filters.matches(Some(&values("2026-10-17", Some(1), Some("apac")))));
+        assert!(!filters.matches(Some(&values("2026-10-16", Some(1), Some("us")))));
+    }
+
+    #[test]
+    fn nulls_match_only_is_null() {
+        let row = values("2026-10-17", None, None);
+        let is_null = bind(vec![filter("region", PartitionPredicate::IsNull(true))]).unwrap();
+        let not_null = bind(vec![filter("region", PartitionPredicate::IsNull(false))]).unwrap();
+        let in_list = bind(vec![filter(
+            "region",
+            PartitionPredicate::In(vec!["us".to_string()]),
+        )])
+        .unwrap();
+
+        assert!(is_null.matches(Some(&row)));
+        assert!(!not_null.matches(Some(&row)));
+        assert!(!in_list.matches(Some(&row)));
+        assert!(
+            is_null.matches(None),
+            "a file with no partition values at all counts as null"
+        );
+    }
+
+    #[test]
+    fn binding_rejects_unknown_non_partition_and_unparsable_filters() {
+        for (bad, expected) in [
+            (
+                filter("nope", PartitionPredicate::IsNull(true)),
+                "does not exist",
+            ),
+            (
+                filter("id", PartitionPredicate::Equals("1".to_string())),
+                "is not a partition column",
+            ),
+            (
+                filter("hour", PartitionPredicate::Equals("noon".to_string())),
+                "not a valid",
+            ),
+            (
+                filter("day", PartitionPredicate::Equals(String::new())),
+                "use is_null",
+            ),
+        ] {
+            let err = bind(vec![bad.clone()]).expect_err("filter should be rejected");
+            assert!(err.to_string().contains(expected), "{bad:?}: {err}");
+        }
+    }
+}
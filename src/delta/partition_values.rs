@@ -0,0 +1,194 @@
+use deltalake::kernel::{Action, DataType, StructType};
+
+use super::errors::DeltaTxnError;
+
+/// One problem with one AddFile's partition values. A file can have
+/// several (a missing column and a misspelled one, say); each is reported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionValueViolation {
+    /// Where in the CommitRequest, e.g. `actions[2].add.partition_values.region`
+    /// -- the BadRequest field path grpc clients expect.
+    pub field: String,
+    /// Names the file, so the message alone says which add to fix.
+    pub description: String,
+}
+
+/// Checks every Add in `actions` against the table's partition columns:
+/// exactly those keys, no more and no fewer, each value parseable as its
+/// column's type. An empty string is Delta's encoding of a null partition
+/// value and is always accepted, as is a missing value in a key that is
+/// present.
+///
+/// delta-rs writes whatever map an Add carries, and readers prune
+/// partitions by these values without looking at the data -- a
+/// misspelled key or an unparseable value silently hides the file from
+/// every filtered read. All violations across all files are collected so
+/// a client can fix a batch in one round trip.
+pub fn check_add_partition_values(
+    actions: &[Action],
+    schema: &StructType,
+    partition_columns: &[String],
+) -> Result<(), DeltaTxnError> {
+    let mut violations = Vec::new();
+    for (index, action) in actions.iter().enumerate() {
+        let Action::Add(add) = action else {
+            continue;
+        };
+        let field = format!("actions[{index}].add.partition_values");
+
+        for column in partition_columns {
+            if !add.partition_values.contains_key(column) {
+                violations.push(PartitionValueViolation {
+                    field: field.clone(),
+                    description: format!(
+                        "'{}': missing a value for partition column '{column}'",
+                        add.path
+                    ),
+                });
+            }
+        }
+
+        let mut keys: Vec<_> = add.partition_values.iter().collect();
+        keys.sort_by_key(|(key, _)| key.as_str());
+        for (key, value) in keys {
+            if !partition_columns.contains(key) {
+                violations.push(PartitionValueViolation {
+                    field: format!("{field}.{key}"),
+                    description: if partition_columns.is_empty() {
+                        format!("'{}': the table is not partitioned", add.path)
+                    } else {
+                        format!(
+                            "'{}': '{key}' is not a partition column (expected {})",
+                            add.path,
+                            partition_columns.join(", ")
+                        )
+                    },
+                });
+                continue;
+            }
+            let Some(raw) = value.as_deref().filter(|raw| !raw.is_empty()) else {
+                continue;
+            };
+            // A partition column missing from the schema is the metadata's
+            // own inconsistency, for delta-rs to reject; nothing to parse
+            // against here.
+            let Some(column) = schema.field(key) else {
+                continue;
+            };
+            let parsed = match column.data_type() {
+                DataType::Primitive(primitive) => primitive.parse_scalar(raw).is_ok(),
+                _ => false,
+            };
+            if !parsed {
+                violations.push(PartitionValueViolation {
+                    field: format!("{field}.{key}"),
+                    description: format!(
+                        "'{}': '{raw}' is not a valid {} value for partition column '{key}'",
+                        add.path,
+                        column.data_type()
+                    ),
+                });
+            }
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(DeltaTxnError::InvalidPartitionValues(violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake::kernel::{Add, StructField};
+    use std::collections::HashMap;
+
+    fn schema() -> StructType {
+        StructType::try_new(vec![
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("day", DataType::DATE),
+            StructField::nullable("hour", DataType::INTEGER),
+        ])
+        .unwrap()
+    }
+
+    fn partition_columns() -> Vec<String> {
+        vec!["day".to_string(), "hour".to_string()]
+    }
+
+    fn add(path: &str, values: &[(&str, Option<&str>)]) -> Action {
+        Action::Add(Add {
+            path: path.to_string(),
+            partition_values: values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.map(str::to_string)))
+                .collect::<HashMap<_, _>>(),
+            ..Default::default()
+        })
+    }
+
+    fn violations(actions: &[Action]) -> Vec<PartitionValueViolation> {
+        match check_add_partition_values(actions, &schema(), &partition_columns()) {
+            Ok(()) => Vec::new(),
+            Err(DeltaTxnError::InvalidPartitionValues(violations)) => violations,
+            Err(other) => panic!("unexpected error {other:?}"),
+        }
+    }
+
+    #[test]
+    fn well_formed_and_null_values_pass() {
+        assert!(violations(&[
+            add(
+                "a.parquet",
+                &[("day", Some("2026-10-18")), ("hour", Some("9"))]
+            ),
+            add("b.parquet", &[("day", Some("")), ("hour", None)]),
+        ])
+        .is_empty());
+    }
+
+    #[test]
+    fn every_problem_in_every_file_is_reported() {
+        let found = violations(&[
+            add(
+                "ok.parquet",
+                &[("day", Some("2026-10-18")), ("hour", Some("9"))],
+            ),
+            add(
+                "bad.parquet",
+                &[("day", Some("yesterday")), ("huor", Some("9"))],
+            ),
+        ]);
+        let fields: Vec<_> = found.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "actions[1].add.partition_values",
+                "actions[1].add.partition_values.day",
+                "actions[1].add.partition_values.huor",
+            ]
+        );
+        assert!(found[0]
+            .description
+            .contains("missing a value for partition column 'hour'"));
+        assert!(found[1]
+            .description
+            .contains("'yesterday' is not a valid date"));
+        assert!(found[2].description.contains("'bad.parquet'"));
+    }
+
+    #[test]
+    fn an_unpartitioned_table_takes_no_partition_values() {
+        let result = check_add_partition_values(
+            &[add("a.parquet", &[("day", Some("2026-10-18"))])],
+            &schema(),
+            &[],
+        );
+        let Err(DeltaTxnError::InvalidPartitionValues(found)) = result else {
+            panic!("expected a violation, got {result:?}");
+        };
+        assert!(found[0].description.contains("not partitioned"));
+    }
+}
@@ -0,0 +1,346 @@
+use delta_kernel::table_features::TableFeature;
+use deltalake::kernel::{Action, Add, DomainMetadata, Protocol};
+use deltalake::logstore::get_actions;
+use deltalake::DeltaTable;
+
+use super::errors::DeltaTxnError;
+
+/// The system domain whose configuration records a row-tracking table's
+/// row-ID high-water mark (`{"rowIdHighWaterMark": N}`), per PROTOCOL.md's
+/// "Row Tracking".
+pub const ROW_TRACKING_DOMAIN: &str = "delta.rowTracking";
+
+/// Whether `protocol` lists the `rowTracking` writer feature -- the
+/// feature, not the `delta.enableRowTracking` property: once the feature
+/// is supported, every writer must assign row IDs, enabled or not.
+pub fn row_tracking_supported(protocol: &Protocol) -> bool {
+    protocol
+        .writer_features()
+        .is_some_and(|features| features.contains(&TableFeature::RowTracking))
+}
+
+/// The features this service writes itself rather than CommitBuilder:
+/// row IDs, and the system domain that records their high-water mark
+/// (see assign_row_ids). delta-rs 0.32's protocol checker lists neither,
+/// so commit::commit_actions hides them from it -- it would otherwise
+/// refuse every commit to a row-tracking table.
+pub const SELF_WRITTEN_FEATURES: &[TableFeature] =
+    &[TableFeature::RowTracking, TableFeature::DomainMetadata];
+
+/// The row tracking check for a commit whose actions will be governed by
+/// `protocol`. On a row-tracking table every Add needs its row count (see
+/// check_row_counts); on any other, an Add carrying a base_row_id or
+/// default_row_commit_version is rejected, since readers would ignore
+/// them -- the same reasoning as deletion_vectors::check_deletion_vectors.
+pub fn check_row_tracking(actions: &[Action], protocol: &Protocol) -> Result<(), DeltaTxnError> {
+    if row_tracking_supported(protocol) {
+        return check_row_counts(actions);
+    }
+    match actions.iter().find_map(|action| match action {
+        Action::Add(add)
+            if add.base_row_id.is_some() || add.default_row_commit_version.is_some() =>
+        {
+            Some(&add.path)
+        }
+        _ => None,
+    }) {
+        Some(path) => Err(DeltaTxnError::TableFeatureRequired {
+            feature: TableFeature::RowTracking.to_string(),
+            reason: format!("'{path}' carries row tracking fields"),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// How many of a table's newest commits row_id_high_water_mark reads for
+/// the mark before falling back to replaying the whole log.
+const HIGH_WATER_MARK_LOOKBACK: u64 = 20;
+
+/// The table's row-ID high-water mark as of `table`'s snapshot, `None` if
+/// it has never assigned one. Every commit adding files to a row-tracking
+/// table records the mark, so it's looked for in the snapshot's newest
+/// commits, newest first. Only a table that hasn't added files within
+/// HIGH_WATER_MARK_LOOKBACK commits replays its log through a kernel
+/// snapshot: delta-rs's own `domain_metadata` refuses `delta.*` system
+/// domains, and its snapshot doesn't hand out the kernel one underneath.
+pub async fn row_id_high_water_mark(table: &DeltaTable) -> Result<Option<i64>, DeltaTxnError> {
+    let version = table
+        .version()
+        .ok_or_else(|| DeltaTxnError::OpenFailed("table not initialized".to_string()))?;
+    let log_store = table.log_store();
+    let oldest = version.saturating_sub(HIGH_WATER_MARK_LOOKBACK - 1);
+    for version in (oldest..=version).rev() {
+        let Some(bytes) = log_store
+            .read_commit_entry(version)
+            .await
+            .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?
+        else {
+            // Cleaned up by log retention; a checkpoint has the mark.
+            break;
+        };
+        let actions =
+            get_actions(version, &bytes).map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+        if let Some(domain) = actions.into_iter().find_map(|action| match action {
+            Action::DomainMetadata(dm) if dm.domain == ROW_TRACKING_DOMAIN => Some(dm),
+            _ => None,
+        }) {
+            if domain.removed {
+                return Ok(None);
+            }
+            return parse_high_water_mark(&domain.configuration).map(Some);
+        }
+        if version == 0 {
+            return Ok(None);
+        }
+    }
+
+    let engine = log_store.engine(None);
+    let table_url = table.table_url().clone();
+    let configuration = tokio::task::spawn_blocking(move || {
+        delta_kernel::Snapshot::builder_for(table_url)
+            .at_version(version)
+            .build(engine.as_ref())
+            .and_then(|snapshot| {
+                snapshot.get_domain_metadata_internal(ROW_TRACKING_DOMAIN, engine.as_ref())
+            })
+    })
+    .await
+    .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?
+    .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+    configuration
+        .as_deref()
+        .map(parse_high_water_mark)
+        .transpose()
+}
+
+fn parse_high_water_mark(configuration: &str) -> Result<i64, DeltaTxnError> {
+    serde_json::from_str::<serde_json::Value>(configuration)
+        .ok()
+        .and_then(|value| value.get("rowIdHighWaterMark")?.as_i64())
+        .ok_or_else(|| {
+            DeltaTxnError::OpenFailed(format!(
+                "malformed {ROW_TRACKING_DOMAIN} domain metadata: {configuration}"
+            ))
+        })
+}
+
+/// Rejects an Add to a row-tracking table whose stats don't record
+/// numRecords: its row IDs, client-assigned or not, can't be accounted
+/// for in the high-water mark without it (see assign_row_ids). Run when a
+/// commit is checked, well before assign_row_ids runs at commit time.
+fn check_row_counts(actions: &[Action]) -> Result<(), DeltaTxnError> {
+    for action in actions {
+        if let Action::Add(add) = action {
+            row_count(add)?;
+        }
+    }
+    Ok(())
+}
+
+fn row_count(add: &Add) -> Result<i64, DeltaTxnError> {
+    add.stats
+        .as_deref()
+        .and_then(|stats| serde_json::from_str::<serde_json::Value>(stats).ok())
+        .and_then(|stats| stats.get("numRecords")?.as_i64())
+        .ok_or_else(|| DeltaTxnError::MissingRowCount {
+            path: add.path.clone(),
+        })
+}
+
+/// Fills in what the spec requires of a commit to a row-tracking table,
+/// where the client left it out: each Add's base_row_id, handed out
+/// consecutively from just past `high_water_mark` by the file's row count,
+/// and its default_row_commit_version, `commit_version`. Client-assigned
+/// IDs are kept and only raise the mark -- by their file's row count too,
+/// so every Add's stats must record numRecords either way: guessing at a
+/// client-assigned file's size would under-report the mark and hand the
+/// same IDs out again on a later commit. Appends the `delta.rowTracking` domain
+/// metadata recording the new mark whenever the commit adds files,
+/// merging any the actions already carry.
+///
+/// `commit_version` is the version this commit is written at, which is
+/// why commit::commit_with_row_ids assigns afresh each time it retries.
+pub fn assign_row_ids(
+    actions: &mut Vec<Action>,
+    high_water_mark: Option<i64>,
+    commit_version: i64,
+) -> Result<(), DeltaTxnError> {
+    // -1 for a table that has assigned none yet, so the first file's
+    // base_row_id is 0.
+    let mut mark = high_water_mark.unwrap_or(-1);
+    let mut any_add = false;
+    for action in actions.iter_mut() {
+        let Action::Add(add) = action else {
+            continue;
+        };
+        any_add = true;
+        let records = row_count(add)?;
+        match add.base_row_id {
+            Some(base) => mark = mark.max(base + records - 1),
+            None => {
+                add.base_row_id = Some(mark + 1);
+                mark += records;
+            }
+        }
+        add.default_row_commit_version.get_or_insert(commit_version);
+    }
+
+    // A mark the actions already record is merged into the one recorded
+    // below rather than left alongside it: one commit may carry only one
+    // action per domain.
+    let mut recorded = Vec::new();
+    actions.retain(|action| match action {
+        Action::DomainMetadata(dm) if dm.domain == ROW_TRACKING_DOMAIN => {
+            recorded.push(dm.configuration.clone());
+            false
+        }
+        _ => true,
+    });
+    for configuration in &recorded {
+        mark = mark.max(parse_high_water_mark(configuration)?);
+    }
+
+    if any_add || !recorded.is_empty() {
+        actions.push(Action::DomainMetadata(DomainMetadata {
+            domain: ROW_TRACKING_DOMAIN.to_string(),
+            configuration: serde_json::json!({ "rowIdHighWaterMark": mark }).to_string(),
+            removed: false,
+        }));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(path: &str, num_records: Option<i64>) -> Action {
+        Action::Add(Add {
+            path: path.to_string(),
+            stats: num_records.map(|n| serde_json::json!({ "numRecords": n }).to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn adds(actions: &[Action]) -> Vec<(Option<i64>, Option<i64>)> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::Add(add) => Some((add.base_row_id, add.default_row_commit_version)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn recorded_mark(actions: &[Action]) -> Option<String> {
+        actions.iter().find_map(|action| match action {
+            Action::DomainMetadata(dm) if dm.domain == ROW_TRACKING_DOMAIN => {
+                Some(dm.configuration.clone())
+            }
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn fresh_ids_continue_from_the_high_water_mark() {
+        let mut actions = vec![add("a", Some(10)), add("b", Some(5))];
+        assign_row_ids(&mut actions, Some(99), 4).unwrap();
+        assert_eq!(
+            adds(&actions),
+            vec![(Some(100), Some(4)), (Some(110), Some(4))]
+        );
+        assert_eq!(
+            recorded_mark(&actions).as_deref(),
+            Some(r#"{"rowIdHighWaterMark":114}"#)
+        );
+    }
+
+    #[test]
+    fn a_new_table_starts_at_zero_and_client_ids_only_raise_the_mark() {
+        let mut actions = vec![add("a", Some(3)), add("b", Some(2))];
+        if let Action::Add(b) = &mut actions[1] {
+            b.base_row_id = Some(50);
+            b.default_row_commit_version = Some(7);
+        }
+        assign_row_ids(&mut actions, None, 0).unwrap();
+        assert_eq!(
+            adds(&actions),
+            vec![(Some(0), Some(0)), (Some(50), Some(7))]
+        );
+        assert_eq!(
+            recorded_mark(&actions).as_deref(),
+            Some(r#"{"rowIdHighWaterMark":51}"#)
+        );
+    }
+
+    #[test]
+    fn an_add_without_a_row_count_cant_be_assigned_ids() {
+        let mut actions = vec![add("a", None)];
+        let err = assign_row_ids(&mut actions, None, 0).unwrap_err();
+        assert!(matches!(err, DeltaTxnError::MissingRowCount { path } if path == "a"));
+
+        // Nor can a client-assigned one be accounted for in the mark.
+        let mut actions = vec![add("b", None)];
+        if let Action::Add(b) = &mut actions[0] {
+            b.base_row_id = Some(50);
+        }
+        let err = assign_row_ids(&mut actions, None, 0).unwrap_err();
+        assert!(matches!(err, DeltaTxnError::MissingRowCount { path } if path == "b"));
+    }
+
+    #[test]
+    fn a_mark_already_in_the_actions_is_merged_into_one() {
+        let mut actions = vec![
+            add("a", Some(10)),
+            Action::DomainMetadata(DomainMetadata {
+                domain: ROW_TRACKING_DOMAIN.to_string(),
+                configuration: r#"{"rowIdHighWaterMark":500}"#.to_string(),
+                removed: false,
+            }),
+        ];
+        assign_row_ids(&mut actions, Some(99), 4).unwrap();
+        assert_eq!(adds(&actions), vec![(Some(100), Some(4))]);
+        let marks: Vec<_> = actions
+            .iter()
+            .filter(|action| matches!(action, Action::DomainMetadata(_)))
+            .collect();
+        assert_eq!(marks.len(), 1);
+        assert_eq!(
+            recorded_mark(&actions).as_deref(),
+            Some(r#"{"rowIdHighWaterMark":500}"#)
+        );
+    }
+
+    #[test]
+    fn a_commit_without_adds_leaves_the_mark_alone() {
+        let mut actions = vec![Action::Remove(Default::default())];
+        assign_row_ids(&mut actions, Some(5), 1).unwrap();
+        assert!(recorded_mark(&actions).is_none());
+    }
+
+    #[test]
+    fn row_tracking_fields_need_the_feature() {
+        let legacy: Protocol = serde_json::from_value(
+            serde_json::json!({"minReaderVersion": 1, "minWriterVersion": 2}),
+        )
+        .unwrap();
+        let mut actions = vec![add("a", Some(1))];
+        check_row_tracking(&actions, &legacy).unwrap();
+        if let Action::Add(a) = &mut actions[0] {
+            a.base_row_id = Some(0);
+        }
+        let err = check_row_tracking(&actions, &legacy).unwrap_err();
+        assert!(matches!(err, DeltaTxnError::TableFeatureRequired { .. }));
+
+        let tracked: Protocol = serde_json::from_value(serde_json::json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 7,
+            "writerFeatures": ["domainMetadata", "rowTracking"]
+        }))
+        .unwrap();
+        assert!(row_tracking_supported(&tracked));
+        check_row_tracking(&actions, &tracked).unwrap();
+        let err = check_row_tracking(&[add("b", None)], &tracked).unwrap_err();
+        assert!(matches!(err, DeltaTxnError::MissingRowCount { path } if path == "b"));
+    }
+}
@@ -0,0 +1,306 @@
+use std::fmt;
+use std::str::FromStr;
+
+use deltalake::kernel::{DataType, PrimitiveType, StructType};
+
+use super::errors::DeltaTxnError;
+
+/// How far a Commit's TableMetadata action may move an existing table's
+/// schema away from the one its current snapshot has. Chosen once per
+/// process (`DELTA_TXN_SCHEMA_EVOLUTION`, see config::commit) and applied
+/// by grpc::server::commit() before CommitBuilder ever sees the new
+/// metadata -- delta-rs itself writes any schema it's given.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaEvolutionPolicy {
+    /// Every existing field kept with its exact type; only new nullable
+    /// fields (top-level or inside a struct) added. Nullability may be
+    /// relaxed, never tightened. The default: nothing an existing reader
+    /// relies on changes.
+    #[default]
+    Additive,
+    /// `Additive`, plus the type changes Delta's type-widening feature
+    /// defines (byte -> short -> int -> long, float -> double, the
+    /// integers to double or a wide enough decimal, a decimal to a wider
+    /// one, date -> timestamp_ntz). Readers only honour a widened type on
+    /// a table with the `typeWidening` feature; adding that to the
+    /// protocol is left to the committing client.
+    Widening,
+    /// No check at all -- the behaviour before this policy existed.
+    Any,
+}
+
+impl FromStr for SchemaEvolutionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "additive" => Ok(Self::Additive),
+            "widening" => Ok(Self::Widening),
+            "any" => Ok(Self::Any),
+            other => Err(format!(
+                "unknown schema evolution policy '{other}' (expected additive, widening or any)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SchemaEvolutionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Additive => "additive",
+            Self::Widening => "widening",
+            Self::Any => "any",
+        })
+    }
+}
+
+/// Checks `proposed` against `current` under `policy`, failing with the
+/// first incompatible field found, named by its dotted path (an array's
+/// element and a map's key/value as `.element`, `.key` and `.value`, the
+/// same addressing grpc::mapping::map_schema uses). Fields are matched by
+/// exact name, so a rename reads as a drop plus an add.
+pub fn check_schema_evolution(
+    current: &StructType,
+    proposed: &StructType,
+    policy: SchemaEvolutionPolicy,
+) -> Result<(), DeltaTxnError> {
+    if policy == SchemaEvolutionPolicy::Any {
+        return Ok(());
+    }
+    check_struct(current, proposed, None, policy).map_err(DeltaTxnError::IncompatibleSchema)
+}
+
+fn check_struct(
+    current: &StructType,
+    proposed: &StructType,
+    parent: Option<&str>,
+    policy: SchemaEvolutionPolicy,
+) -> Result<(), String> {
+    let path = |name: &str| match parent {
+        Some(parent) => format!("{parent}.{name}"),
+        None => name.to_string(),
+    };
+
+    for field in current.fields() {
+        let field_path = path(field.name());
+        let Some(new_field) = proposed.field(field.name()) else {
+            return Err(format!("field `{field_path}` was dropped"));
+        };
+        if field.is_nullable() && !new_field.is_nullable() {
+            return Err(format!("field `{field_path}` can't become non-nullable"));
+        }
+        check_type(
+            field.data_type(),
+            new_field.data_type(),
+            &field_path,
+            policy,
+        )?;
+    }
+
+    for new_field in proposed.fields() {
+        if current.field(new_field.name()).is_none() && !new_field.is_nullable() {
+            return Err(format!(
+                "new field `{}` must be nullable: existing rows have no value for it",
+                path(new_field.name())
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_type(
+    current: &DataType,
+    proposed: &DataType,
+    path: &str,
+    policy: SchemaEvolutionPolicy,
+) -> Result<(), String> {
+    match (current, proposed) {
+        (DataType::Struct(current), DataType::Struct(proposed)) => {
+            check_struct(current, proposed, Some(path), policy)
+        }
+        (DataType::Array(current), DataType::Array(proposed)) => {
+            if current.contains_null() && !proposed.contains_null() {
+                return Err(format!("field `{path}` elements can't become non-nullable"));
+            }
+            check_type(
+                current.element_type(),
+                proposed.element_type(),
+                &format!("{path}.element"),
+                policy,
+            )
+        }
+        (DataType::Map(current), DataType::Map(proposed)) => {
+            if current.value_contains_null() && !proposed.value_contains_null() {
+                return Err(format!("field `{path}` values can't become non-nullable"));
+            }
+            check_type(
+                current.key_type(),
+                proposed.key_type(),
+                &format!("{path}.key"),
+                policy,
+            )?;
+            check_type(
+                current.value_type(),
+                proposed.value_type(),
+                &format!("{path}.value"),
+                policy,
+            )
+        }
+        (DataType::Primitive(from), DataType::Primitive(to))
+            if policy == SchemaEvolutionPolicy::Widening && can_widen(from, to) =>
+        {
+            Ok(())
+        }
+        _ if current == proposed => Ok(()),
+        _ => Err(format!(
+            "field `{path}` changes type from {current} to {proposed}{}",
+            if policy == SchemaEvolutionPolicy::Additive {
+                " (the additive schema evolution policy allows no type changes)"
+            } else {
+                ""
+            }
+        )),
+    }
+}
+
+// Delta's type-widening table (PROTOCOL.md, "Type Widening"). Integer to
+// decimal needs room for every digit the integer type can have: 3 for a
+// byte, 5 for a short, 10 for an int, 20 for a long.
+fn can_widen(from: &PrimitiveType, to: &PrimitiveType) -> bool {
+    use PrimitiveType::*;
+    let integer_digits = |t: &PrimitiveType| match t {
+        Byte => Some(3),
+        Short => Some(5),
+        Integer => Some(10),
+        Long => Some(20),
+        _ => None,
+    };
+    match (from, to) {
+        (Byte, Short | Integer | Long | Double)
+        | (Short, Integer | Long | Double)
+        | (Integer, Long | Double)
+        | (Float, Double)
+        | (Date, TimestampNtz) => true,
+        (Decimal(from), Decimal(to)) => {
+            to.precision() >= from.precision()
+                && to.scale() >= from.scale()
+                && to.precision() - to.scale() >= from.precision() - from.scale()
+        }
+        (integer, Decimal(to)) => integer_digits(integer)
+            .is_some_and(|digits| (to.precision() - to.scale()) as u32 >= digits),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake::kernel::{ArrayType, StructField};
+
+    fn schema(fields: impl IntoIterator<Item = StructField>) -> StructType {
+        StructType::try_new(fields).unwrap()
+    }
+
+    fn current() -> StructType {
+        schema([
+            StructField::not_null("id", DataType::INTEGER),
+            StructField::nullable(
+                "address",
+                schema([StructField::nullable("city", DataType::STRING)]),
+            ),
+            StructField::nullable("tags", ArrayType::new(DataType::FLOAT, true)),
+        ])
+    }
+
+    fn err(proposed: StructType, policy: SchemaEvolutionPolicy) -> String {
+        check_schema_evolution(&current(), &proposed, policy)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn additive_accepts_new_nullable_fields_and_relaxed_nullability() {
+        let proposed = schema([
+            StructField::nullable("id", DataType::INTEGER),
+            StructField::nullable(
+                "address",
+                schema([
+                    StructField::nullable("city", DataType::STRING),
+                    StructField::nullable("zip", DataType::STRING),
+                ]),
+            ),
+            StructField::nullable("tags", ArrayType::new(DataType::FLOAT, true)),
+            StructField::nullable("note", DataType::STRING),
+        ]);
+        check_schema_evolution(&current(), &proposed, SchemaEvolutionPolicy::Additive).unwrap();
+    }
+
+    #[test]
+    fn additive_rejects_drops_new_required_fields_and_type_changes_by_path() {
+        let dropped = schema([
+            StructField::not_null("id", DataType::INTEGER),
+            StructField::nullable("address", schema([])),
+            StructField::nullable("tags", ArrayType::new(DataType::FLOAT, true)),
+        ]);
+        assert!(
+            err(dropped, SchemaEvolutionPolicy::Additive).contains("`address.city` was dropped")
+        );
+
+        let mut fields: Vec<_> = current().fields().cloned().collect();
+        fields.push(StructField::not_null("note", DataType::STRING));
+        assert!(err(schema(fields), SchemaEvolutionPolicy::Additive)
+            .contains("`note` must be nullable"));
+
+        let widened = schema([
+            StructField::not_null("id", DataType::INTEGER),
+            StructField::nullable(
+                "address",
+                schema([StructField::nullable("city", DataType::STRING)]),
+            ),
+            StructField::nullable("tags", ArrayType::new(DataType::DOUBLE, true)),
+        ]);
+        assert!(err(widened.clone(), SchemaEvolutionPolicy::Additive)
+            .contains("`tags.element` changes type from float to double"));
+        check_schema_evolution(&current(), &widened, SchemaEvolutionPolicy::Widening).unwrap();
+    }
+
+    #[test]
+    fn widening_follows_deltas_type_widening_table() {
+        use PrimitiveType::*;
+        assert!(can_widen(&Integer, &Long));
+        assert!(can_widen(&Short, &Double));
+        assert!(can_widen(&Date, &TimestampNtz));
+        assert!(can_widen(&Integer, &PrimitiveType::decimal(12, 2).unwrap()));
+        assert!(!can_widen(
+            &Integer,
+            &PrimitiveType::decimal(10, 2).unwrap()
+        ));
+        assert!(can_widen(
+            &PrimitiveType::decimal(10, 2).unwrap(),
+            &PrimitiveType::decimal(12, 4).unwrap()
+        ));
+        assert!(!can_widen(
+            &PrimitiveType::decimal(10, 2).unwrap(),
+            &PrimitiveType::decimal(10, 4).unwrap()
+        ));
+        assert!(!can_widen(&Long, &Integer));
+        assert!(!can_widen(&Long, &String));
+
+        let narrowed = schema([StructField::not_null("id", DataType::SHORT)]);
+        assert!(err(narrowed, SchemaEvolutionPolicy::Widening).contains("`id` changes type"));
+    }
+
+    #[test]
+    fn any_accepts_everything() {
+        check_schema_evolution(&current(), &schema([]), SchemaEvolutionPolicy::Any).unwrap();
+    }
+
+    #[test]
+    fn policy_parses_case_insensitively() {
+        assert_eq!(
+            " Widening ".parse::<SchemaEvolutionPolicy>(),
+            Ok(SchemaEvolutionPolicy::Widening)
+        );
+        assert!("strict".parse::<SchemaEvolutionPolicy>().is_err());
+    }
+}
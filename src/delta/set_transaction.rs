@@ -0,0 +1,65 @@
+use deltalake::kernel::{Action, Transaction};
+use deltalake::logstore::LogStore;
+use deltalake::table::state::DeltaTableState;
+
+use super::errors::DeltaTxnError;
+
+/// Rejects a Commit carrying a SetTransaction whose version isn't newer
+/// than the one `snapshot` records for its app_id -- the retry of a
+/// commit that already landed. One lookup per txn action; a commit rarely
+/// has more than one.
+///
+/// Checked against the snapshot the commit is about to be built on. A
+/// duplicate committed by another writer after that snapshot was read is
+/// delta-rs's conflict checker's to catch: it fails any commit whose txn
+/// app_id a concurrent winner also wrote.
+pub async fn check_transactions(
+    actions: &[Action],
+    snapshot: &DeltaTableState,
+    log_store: &dyn LogStore,
+) -> Result<(), DeltaTxnError> {
+    for action in actions {
+        let Action::Txn(txn) = action else {
+            continue;
+        };
+        let recorded = snapshot
+            .transaction_version(log_store, &txn.app_id)
+            .await
+            .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?;
+        check_transaction_version(txn, recorded)?;
+    }
+    Ok(())
+}
+
+fn check_transaction_version(
+    txn: &Transaction,
+    recorded: Option<i64>,
+) -> Result<(), DeltaTxnError> {
+    match recorded {
+        Some(recorded) if txn.version <= recorded => Err(DeltaTxnError::DuplicateTransaction {
+            app_id: txn.app_id.clone(),
+            version: txn.version,
+            recorded,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_newer_version_passes() {
+        let txn = Transaction::new("ingest", 7);
+        check_transaction_version(&txn, None).unwrap();
+        check_transaction_version(&txn, Some(6)).unwrap();
+        for recorded in [7, 8] {
+            let err = check_transaction_version(&txn, Some(recorded)).unwrap_err();
+            assert!(
+                matches!(err, DeltaTxnError::DuplicateTransaction { version: 7, .. }),
+                "{err}"
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use super::errors::DeltaTxnError;
+use delta_kernel::Error as KernelError;
 use deltalake::logstore::LogStore;
-use deltalake::{DeltaTable, DeltaTableBuilder};
+use deltalake::{DeltaTable, DeltaTableBuilder, DeltaTableError};
 use std::collections::HashMap;
 use url::Url;
 
@@ -26,6 +27,80 @@ pub async fn open_table(
         .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))
 }
 
+/// The time-travel counterpart to `open_table`: loads the table as of
+/// exactly `version` rather than its latest. Same "call `table_exists`
+/// first" contract as `open_table`.
+///
+/// delta-rs's own versioned load fails the same opaque way for a version
+/// that doesn't exist *yet* as for one that's been cleaned up by log
+/// retention (both just "no log segment for that version"), and those
+/// need different answers for the client -- so on that failure, this
+/// opens the table at its latest version to tell the two apart. That
+/// second load only ever happens on the error path; a successful
+/// time-travel read costs exactly one load, same as `open_table`. Any
+/// other load failure -- an object-store error, a permission error, a
+/// checkpoint that won't parse -- says nothing about whether the version
+/// exists, and passes through as `OpenFailed` like `open_table`'s.
+pub async fn open_table_at_version(
+    table_url: &Url,
+    storage_options: HashMap<String, String>,
+    version: u64,
+) -> Result<DeltaTable, DeltaTxnError> {
+    let load_err = match DeltaTableBuilder::from_url(table_url.clone())
+        .map_err(|e| DeltaTxnError::OpenFailed(e.to_string()))?
+        .with_storage_options(storage_options.clone())
+        .with_version(version)
+        .load()
+        .await
+    {
+        Ok(table) => return Ok(table),
+        Err(e) if is_missing_log_segment(&e) => e,
+        Err(e) => return Err(DeltaTxnError::OpenFailed(e.to_string())),
+    };
+
+    let latest = open_table(table_url, storage_options)
+        .await?
+        .version()
+        .ok_or_else(|| DeltaTxnError::OpenFailed("table has no loaded version".to_string()))?;
+
+    // u64 -> i64 casts: see grpc::server's matching comment on
+    // snapshot.version() -- a real table version never approaches
+    // i64::MAX, and the wire contract is int64 throughout.
+    if version > latest {
+        Err(DeltaTxnError::VersionNotFound {
+            requested: version as i64,
+            latest: latest as i64,
+        })
+    } else {
+        Err(DeltaTxnError::VersionUnavailable {
+            requested: version as i64,
+            reason: load_err.to_string(),
+        })
+    }
+}
+
+/// Whether a versioned load failed because the log has nothing to build
+/// that version from: no commit file or checkpoint at or below it at all
+/// (delta-rs's NotATable, "No files in log segment"), a log that ends
+/// before it, or commit files missing from the middle of the segment.
+/// delta_kernel reports most of these as generic errors, so the last
+/// three are told apart by message.
+fn is_missing_log_segment(err: &DeltaTableError) -> bool {
+    match err {
+        DeltaTableError::NotATable(_) | DeltaTableError::InvalidVersion(_) => true,
+        DeltaTableError::KernelError(KernelError::MissingVersion) => true,
+        DeltaTableError::KernelError(KernelError::Generic(msg)) => {
+            msg.starts_with("LogSegment end version")
+                || msg.starts_with("Expected contiguous commit files")
+                || msg.starts_with("Expected the first commit")
+        }
+        DeltaTableError::KernelError(KernelError::InvalidCheckpoint(msg)) => {
+            msg.starts_with("Gap between checkpoint version")
+        }
+        _ => false,
+    }
+}
+
 /// Checks whether `table_uri` already has an initialized Delta log
 /// (`_delta_log/*.json`/a checkpoint), without paying for a full load.
 ///
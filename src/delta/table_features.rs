@@ -0,0 +1,269 @@
+use std::str::FromStr;
+
+use delta_kernel::table_features::TableFeature;
+use deltalake::kernel::{Protocol, TableFeatures};
+
+use super::errors::DeltaTxnError;
+
+/// The features a legacy (pre-feature-list) writer version implies, per
+/// PROTOCOL.md's "Table Features" table: each version supports its own
+/// row plus every row below it.
+const LEGACY_WRITER_FEATURES: &[(i32, TableFeature)] = &[
+    (2, TableFeature::AppendOnly),
+    (2, TableFeature::Invariants),
+    (3, TableFeature::CheckConstraints),
+    (4, TableFeature::ChangeDataFeed),
+    (4, TableFeature::GeneratedColumns),
+    (5, TableFeature::ColumnMapping),
+    (6, TableFeature::IdentityColumns),
+];
+
+/// What a feature needs supported alongside it -- PROTOCOL.md's
+/// "requires" for each. Only the features EnableTableFeature accepts
+/// (delta-rs's TableFeatures) need listing.
+fn required_features(feature: &TableFeature) -> &'static [TableFeature] {
+    match feature {
+        TableFeature::RowTracking => &[TableFeature::DomainMetadata],
+        TableFeature::IcebergCompatV1 => &[TableFeature::ColumnMapping],
+        _ => &[],
+    }
+}
+
+/// Whether `feature` is a reader-writer feature (listed in both of a
+/// protocol's lists) rather than writer-only -- delta-rs's own
+/// classification.
+fn is_reader_writer(feature: &TableFeature) -> bool {
+    TableFeatures::from_str(feature.as_ref())
+        .is_ok_and(|feature| feature.to_reader_writer_features().0.is_some())
+}
+
+/// A feature name as EnableTableFeatureRequest.feature carries it -- the
+/// log name, e.g. "deletionVectors" -- checked against the features
+/// delta-rs knows how to classify. Anything else, including features
+/// only newer Delta writers know, is rejected rather than guessed at.
+/// delta-rs's own enum, since that's what `DeltaOperation::AddFeature`
+/// records.
+pub fn parse_table_feature(name: &str) -> Result<TableFeatures, DeltaTxnError> {
+    TableFeatures::from_str(name)
+        .ok()
+        .filter(|feature| TableFeature::try_from(feature).is_ok())
+        .ok_or_else(|| DeltaTxnError::UnknownTableFeature(name.to_string()))
+}
+
+/// Every writer feature `protocol` supports: its writerFeatures list at
+/// writer version 7, else whatever its legacy version implies.
+fn supported_writer_features(protocol: &Protocol) -> Vec<TableFeature> {
+    match protocol.writer_features() {
+        Some(features) => features.to_vec(),
+        None => LEGACY_WRITER_FEATURES
+            .iter()
+            .filter(|(version, _)| protocol.min_writer_version() >= *version)
+            .map(|(_, feature)| feature.clone())
+            .collect(),
+    }
+}
+
+/// Every reader feature `protocol` supports: its readerFeatures list at
+/// reader version 3; columnMapping at legacy version 2.
+fn supported_reader_features(protocol: &Protocol) -> Vec<TableFeature> {
+    match protocol.reader_features() {
+        Some(features) => features.to_vec(),
+        None if protocol.min_reader_version() >= 2 => vec![TableFeature::ColumnMapping],
+        None => Vec::new(),
+    }
+}
+
+fn push_missing(features: &mut Vec<TableFeature>, feature: TableFeature) {
+    if !features.contains(&feature) {
+        features.push(feature);
+    }
+}
+
+/// `current` with `feature` (and whatever it requires) supported, or
+/// `None` if `current` already supports it.
+///
+/// Not delta-rs's own AddTableFeatureBuilder: moving a legacy protocol to
+/// writer version 7 there drops the features its old version implied
+/// (writer version 2's appendOnly and invariants, say), since at 7 only
+/// the listed ones count. Here they're listed explicitly first. The
+/// reader version only moves to 3 when a reader-writer feature needs
+/// listing there -- a writer-only feature leaves readers alone.
+pub fn protocol_with_feature(current: &Protocol, feature: &TableFeatures) -> Option<Protocol> {
+    // parse_table_feature only hands out features that convert.
+    let feature = &TableFeature::try_from(feature)
+        .unwrap_or_else(|_| TableFeature::Unknown(feature.to_string()));
+    let mut writer_features = supported_writer_features(current);
+    let mut reader_features = supported_reader_features(current);
+    let reader_writer = is_reader_writer(feature);
+    if writer_features.contains(feature) && (!reader_writer || reader_features.contains(feature)) {
+        return None;
+    }
+
+    for required in required_features(feature) {
+        push_missing(&mut writer_features, required.clone());
+    }
+    push_missing(&mut writer_features, feature.clone());
+
+    // A legacy reader version 2 covers columnMapping without a list; any
+    // other reader-writer feature needs reader version 3's list, which
+    // must then name every reader-writer feature the writer list does.
+    let needs_reader_list = current.min_reader_version() >= 3
+        || writer_features.iter().any(|feature| {
+            is_reader_writer(feature)
+                && !(current.min_reader_version() == 2 && *feature == TableFeature::ColumnMapping)
+        });
+    let mut value = serde_json::json!({
+        "minReaderVersion": current.min_reader_version(),
+        "minWriterVersion": 7,
+        "writerFeatures": writer_features,
+    });
+    if needs_reader_list {
+        for feature in writer_features.iter().filter(|f| is_reader_writer(f)) {
+            push_missing(&mut reader_features, feature.clone());
+        }
+        value["minReaderVersion"] = serde_json::json!(3);
+        value["readerFeatures"] = serde_json::json!(reader_features);
+    }
+    // Only ever built from known features at versions that take them, so
+    // this can't fail.
+    Some(serde_json::from_value(value).expect("a valid table-features protocol"))
+}
+
+/// Rejects a Protocol action that would take something away from
+/// `current`: a lower reader or writer version, or a feature it supports
+/// (listed or implied by a legacy version) left out. Dropping a feature
+/// is its own, more involved operation in Delta (the feature's traces
+/// must be gone from the table first) that this service doesn't offer.
+pub fn check_protocol_upgrade(
+    current: &Protocol,
+    proposed: &Protocol,
+) -> Result<(), DeltaTxnError> {
+    for (kind, from, to) in [
+        (
+            "min_reader_version",
+            current.min_reader_version(),
+            proposed.min_reader_version(),
+        ),
+        (
+            "min_writer_version",
+            current.min_writer_version(),
+            proposed.min_writer_version(),
+        ),
+    ] {
+        if to < from {
+            return Err(DeltaTxnError::ProtocolDowngrade(format!(
+                "lowers {kind} from {from} to {to}"
+            )));
+        }
+    }
+    for (kind, supported) in [
+        (
+            "writer",
+            supported_writer_features as fn(&Protocol) -> Vec<_>,
+        ),
+        ("reader", supported_reader_features),
+    ] {
+        let proposed_features = supported(proposed);
+        if let Some(dropped) = supported(current)
+            .into_iter()
+            .find(|feature| !proposed_features.contains(feature))
+        {
+            return Err(DeltaTxnError::ProtocolDowngrade(format!(
+                "drops the {kind} feature '{dropped}'"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protocol(value: serde_json::Value) -> Protocol {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn names(features: Option<&[TableFeature]>) -> Option<Vec<String>> {
+        features.map(|features| features.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn a_writer_only_feature_keeps_legacy_features_and_the_reader_version() {
+        let legacy = protocol(serde_json::json!({"minReaderVersion": 1, "minWriterVersion": 2}));
+        let upgraded = protocol_with_feature(&legacy, &TableFeatures::ChangeDataFeed).unwrap();
+        assert_eq!(upgraded.min_reader_version(), 1);
+        assert_eq!(upgraded.min_writer_version(), 7);
+        assert_eq!(upgraded.reader_features(), None);
+        assert_eq!(
+            names(upgraded.writer_features()),
+            Some(vec![
+                "appendOnly".to_string(),
+                "invariants".to_string(),
+                "changeDataFeed".to_string()
+            ])
+        );
+        check_protocol_upgrade(&legacy, &upgraded).unwrap();
+    }
+
+    #[test]
+    fn a_reader_writer_feature_moves_to_reader_version_three() {
+        // Legacy reader 2/writer 5 supports columnMapping implicitly; at
+        // reader version 3 it has to be listed alongside the new feature.
+        let legacy = protocol(serde_json::json!({"minReaderVersion": 2, "minWriterVersion": 5}));
+        let upgraded = protocol_with_feature(&legacy, &TableFeatures::DeletionVectors).unwrap();
+        assert_eq!(upgraded.min_reader_version(), 3);
+        assert_eq!(
+            names(upgraded.reader_features()),
+            Some(vec![
+                "columnMapping".to_string(),
+                "deletionVectors".to_string()
+            ])
+        );
+        assert!(upgraded
+            .writer_features()
+            .unwrap()
+            .contains(&TableFeature::ColumnMapping));
+        check_protocol_upgrade(&legacy, &upgraded).unwrap();
+    }
+
+    #[test]
+    fn supported_features_need_no_upgrade_and_requirements_come_along() {
+        let legacy = protocol(serde_json::json!({"minReaderVersion": 2, "minWriterVersion": 5}));
+        assert!(protocol_with_feature(&legacy, &TableFeatures::AppendOnly).is_none());
+        assert!(protocol_with_feature(&legacy, &TableFeatures::ColumnMapping).is_none());
+
+        let upgraded = protocol_with_feature(&legacy, &TableFeatures::RowTracking).unwrap();
+        let writer_features = upgraded.writer_features().unwrap();
+        assert!(writer_features.contains(&TableFeature::DomainMetadata));
+        assert!(writer_features.contains(&TableFeature::RowTracking));
+        // Still reader version 2: columnMapping is covered without a list.
+        assert_eq!(upgraded.min_reader_version(), 2);
+    }
+
+    #[test]
+    fn downgrades_are_refused() {
+        let current = protocol(serde_json::json!({"minReaderVersion": 1, "minWriterVersion": 4}));
+        let lower = protocol(serde_json::json!({"minReaderVersion": 1, "minWriterVersion": 3}));
+        let err = check_protocol_upgrade(&current, &lower).unwrap_err();
+        assert!(err.to_string().contains("min_writer_version"), "{err}");
+
+        // Writer version 7 listing fewer features than version 4 implied.
+        let dropped = protocol(serde_json::json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 7,
+            "writerFeatures": ["appendOnly", "invariants", "checkConstraints"]
+        }));
+        let err = check_protocol_upgrade(&current, &dropped).unwrap_err();
+        assert!(err.to_string().contains("changeDataFeed"), "{err}");
+    }
+
+    #[test]
+    fn unknown_feature_names_are_rejected() {
+        assert_eq!(
+            parse_table_feature("timestampNtz").unwrap(),
+            TableFeatures::TimestampWithoutTimezone
+        );
+        assert!(parse_table_feature("noSuchFeature").is_err());
+    }
+}
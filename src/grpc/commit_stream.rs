@@ -0,0 +1,134 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use prost::Message;
+use tonic::Status;
+
+use crate::grpc::server::pb;
+
+/// How long an in-flight CommitStream may go quiet, and how much of its
+/// uploads the server holds at most -- see
+/// config::commit::load_commit_stream_limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitStreamLimits {
+    /// How long a CommitStream may go between messages before it's
+    /// abandoned. An upload is one RPC sending as fast as it can, and a
+    /// stalled one holds its share of `max_bytes` until this runs out.
+    pub idle_timeout: Duration,
+    /// Across every in-flight CommitStream: the received actions' encoded
+    /// size.
+    pub max_bytes: usize,
+}
+
+impl Default for CommitStreamLimits {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(60),
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// The byte budget every CommitStream upload on this server draws on.
+/// Cheap to clone; clones share the budget.
+#[derive(Clone, Default)]
+pub struct CommitStreamUploads {
+    bytes: Arc<Mutex<usize>>,
+    limits: CommitStreamLimits,
+}
+
+impl CommitStreamUploads {
+    pub fn new(limits: CommitStreamLimits) -> Self {
+        Self {
+            bytes: Arc::default(),
+            limits,
+        }
+    }
+
+    pub fn limits(&self) -> CommitStreamLimits {
+        self.limits
+    }
+
+    /// An empty buffer for one CommitStream upload.
+    pub fn buffer(&self) -> StreamBuffer {
+        StreamBuffer {
+            uploads: self.clone(),
+            bytes: 0,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, usize> {
+        // Nothing panics while holding it; see StagedTransactionManager's
+        // matching lock().
+        self.bytes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// What one CommitStream upload has received so far, counted against
+/// CommitStreamLimits::max_bytes -- otherwise a few concurrent uploads
+/// could each buffer an unbounded action list. The bytes are given back
+/// when the buffer is dropped, however the RPC ends.
+pub struct StreamBuffer {
+    uploads: CommitStreamUploads,
+    bytes: usize,
+}
+
+impl StreamBuffer {
+    /// Accounts for `actions` arriving, or refuses them
+    /// (RESOURCE_EXHAUSTED) if they'd take the server past its limit.
+    pub fn reserve(&mut self, actions: &[pb::Action]) -> Result<(), Status> {
+        let bytes = actions.iter().map(Message::encoded_len).sum::<usize>();
+        let max_bytes = self.uploads.limits.max_bytes;
+        let mut held = self.uploads.lock();
+        if *held + bytes > max_bytes {
+            return Err(Status::resource_exhausted(format!(
+                "holding {bytes} more bytes would exceed the server's {max_bytes}-byte limit for \
+                 in-flight CommitStream uploads -- retry once other uploads finish, or stage the \
+                 actions with BeginTransaction instead"
+            )));
+        }
+        *held += bytes;
+        self.bytes += bytes;
+        Ok(())
+    }
+}
+
+impl Drop for StreamBuffer {
+    fn drop(&mut self) {
+        *self.uploads.lock() -= self.bytes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(path: &str) -> pb::Action {
+        pb::Action {
+            action: Some(pb::action::Action::Add(pb::AddFile {
+                path: path.to_string(),
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[test]
+    fn stream_buffers_share_the_budget_until_dropped() {
+        let one_add = add("part-0.parquet").encoded_len();
+        let uploads = CommitStreamUploads::new(CommitStreamLimits {
+            max_bytes: 2 * one_add,
+            ..Default::default()
+        });
+        let mut buffer = uploads.buffer();
+        buffer.reserve(&[add("part-0.parquet")]).unwrap();
+        let mut other = uploads.buffer();
+        other.reserve(&[add("part-1.parquet")]).unwrap();
+        let err = buffer.reserve(&[add("part-2.parquet")]).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+        drop(other);
+        buffer.reserve(&[add("part-2.parquet")]).unwrap();
+        drop(buffer);
+        assert_eq!(*uploads.lock(), 0);
+    }
+}
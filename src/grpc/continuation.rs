@@ -0,0 +1,93 @@
+use prost::Message;
+
+// Bumped whenever ActiveFilesCursor's meaning changes incompatibly, so a
+// token minted by an older server is rejected as unrecognized rather than
+// misread. Tokens are short-lived (one interrupted listing), so there's no
+// need to keep decoding old formats.
+const CURSOR_FORMAT: u32 = 1;
+
+/// Where an interrupted ListActiveFiles stream got to, carried to the
+/// client as each `ListActiveFilesBatch.continuation_token` and back as
+/// `ListActiveFilesRequest.continuation_token` to resume.
+///
+/// Opaque to the client: the bytes are this message prost-encoded, and
+/// nothing about the layout is part of the wire contract. `position` counts
+/// files of the snapshot's own file list (in its in-memory order), *before*
+/// partition filters or a predicate drop any, so a resumed request picks
+/// up at the same place regardless of how many of those files were
+/// actually streamed. `last_path` is the file at `position - 1`, there to
+/// catch a listing whose order changed under the token (see
+/// server.rs's stream_active_files_inner).
+#[derive(Clone, PartialEq, Message)]
+pub struct ActiveFilesCursor {
+    #[prost(uint32, tag = "1")]
+    format: u32,
+    /// The normalized table URI the token was issued for.
+    #[prost(string, tag = "2")]
+    pub table_uri: String,
+    #[prost(uint64, tag = "3")]
+    pub version: u64,
+    #[prost(uint64, tag = "4")]
+    pub position: u64,
+    #[prost(string, tag = "5")]
+    pub last_path: String,
+}
+
+impl ActiveFilesCursor {
+    pub fn new(table_uri: String, version: u64, position: u64, last_path: String) -> Self {
+        Self {
+            format: CURSOR_FORMAT,
+            table_uri,
+            version,
+            position,
+            last_path,
+        }
+    }
+
+    pub fn to_token(&self) -> Vec<u8> {
+        self.encode_to_vec()
+    }
+
+    /// Decodes a token a client sent back. Anything that isn't a token this
+    /// server minted -- garbage bytes, an unknown format, a zero position
+    /// (never issued: every token follows at least one file) -- is an
+    /// error string for an INVALID_ARGUMENT.
+    pub fn from_token(token: &[u8]) -> Result<Self, String> {
+        let cursor = Self::decode(token)
+            .map_err(|_| "continuation_token is not a valid token".to_string())?;
+        if cursor.format != CURSOR_FORMAT || cursor.position == 0 {
+            return Err("continuation_token is not a valid token".to_string());
+        }
+        Ok(cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_round_trip() {
+        let cursor = ActiveFilesCursor::new(
+            "file:///tmp/orders/".to_string(),
+            7,
+            1000,
+            "part-999.parquet".to_string(),
+        );
+        assert_eq!(
+            ActiveFilesCursor::from_token(&cursor.to_token()).unwrap(),
+            cursor
+        );
+    }
+
+    #[test]
+    fn foreign_bytes_are_rejected() {
+        assert!(ActiveFilesCursor::from_token(b"not a token at all").is_err());
+        assert!(ActiveFilesCursor::from_token(&[]).is_err());
+
+        let mut future_format =
+            ActiveFilesCursor::new("file:///tmp/t/".to_string(), 1, 1, "a".to_string());
+        future_format.format = CURSOR_FORMAT + 1;
+        assert!(ActiveFilesCursor::from_token(&future_format.to_token()).is_err());
+    }
+}
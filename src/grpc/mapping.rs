@@ -6,11 +6,18 @@
 //! call): proto -> kernel, so grpc::server::commit() can hand the result
 //! straight to delta-rs's CommitBuilder.
 //!
+//! ListActiveFiles' partition filters (`map_partition_filters`) and
+//! projection (`map_file_projection`) go the write direction's way too --
+//! request -> delta::partition_filter / FileProjection -- despite belonging
+//! to a read.
+//!
 //! Read direction (ListActiveFiles, `map_active_file_to_pb` and
 //! `map_stats_json_to_pb`): the reverse, taking plain fields read off a
 //! LogicalFileView (see grpc::server::stream_active_files_inner) rather
 //! than a kernel::Add itself, since LogicalFileView's public API doesn't
-//! expose one (see map_active_file_to_pb's own doc comment).
+//! expose one (see map_active_file_to_pb's own doc comment). GetHistory
+//! (`map_history_entry_to_pb`) and GetChanges (`map_action_to_pb`) read
+//! whole commit files, so they do get real kernel actions to map back.
 //!
 //! Several kernel types (Metadata, Protocol, CommitInfo) are built here by
 //! constructing a serde_json::Value in Delta's own log JSON shape
@@ -20,24 +27,55 @@
 //! field -- reuses delta-rs's own parsing/validation instead of
 //! duplicating it, at the cost of the mapping being one step more
 //! indirect than a plain struct literal would be.
-use deltalake::kernel::{Action, Add, CommitInfo, Metadata, Protocol, Remove};
+use delta_kernel::table_features::{
+    TableFeature, TABLE_FEATURES_MIN_READER_VERSION, TABLE_FEATURES_MIN_WRITER_VERSION,
+};
+use deltalake::kernel::{
+    Action, Add, ArrayType, CommitInfo, DataType, DeletionVectorDescriptor, MapType, Metadata,
+    MetadataValue, PrimitiveType, Protocol, Remove, StorageType, StructField, StructType,
+    Transaction,
+};
 
 use serde_json::Value;
 
+use crate::delta::detail::PartitionSummary;
+use crate::delta::idempotency::commit_idempotency_key;
+use crate::delta::partition_filter::{PartitionFilter, PartitionPredicate, RangeBound};
 use crate::grpc::server::pb;
 use pb::action::Action as PbAction;
+use pb::partition_filter::Predicate as PbPartitionPredicate;
 
 /// Top-level entry point for Commit: every action in the request, in
 /// order (order matters -- delta-rs's CommitBuilder applies them as a
 /// single ordered transaction, e.g. a Remove before an Add for the same
 /// logical file is a different outcome than the reverse).
-pub fn map_actions(pb_actions: Vec<pb::Action>) -> Result<Vec<Action>, String> {
-    pb_actions.into_iter().map(map_action).collect()
+///
+/// The one check that needs the whole list rather than one action: the
+/// Delta spec allows a single SetTransaction per app_id per commit.
+pub fn map_actions(
+    pb_actions: impl IntoIterator<Item = pb::Action>,
+) -> Result<Vec<Action>, String> {
+    let actions = pb_actions
+        .into_iter()
+        .map(map_action)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut app_ids = std::collections::HashSet::new();
+    for action in &actions {
+        if let Action::Txn(txn) = action {
+            if !app_ids.insert(txn.app_id.as_str()) {
+                return Err(format!(
+                    "app_id '{}' appears in more than one SetTransaction action",
+                    txn.app_id
+                ));
+            }
+        }
+    }
+    Ok(actions)
 }
 
 // The read-side counterpart to map_action's AddFile arm above: one active
 // file (as read back off a table's log via
-// EagerSnapshot::file_views()/LogicalFileView, see server.rs's
+// EagerSnapshot::try_log_data()/LogicalFileView, see server.rs's
 // list_active_files) converted to its wire form for ListActiveFiles.
 // Takes plain fields rather than a LogicalFileView itself so this stays a
 // pure data transformation with no deltalake-core snapshot-iterator type
@@ -46,31 +84,54 @@ pub fn map_actions(pb_actions: Vec<pb::Action>) -> Result<Vec<Action>, String> {
 // `partition_values` off a LogicalFileView happens in server.rs, right
 // where the borrow lives.
 //
+// `deletion_vector` is passed whatever projection says: without it a
+// client would count rows the file no longer has.
+//
 // `data_change` and `tags` have no equivalent on LogicalFileView's public
 // API: both are write-time-only concepts (data_change distinguishes a
 // CDC-relevant write from a metadata-only one *at commit time*; tags are
 // writer-supplied and not surfaced by the snapshot read API) that don't
 // apply to "this file is part of the table's current snapshot," which is
 // all a read needs to say -- so data_change is always reported True (this
-// file has real data, whatever wrote it) and tags always empty.
+// file has real data, whatever wrote it) and tags always empty. Neither
+// do `base_row_id`/`default_row_commit_version`, though they're no
+// write-time concept: LogicalFileView just doesn't expose them, so they're
+// always unset here (GetChanges reports them).
+//
+// `partition_values` comes in the table's partition-column order (see
+// delta::detail::serialize_partition_values) and fills in both wire
+// fields: the map, a null flattened to "", and nullable_partition_values,
+// in that order with the null intact.
+//
+// Projection (ListActiveFilesRequest.projection) is mostly the caller's
+// job: it passes empty partition_values/None stats_json for whatever the
+// client left out, so the extraction off the LogicalFileView is skipped
+// too, not just the payload. `stats_columns` is the one part applied here,
+// while parsing the stats JSON.
 pub fn map_active_file_to_pb(
     path: String,
     size: i64,
     modification_time: i64,
-    partition_values: std::collections::HashMap<String, Option<String>>,
+    partition_values: Vec<(String, Option<String>)>,
     stats_json: Option<String>,
+    stats_columns: Option<&std::collections::HashSet<String>>,
+    deletion_vector: Option<DeletionVectorDescriptor>,
 ) -> pb::AddFile {
     pb::AddFile {
         path,
         size,
         modification_time,
         partition_values: partition_values
-            .into_iter()
-            .map(|(k, v)| (k, v.unwrap_or_default()))
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone().unwrap_or_default()))
             .collect(),
         data_change: pb::DataChange::True as i32,
-        stats: map_stats_json_to_pb(stats_json),
+        stats: map_stats_json_to_pb(stats_json, stats_columns),
         tags: std::collections::HashMap::new(),
+        nullable_partition_values: map_partition_values_to_pb(partition_values),
+        deletion_vector: deletion_vector.map(map_deletion_vector_to_pb),
+        base_row_id: None,
+        default_row_commit_version: None,
     }
 }
 
@@ -80,8 +141,13 @@ pub fn map_active_file_to_pb(
 // service either way) back into typed pb::FileStats. A column is included
 // if it appears in any of minValues/maxValues/nullCount -- a column with
 // only e.g. a null count and no min/max (all-null column) still gets an
-// entry, just with min_value/max_value left unset.
-fn map_stats_json_to_pb(stats_json: Option<String>) -> Option<pb::FileStats> {
+// entry, just with min_value/max_value left unset. With `only_columns`,
+// every other column is dropped before its values are converted
+// (numRecords is always kept).
+fn map_stats_json_to_pb(
+    stats_json: Option<String>,
+    only_columns: Option<&std::collections::HashSet<String>>,
+) -> Option<pb::FileStats> {
     let stats_json = stats_json?;
     let value: Value = serde_json::from_str(&stats_json).ok()?;
 
@@ -100,6 +166,9 @@ fn map_stats_json_to_pb(stats_json: Option<String>) -> Option<pb::FileStats> {
     if let Some(m) = null_counts {
         column_names.extend(m.keys());
     }
+    if let Some(only) = only_columns {
+        column_names.retain(|column| only.contains(*column));
+    }
 
     let mut columns = std::collections::HashMap::new();
     for column in column_names {
@@ -156,9 +225,9 @@ fn value_to_max(v: &Value) -> Option<pb::column_stats::MaxValue> {
 }
 
 /// Dispatches on the request Action's oneof to the matching kernel::Action
-/// variant. `Add`/`Remove` fields not listed here at all (deletion_vector,
-/// base_row_id, default_row_commit_version, clustering_provider,
-/// extended_file_metadata) aren't oversights -- the AddFile/RemoveFile
+/// variant. `Add`/`Remove` fields not listed here at all
+/// (clustering_provider, extended_file_metadata, and Remove's row
+/// tracking fields) aren't oversights -- the AddFile/RemoveFile
 /// proto messages simply don't have wire fields for them yet (newer Delta
 /// features this service's schema hasn't been extended to carry); every
 /// commit through this service always sets them to their "not present"
@@ -169,13 +238,16 @@ fn map_action(action: pb::Action) -> Result<Action, String> {
             path: a.path,
             size: a.size,
             modification_time: a.modification_time,
-            partition_values: map_string_map(a.partition_values),
+            partition_values: map_add_partition_values(
+                a.partition_values,
+                a.nullable_partition_values,
+            )?,
             data_change: map_data_change(a.data_change)?,
             stats: map_file_stats(a.stats),
             tags: map_optional_string_map(a.tags),
-            deletion_vector: None,
-            base_row_id: None,
-            default_row_commit_version: None,
+            deletion_vector: map_deletion_vector(a.deletion_vector)?,
+            base_row_id: a.base_row_id,
+            default_row_commit_version: a.default_row_commit_version,
             clustering_provider: None,
         })),
 
@@ -187,7 +259,7 @@ fn map_action(action: pb::Action) -> Result<Action, String> {
             partition_values: None,
             size: None,
             tags: None,
-            deletion_vector: None,
+            deletion_vector: map_deletion_vector(r.deletion_vector)?,
             base_row_id: None,
             default_row_commit_version: None,
         })),
@@ -197,6 +269,17 @@ fn map_action(action: pb::Action) -> Result<Action, String> {
         PbAction::MetaData(m) => Ok(Action::Metadata(map_metadata(m)?)),
 
         PbAction::CommitInfo(ci) => Ok(Action::CommitInfo(map_commit_info(ci)?)),
+
+        PbAction::Txn(txn) => {
+            if txn.app_id.is_empty() {
+                return Err("SetTransaction.app_id is required".to_string());
+            }
+            Ok(Action::Txn(Transaction {
+                app_id: txn.app_id,
+                version: txn.version,
+                last_updated: txn.last_updated,
+            }))
+        }
     }
 }
 
@@ -216,6 +299,53 @@ fn map_data_change(dc: i32) -> Result<bool, String> {
     }
 }
 
+/// AddFile/RemoveFile.deletion_vector -> kernel's descriptor. Checks only
+/// what the descriptor says about itself (a storage type, somewhere to
+/// find the vector, no offset into an inline one, no negative sizes);
+/// whether the table may have deletion vectors at all is
+/// delta::deletion_vectors' check, against its protocol.
+fn map_deletion_vector(
+    dv: Option<pb::DeletionVectorDescriptor>,
+) -> Result<Option<DeletionVectorDescriptor>, String> {
+    let Some(dv) = dv else {
+        return Ok(None);
+    };
+    let storage_type = match pb::DeletionVectorStorageType::try_from(dv.storage_type) {
+        Ok(pb::DeletionVectorStorageType::UuidRelativePath) => StorageType::UuidRelativePath,
+        Ok(pb::DeletionVectorStorageType::Inline) => StorageType::Inline,
+        Ok(pb::DeletionVectorStorageType::AbsolutePath) => StorageType::AbsolutePath,
+        Ok(pb::DeletionVectorStorageType::Unspecified) => {
+            return Err("deletion_vector.storage_type is unspecified".to_string())
+        }
+        Err(_) => {
+            return Err(format!(
+                "invalid deletion_vector.storage_type value: {}",
+                dv.storage_type
+            ))
+        }
+    };
+    if dv.path_or_inline_dv.is_empty() {
+        return Err("deletion_vector.path_or_inline_dv is empty".to_string());
+    }
+    if storage_type == StorageType::Inline && dv.offset.is_some() {
+        return Err(
+            "deletion_vector.offset must be unset for an inline deletion vector".to_string(),
+        );
+    }
+    if dv.offset.is_some_and(|offset| offset < 0) || dv.size_in_bytes < 0 || dv.cardinality < 0 {
+        return Err(
+            "deletion_vector.offset, size_in_bytes and cardinality can't be negative".to_string(),
+        );
+    }
+    Ok(Some(DeletionVectorDescriptor {
+        storage_type,
+        path_or_inline_dv: dv.path_or_inline_dv,
+        offset: dv.offset,
+        size_in_bytes: dv.size_in_bytes,
+        cardinality: dv.cardinality,
+    }))
+}
+
 /// The write-side counterpart to map_stats_json_to_pb (this file's
 /// read-side inverse of this exact function): typed pb::FileStats ->
 /// Delta's own numRecords/minValues/maxValues/nullCount log JSON
@@ -273,16 +403,40 @@ fn map_column_max_value(value: Option<pb::column_stats::MaxValue>) -> Option<Val
     }
 }
 
-/// The proto's `map<string, string>` (no null values possible on the
-/// wire -- protobuf map values are never optional) widened to kernel::
-/// Add's own `HashMap<String, Option<String>>` partition_values shape,
-/// which *does* need to represent a null partition value (a row whose
-/// partition-key source column was itself null). Every value coming
-/// through this specific path is `Some` -- there is no wire encoding for
-/// "this partition value is null" in AddFile.partition_values today, only
-/// in whatever a *read* (ListActiveFiles) might report back (see
-/// grpc::server's own partition_values extraction, which does handle a
-/// genuinely-null Scalar).
+/// AddFile's two partition-value fields -> kernel::Add's
+/// `HashMap<String, Option<String>>`. The map can't carry a null (protobuf
+/// map values are never optional), so a client with a null partition value
+/// sends nullable_partition_values instead, where an unset `value` becomes
+/// `None`. Setting both is ambiguous -- which one wins? -- so rejected, as
+/// is a column listed twice. Whether the keys are the right ones is
+/// delta::partition_values' check, run once the table's schema is known.
+fn map_add_partition_values(
+    map: std::collections::HashMap<String, String>,
+    list: Vec<pb::PartitionValue>,
+) -> Result<std::collections::HashMap<String, Option<String>>, String> {
+    if list.is_empty() {
+        return Ok(map_string_map(map));
+    }
+    if !map.is_empty() {
+        return Err(
+            "set either partition_values or nullable_partition_values, not both".to_string(),
+        );
+    }
+    let mut values = std::collections::HashMap::with_capacity(list.len());
+    for pb::PartitionValue { column, value } in list {
+        if values.contains_key(&column) {
+            return Err(format!(
+                "partition column '{column}' appears twice in nullable_partition_values"
+            ));
+        }
+        values.insert(column, value);
+    }
+    Ok(values)
+}
+
+/// The proto's `map<string, string>` widened to kernel's
+/// `HashMap<String, Option<String>>` shape (partition values, tags), every
+/// value `Some`.
 fn map_string_map(
     input: std::collections::HashMap<String, String>,
 ) -> std::collections::HashMap<String, Option<String>> {
@@ -303,11 +457,42 @@ fn map_optional_string_map(
     }
 }
 
+/// The feature lists are written only at the versions that have them --
+/// readerFeatures at reader version 3, writerFeatures at writer version 7,
+/// present (if possibly empty) there and absent everywhere else, as the
+/// spec requires. delta-rs deserializes a Protocol without checking any
+/// of that, so a list at the wrong version is rejected here rather than
+/// written into the log for readers to trip over.
 fn map_protocol(protocol: pb::Protocol) -> Result<Protocol, String> {
-    let value = serde_json::json!({
+    let mut value = serde_json::json!({
         "minReaderVersion": protocol.min_reader_version,
         "minWriterVersion": protocol.min_writer_version,
     });
+    if protocol.min_reader_version == TABLE_FEATURES_MIN_READER_VERSION {
+        if let Some(feature) = protocol
+            .reader_features
+            .iter()
+            .find(|feature| !protocol.writer_features.contains(feature))
+        {
+            return Err(format!(
+                "reader feature '{feature}' must also be listed in writer_features"
+            ));
+        }
+        value["readerFeatures"] = serde_json::json!(protocol.reader_features);
+    } else if !protocol.reader_features.is_empty() {
+        return Err(format!(
+            "reader_features requires min_reader_version {TABLE_FEATURES_MIN_READER_VERSION}, got {}",
+            protocol.min_reader_version
+        ));
+    }
+    if protocol.min_writer_version == TABLE_FEATURES_MIN_WRITER_VERSION {
+        value["writerFeatures"] = serde_json::json!(protocol.writer_features);
+    } else if !protocol.writer_features.is_empty() {
+        return Err(format!(
+            "writer_features requires min_writer_version {TABLE_FEATURES_MIN_WRITER_VERSION}, got {}",
+            protocol.min_writer_version
+        ));
+    }
     serde_json::from_value(value).map_err(|e| e.to_string())
 }
 
@@ -334,12 +519,19 @@ fn map_metadata(metadata: pb::TableMetadata) -> Result<Metadata, String> {
     } else {
         Some(metadata.created_time)
     };
+    let schema_string = match metadata.schema {
+        None => metadata.schema_string,
+        Some(schema) if metadata.schema_string.is_empty() => {
+            serde_json::to_string(&map_schema(schema)?).map_err(|e| e.to_string())?
+        }
+        Some(_) => return Err("set either schema_string or schema, not both".to_string()),
+    };
     let value = serde_json::json!({
         "id": metadata.id,
         "name": name,
         "description": description,
         "format": { "provider": "parquet", "options": {} },
-        "schemaString": metadata.schema_string,
+        "schemaString": schema_string,
         "partitionColumns": metadata.partition_columns,
         "configuration": metadata.configuration,
         "createdTime": created_time,
@@ -347,6 +539,117 @@ fn map_metadata(metadata: pb::TableMetadata) -> Result<Metadata, String> {
     serde_json::from_value(value).map_err(|e| e.to_string())
 }
 
+/// TableMetadata.schema -> the kernel StructType whose JSON becomes the
+/// log's schemaString -- the same type delta-rs parses schemaString back
+/// into, so its own checks (decimal bounds, duplicate field names,
+/// case-insensitively) apply exactly as they would to a hand-written
+/// schema_string. Errors name the offending field by its dotted path, an
+/// array's element and a map's key/value addressed as `.element`, `.key`
+/// and `.value`.
+fn map_schema(schema: pb::Schema) -> Result<StructType, String> {
+    map_struct_type(schema.fields, None)
+}
+
+fn map_struct_type(
+    fields: Vec<pb::StructField>,
+    parent: Option<&str>,
+) -> Result<StructType, String> {
+    let fields = fields
+        .into_iter()
+        .map(|field| {
+            if field.name.is_empty() {
+                return Err(match parent {
+                    Some(parent) => format!("schema field `{parent}` has a field with no name"),
+                    None => "schema has a field with no name".to_string(),
+                });
+            }
+            let path = match parent {
+                Some(parent) => format!("{parent}.{}", field.name),
+                None => field.name.clone(),
+            };
+            let data_type = map_data_type(field.data_type, &path)?;
+            let metadata = field
+                .metadata
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = map_field_metadata_value(value).ok_or_else(|| {
+                        format!("schema field `{path}`: metadata `{key}` has no value")
+                    })??;
+                    Ok((key, value))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(StructField::new(field.name, data_type, field.nullable).with_metadata(metadata))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    StructType::try_new(fields).map_err(|e| match parent {
+        Some(parent) => format!("schema field `{parent}`: {e}"),
+        None => format!("schema: {e}"),
+    })
+}
+
+fn map_data_type(data_type: Option<pb::DataType>, path: &str) -> Result<DataType, String> {
+    use pb::data_type::Kind;
+    let kind = data_type
+        .and_then(|data_type| data_type.kind)
+        .ok_or_else(|| format!("schema field `{path}` has no data_type"))?;
+    match kind {
+        Kind::Primitive(primitive) => map_primitive_type(primitive)
+            .ok_or_else(|| format!("schema field `{path}` has an unspecified or unknown type")),
+        Kind::Decimal(decimal) => {
+            let precision = u8::try_from(decimal.precision).unwrap_or(u8::MAX);
+            let scale = u8::try_from(decimal.scale).unwrap_or(u8::MAX);
+            DataType::decimal(precision, scale).map_err(|e| format!("schema field `{path}`: {e}"))
+        }
+        Kind::Array(array) => {
+            let element_type =
+                map_data_type(array.element_type.map(|t| *t), &format!("{path}.element"))?;
+            Ok(ArrayType::new(element_type, array.contains_null).into())
+        }
+        Kind::Map(map) => {
+            let key_type = map_data_type(map.key_type.map(|t| *t), &format!("{path}.key"))?;
+            let value_type = map_data_type(map.value_type.map(|t| *t), &format!("{path}.value"))?;
+            Ok(MapType::new(key_type, value_type, map.value_contains_null).into())
+        }
+        Kind::StructType(schema) => Ok(map_struct_type(schema.fields, Some(path))?.into()),
+    }
+}
+
+fn map_primitive_type(primitive: i32) -> Option<DataType> {
+    let data_type = match pb::PrimitiveType::try_from(primitive).ok()? {
+        pb::PrimitiveType::Unspecified => return None,
+        pb::PrimitiveType::String => DataType::STRING,
+        pb::PrimitiveType::Long => DataType::LONG,
+        pb::PrimitiveType::Integer => DataType::INTEGER,
+        pb::PrimitiveType::Short => DataType::SHORT,
+        pb::PrimitiveType::Byte => DataType::BYTE,
+        pb::PrimitiveType::Float => DataType::FLOAT,
+        pb::PrimitiveType::Double => DataType::DOUBLE,
+        pb::PrimitiveType::Boolean => DataType::BOOLEAN,
+        pb::PrimitiveType::Binary => DataType::BINARY,
+        pb::PrimitiveType::Date => DataType::DATE,
+        pb::PrimitiveType::Timestamp => DataType::TIMESTAMP,
+        pb::PrimitiveType::TimestampNtz => DataType::TIMESTAMP_NTZ,
+        pb::PrimitiveType::Variant => DataType::unshredded_variant(),
+    };
+    Some(data_type)
+}
+
+// `None` for an unset oneof (the caller names the key); a json_value that
+// isn't JSON is the inner error.
+fn map_field_metadata_value(
+    value: pb::FieldMetadataValue,
+) -> Option<Result<MetadataValue, String>> {
+    use pb::field_metadata_value::Value as PbValue;
+    Some(match value.value? {
+        PbValue::NumberValue(n) => Ok(MetadataValue::Number(n)),
+        PbValue::StringValue(s) => Ok(MetadataValue::String(s)),
+        PbValue::BoolValue(b) => Ok(MetadataValue::Boolean(b)),
+        PbValue::JsonValue(json) => serde_json::from_str(&json)
+            .map(MetadataValue::Other)
+            .map_err(|e| format!("field metadata json_value is not JSON: {e}")),
+    })
+}
+
 /// Builds the CommitInfo *action* (one row of ordinary commit-history
 /// metadata written into the log alongside the Add/Remove/etc. actions --
 /// what shows up in Delta's own commit-history API/tooling) from the
@@ -454,10 +757,471 @@ fn map_commit_operation(operation: i32) -> Option<String> {
         pb::CommitOperation::Vacuum => Some("VACUUM".to_string()),
         pb::CommitOperation::Restore => Some("RESTORE".to_string()),
         pb::CommitOperation::Convert => Some("CONVERT".to_string()),
+        pb::CommitOperation::AddFeature => Some("ADD FEATURE".to_string()),
         pb::CommitOperation::Unspecified => None,
     }
 }
 
+/// ListActiveFilesRequest.partition_filters -> delta::partition_filter's
+/// unbound filters. Only the shape is checked here (see PartitionFilter's
+/// own proto comment for the rules); whether each column and value fits
+/// the table is PartitionFilters::bind's job, once the schema is loaded.
+pub fn map_partition_filters(
+    filters: Vec<pb::PartitionFilter>,
+) -> Result<Vec<PartitionFilter>, String> {
+    filters.into_iter().map(map_partition_filter).collect()
+}
+
+/// Which optional parts of each ListActiveFiles AddFile to fill in --
+/// ListActiveFilesRequest.projection, with its unset fields resolved to
+/// their defaults (everything included).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileProjection {
+    pub include_stats: bool,
+    pub include_partition_values: bool,
+    /// `None` is every column the stats cover.
+    pub stats_columns: Option<std::collections::HashSet<String>>,
+}
+
+impl Default for FileProjection {
+    fn default() -> Self {
+        Self {
+            include_stats: true,
+            include_partition_values: true,
+            stats_columns: None,
+        }
+    }
+}
+
+/// ListActiveFilesRequest.projection -> FileProjection. Unset means the
+/// full AddFile, as before projection existed. Column names aren't checked
+/// against the table (stats rarely cover every column anyway, so naming
+/// one without stats just gets nothing for it); only an empty name, or
+/// stats_columns alongside an explicit `include_stats: false`, is
+/// rejected as a request that can't mean what it says.
+pub fn map_file_projection(
+    projection: Option<pb::FileProjection>,
+) -> Result<FileProjection, String> {
+    let Some(projection) = projection else {
+        return Ok(FileProjection::default());
+    };
+    let include_stats = projection.include_stats.unwrap_or(true);
+    if !include_stats && !projection.stats_columns.is_empty() {
+        return Err("projection.stats_columns is set but include_stats is false".to_string());
+    }
+    if projection.stats_columns.iter().any(String::is_empty) {
+        return Err("projection.stats_columns contains an empty column name".to_string());
+    }
+    Ok(FileProjection {
+        include_stats,
+        include_partition_values: projection.include_partition_values.unwrap_or(true),
+        stats_columns: (!projection.stats_columns.is_empty())
+            .then(|| projection.stats_columns.into_iter().collect()),
+    })
+}
+
+fn map_partition_filter(filter: pb::PartitionFilter) -> Result<PartitionFilter, String> {
+    if filter.column.is_empty() {
+        return Err("partition filter is missing its column".to_string());
+    }
+    let predicate = match filter.predicate.ok_or_else(|| {
+        format!(
+            "partition filter on column '{}' has no predicate",
+            filter.column
+        )
+    })? {
+        PbPartitionPredicate::Equals(value) => PartitionPredicate::Equals(value),
+        PbPartitionPredicate::InList(list) => {
+            if list.values.is_empty() {
+                return Err(format!(
+                    "partition filter on column '{}' has an empty in_list",
+                    filter.column
+                ));
+            }
+            PartitionPredicate::In(list.values)
+        }
+        PbPartitionPredicate::Range(range) => {
+            if range.lower.is_none() && range.upper.is_none() {
+                return Err(format!(
+                    "partition filter on column '{}' has a range with neither bound set",
+                    filter.column
+                ));
+            }
+            PartitionPredicate::Range {
+                lower: range.lower.map(|value| RangeBound {
+                    value,
+                    inclusive: range.lower_inclusive,
+                }),
+                upper: range.upper.map(|value| RangeBound {
+                    value,
+                    inclusive: range.upper_inclusive,
+                }),
+            }
+        }
+        PbPartitionPredicate::IsNull(is_null) => PartitionPredicate::IsNull(is_null),
+    };
+    Ok(PartitionFilter {
+        column: filter.column,
+        predicate,
+    })
+}
+
+/// The read-side counterpart to map_commit_info above: one version's
+/// commit-history entry (see delta::history::TableLog::read_commit) in its
+/// GetHistory wire form, the CommitInfo itself via map_commit_info_to_pb
+/// below -- plus the raw recorded strings for exactly the cases that
+/// mapping can't represent (see GetHistoryResponse's own field comments).
+pub fn map_history_entry_to_pb(
+    version: u64,
+    timestamp: i64,
+    commit_info: Option<CommitInfo>,
+) -> pb::GetHistoryResponse {
+    // u64 -> i64: see grpc::server's matching comment on
+    // snapshot.version().
+    let version = version as i64;
+    let Some(commit_info) = commit_info else {
+        return pb::GetHistoryResponse {
+            version,
+            timestamp,
+            commit_info: None,
+            operation: String::new(),
+            raw_user_metadata: None,
+        };
+    };
+
+    let operation = commit_info.operation.clone().unwrap_or_default();
+    let raw_user_metadata = map_user_metadata_to_pb(commit_info.user_metadata.clone()).err();
+
+    pb::GetHistoryResponse {
+        version,
+        timestamp,
+        commit_info: Some(map_commit_info_to_pb(commit_info)),
+        operation,
+        raw_user_metadata,
+    }
+}
+
+/// DescribeDetail's/ListPartitions' per-partition aggregates, in wire form.
+pub fn map_partition_summary_to_pb(summary: PartitionSummary) -> pb::PartitionDetail {
+    pb::PartitionDetail {
+        partition_values: map_partition_values_to_pb(summary.values),
+        // u64 -> i64: counts and byte totals are nowhere near i64::MAX.
+        num_files: summary.num_files as i64,
+        size_in_bytes: summary.size_in_bytes as i64,
+        num_records: summary.num_records as i64,
+        num_files_without_record_count: summary.num_files_without_record_count as i64,
+    }
+}
+
+/// Ordered `(column, value)` pairs (see
+/// delta::detail::serialize_partition_values) as pb::PartitionValues, a
+/// null staying distinguishable from an empty string.
+pub fn map_partition_values_to_pb(
+    values: Vec<(String, Option<String>)>,
+) -> Vec<pb::PartitionValue> {
+    values
+        .into_iter()
+        .map(|(column, value)| pb::PartitionValue { column, value })
+        .collect()
+}
+
+/// Inverse of map_action, for GetChanges: one action read back out of a
+/// commit file. `None` for the kernel actions pb::Action has no variant
+/// for (cdc, domainMetadata) -- GetChanges documents that it only
+/// carries the action kinds Commit itself accepts.
+pub fn map_action_to_pb(action: Action) -> Option<pb::Action> {
+    let action = match action {
+        Action::Add(add) => PbAction::Add(map_add_to_pb(add)),
+        Action::Remove(remove) => PbAction::Remove(map_remove_to_pb(remove)),
+        Action::Protocol(protocol) => PbAction::Protocol(map_protocol_to_pb(&protocol)),
+        Action::Metadata(metadata) => PbAction::MetaData(map_metadata_to_pb(&metadata)),
+        Action::CommitInfo(ci) => PbAction::CommitInfo(map_commit_info_to_pb(ci)),
+        Action::Txn(txn) => PbAction::Txn(pb::SetTransaction {
+            app_id: txn.app_id,
+            version: txn.version,
+            last_updated: txn.last_updated,
+        }),
+        Action::Cdc(_) | Action::DomainMetadata(_) => return None,
+    };
+    Some(pb::Action {
+        action: Some(action),
+    })
+}
+
+/// Inverse of map_action's AddFile arm. Unlike map_active_file_to_pb
+/// (which only has a LogicalFileView to work from), a commit file's Add
+/// carries its real data_change and tags, so both come back faithfully.
+/// Partition values fill in both wire fields, as map_active_file_to_pb's
+/// do; with no table metadata at hand to give the partition-column order,
+/// nullable_partition_values is sorted by column name instead.
+fn map_add_to_pb(add: Add) -> pb::AddFile {
+    let mut nullable_partition_values: Vec<_> = add
+        .partition_values
+        .iter()
+        .map(|(column, value)| (column.clone(), value.clone()))
+        .collect();
+    nullable_partition_values.sort();
+    pb::AddFile {
+        path: add.path,
+        size: add.size,
+        modification_time: add.modification_time,
+        partition_values: flatten_optional_values(add.partition_values),
+        data_change: map_data_change_to_pb(add.data_change) as i32,
+        stats: map_stats_json_to_pb(add.stats, None),
+        tags: flatten_optional_values(add.tags.unwrap_or_default()),
+        nullable_partition_values: map_partition_values_to_pb(nullable_partition_values),
+        deletion_vector: add.deletion_vector.map(map_deletion_vector_to_pb),
+        base_row_id: add.base_row_id,
+        default_row_commit_version: add.default_row_commit_version,
+    }
+}
+
+/// Inverse of map_action's RemoveFile arm.
+fn map_remove_to_pb(remove: Remove) -> pb::RemoveFile {
+    pb::RemoveFile {
+        path: remove.path,
+        deletion_timestamp: remove.deletion_timestamp,
+        data_change: map_data_change_to_pb(remove.data_change) as i32,
+        deletion_vector: remove.deletion_vector.map(map_deletion_vector_to_pb),
+    }
+}
+
+/// Inverse of map_deletion_vector.
+fn map_deletion_vector_to_pb(dv: DeletionVectorDescriptor) -> pb::DeletionVectorDescriptor {
+    let storage_type = match dv.storage_type {
+        StorageType::UuidRelativePath => pb::DeletionVectorStorageType::UuidRelativePath,
+        StorageType::Inline => pb::DeletionVectorStorageType::Inline,
+        StorageType::AbsolutePath => pb::DeletionVectorStorageType::AbsolutePath,
+    };
+    pb::DeletionVectorDescriptor {
+        storage_type: storage_type as i32,
+        path_or_inline_dv: dv.path_or_inline_dv,
+        offset: dv.offset,
+        size_in_bytes: dv.size_in_bytes,
+        cardinality: dv.cardinality,
+    }
+}
+
+fn map_data_change_to_pb(data_change: bool) -> pb::DataChange {
+    if data_change {
+        pb::DataChange::True
+    } else {
+        pb::DataChange::False
+    }
+}
+
+// kernel's `HashMap<String, Option<String>>` (partition values, tags)
+// narrowed to the wire's string-only map -- the inverse of map_string_map,
+// lossy for a null value, which comes back as an empty string.
+fn flatten_optional_values(
+    input: std::collections::HashMap<String, Option<String>>,
+) -> std::collections::HashMap<String, String> {
+    input
+        .into_iter()
+        .map(|(k, v)| (k, v.unwrap_or_default()))
+        .collect()
+}
+
+/// Inverse of map_protocol, an absent feature list becoming an empty one.
+/// Shared with grpc::server's build_metadata_and_protocol
+/// (GetTable/ListActiveFiles).
+pub fn map_protocol_to_pb(protocol: &Protocol) -> pb::Protocol {
+    let names = |features: Option<&[TableFeature]>| {
+        features
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    };
+    pb::Protocol {
+        min_reader_version: protocol.min_reader_version(),
+        min_writer_version: protocol.min_writer_version(),
+        reader_features: names(protocol.reader_features()),
+        writer_features: names(protocol.writer_features()),
+    }
+}
+
+/// Inverse of map_metadata, with `schema_string` exactly as recorded in
+/// the log and no structured `schema`. grpc::server's
+/// build_metadata_and_protocol starts from this too, replacing
+/// schema_string with its re-serialized form and adding the structured
+/// one (map_schema_to_pb).
+pub fn map_metadata_to_pb(metadata: &Metadata) -> pb::TableMetadata {
+    pb::TableMetadata {
+        id: metadata.id().to_string(),
+        name: metadata.name().unwrap_or_default().to_string(),
+        description: metadata.description().unwrap_or_default().to_string(),
+        schema_string: metadata.schema_string().clone(),
+        partition_columns: metadata.partition_columns().to_vec(),
+        configuration: metadata.configuration().clone(),
+        created_time: metadata.created_time().unwrap_or_default(),
+        schema: None,
+    }
+}
+
+/// Inverse of map_schema: a parsed table schema as TableMetadata.schema.
+pub fn map_schema_to_pb(schema: &StructType) -> pb::Schema {
+    pb::Schema {
+        fields: schema.fields().map(map_struct_field_to_pb).collect(),
+    }
+}
+
+fn map_struct_field_to_pb(field: &StructField) -> pb::StructField {
+    pb::StructField {
+        name: field.name().clone(),
+        data_type: Some(map_data_type_to_pb(field.data_type())),
+        nullable: field.is_nullable(),
+        metadata: field
+            .metadata()
+            .iter()
+            .map(|(key, value)| (key.clone(), map_field_metadata_value_to_pb(value)))
+            .collect(),
+    }
+}
+
+fn map_data_type_to_pb(data_type: &DataType) -> pb::DataType {
+    use pb::data_type::Kind;
+    let kind = match data_type {
+        DataType::Primitive(primitive) => map_primitive_type_to_pb(primitive),
+        // A table schema only ever records the unshredded variant.
+        DataType::Variant(_) => Kind::Primitive(pb::PrimitiveType::Variant as i32),
+        DataType::Array(array) => Kind::Array(Box::new(pb::ArrayType {
+            element_type: Some(Box::new(map_data_type_to_pb(array.element_type()))),
+            contains_null: array.contains_null(),
+        })),
+        DataType::Map(map) => Kind::Map(Box::new(pb::MapType {
+            key_type: Some(Box::new(map_data_type_to_pb(map.key_type()))),
+            value_type: Some(Box::new(map_data_type_to_pb(map.value_type()))),
+            value_contains_null: map.value_contains_null(),
+        })),
+        DataType::Struct(fields) => Kind::StructType(map_schema_to_pb(fields)),
+    };
+    pb::DataType { kind: Some(kind) }
+}
+
+// Decimal is a kernel primitive but its own DataType case on the wire,
+// since it carries precision and scale.
+fn map_primitive_type_to_pb(primitive: &PrimitiveType) -> pb::data_type::Kind {
+    use pb::data_type::Kind;
+    let primitive = match primitive {
+        PrimitiveType::Decimal(decimal) => {
+            return Kind::Decimal(pb::DecimalType {
+                precision: decimal.precision().into(),
+                scale: decimal.scale().into(),
+            })
+        }
+        PrimitiveType::String => pb::PrimitiveType::String,
+        PrimitiveType::Long => pb::PrimitiveType::Long,
+        PrimitiveType::Integer => pb::PrimitiveType::Integer,
+        PrimitiveType::Short => pb::PrimitiveType::Short,
+        PrimitiveType::Byte => pb::PrimitiveType::Byte,
+        PrimitiveType::Float => pb::PrimitiveType::Float,
+        PrimitiveType::Double => pb::PrimitiveType::Double,
+        PrimitiveType::Boolean => pb::PrimitiveType::Boolean,
+        PrimitiveType::Binary => pb::PrimitiveType::Binary,
+        PrimitiveType::Date => pb::PrimitiveType::Date,
+        PrimitiveType::Timestamp => pb::PrimitiveType::Timestamp,
+        PrimitiveType::TimestampNtz => pb::PrimitiveType::TimestampNtz,
+    };
+    Kind::Primitive(primitive as i32)
+}
+
+fn map_field_metadata_value_to_pb(value: &MetadataValue) -> pb::FieldMetadataValue {
+    use pb::field_metadata_value::Value as PbValue;
+    let value = match value {
+        MetadataValue::Number(n) => PbValue::NumberValue(*n),
+        MetadataValue::String(s) => PbValue::StringValue(s.clone()),
+        MetadataValue::Boolean(b) => PbValue::BoolValue(*b),
+        MetadataValue::Other(json) => PbValue::JsonValue(json.to_string()),
+    };
+    pb::FieldMetadataValue { value: Some(value) }
+}
+
+/// Inverse of map_commit_info, mapping a recorded commitInfo back onto
+/// the same pb::CommitInfo Commit accepts by inverting each write-side
+/// helper (map_commit_operation, map_engine_info, map_json_map,
+/// map_user_metadata) -- lossy wherever those are narrower than Delta's
+/// log format: an operation string CommitOperation has no value for is
+/// UNSPECIFIED, and a userMetadata that isn't a JSON object of strings
+/// leaves user_metadata empty.
+fn map_commit_info_to_pb(commit_info: CommitInfo) -> pb::CommitInfo {
+    let idempotency_key = commit_idempotency_key(&commit_info)
+        .unwrap_or_default()
+        .to_string();
+    let (engine_name, engine_version) = map_engine_info_to_pb(commit_info.engine_info);
+    pb::CommitInfo {
+        engine_name,
+        engine_version,
+        operation: map_commit_operation_to_pb(commit_info.operation.as_deref().unwrap_or(""))
+            as i32,
+        operation_parameters: map_json_map_to_pb(commit_info.operation_parameters),
+        user_metadata: map_user_metadata_to_pb(commit_info.user_metadata).unwrap_or_default(),
+        timestamp: commit_info.timestamp.unwrap_or_default(),
+        idempotency_key,
+    }
+}
+
+/// Inverse of map_commit_operation. Anything CommitOperation has no value
+/// for -- delta-rs's own "CREATE TABLE", Spark's "STREAMING UPDATE", etc.
+/// -- is `Unspecified`, the caller keeping the raw string separately.
+fn map_commit_operation_to_pb(operation: &str) -> pb::CommitOperation {
+    match operation {
+        "WRITE" => pb::CommitOperation::Write,
+        "MERGE" => pb::CommitOperation::Merge,
+        "UPDATE" => pb::CommitOperation::Update,
+        "DELETE" => pb::CommitOperation::Delete,
+        "OPTIMIZE" => pb::CommitOperation::Optimize,
+        "VACUUM" => pb::CommitOperation::Vacuum,
+        "RESTORE" => pb::CommitOperation::Restore,
+        "CONVERT" => pb::CommitOperation::Convert,
+        "ADD FEATURE" => pb::CommitOperation::AddFeature,
+        _ => pb::CommitOperation::Unspecified,
+    }
+}
+
+/// Inverse of map_engine_info: split at the first "/". Not a perfect
+/// inverse -- an engine *name* containing "/" was already ambiguous once
+/// joined -- but exact for everything Commit itself writes from a
+/// slash-free name.
+fn map_engine_info_to_pb(engine_info: Option<String>) -> (String, String) {
+    match engine_info {
+        None => (String::new(), String::new()),
+        Some(info) => match info.split_once('/') {
+            Some((name, version)) => (name.to_string(), version.to_string()),
+            None => (info, String::new()),
+        },
+    }
+}
+
+/// Inverse of map_json_map: a JSON string value comes back as the string
+/// itself, anything else (other writers record numbers, booleans, nested
+/// objects here) as its JSON text, since the wire map is string-only.
+fn map_json_map_to_pb(
+    input: Option<std::collections::HashMap<String, Value>>,
+) -> std::collections::HashMap<String, String> {
+    input
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(k, v)| match v {
+            Value::String(s) => (k, s),
+            other => (k, other.to_string()),
+        })
+        .collect()
+}
+
+/// Inverse of map_user_metadata: `Ok` with the map if the recorded string
+/// is a JSON object of strings (always the case for a Commit made through
+/// this service), `Err` with the raw string for anything else some other
+/// writer chose to record there.
+fn map_user_metadata_to_pb(
+    user_metadata: Option<String>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let Some(raw) = user_metadata else {
+        return Ok(std::collections::HashMap::new());
+    };
+    serde_json::from_str(&raw).map_err(|_| raw)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -465,11 +1229,8 @@ mod tests {
 
     #[test]
     fn map_data_change_maps_valid_values() {
-        assert_eq!(map_data_change(pb::DataChange::True as i32).unwrap(), true);
-        assert_eq!(
-            map_data_change(pb::DataChange::False as i32).unwrap(),
-            false
-        );
+        assert!(map_data_change(pb::DataChange::True as i32).unwrap());
+        assert!(!map_data_change(pb::DataChange::False as i32).unwrap());
     }
 
     #[test]
@@ -578,12 +1339,12 @@ mod tests {
 
     #[test]
     fn map_stats_json_to_pb_returns_none_when_absent() {
-        assert!(map_stats_json_to_pb(None).is_none());
+        assert!(map_stats_json_to_pb(None, None).is_none());
     }
 
     #[test]
     fn map_stats_json_to_pb_returns_none_on_malformed_json() {
-        assert!(map_stats_json_to_pb(Some("not json".to_string())).is_none());
+        assert!(map_stats_json_to_pb(Some("not json".to_string()), None).is_none());
     }
 
     #[test]
@@ -596,7 +1357,7 @@ mod tests {
         })
         .to_string();
 
-        let stats = map_stats_json_to_pb(Some(json)).expect("expected stats");
+        let stats = map_stats_json_to_pb(Some(json), None).expect("expected stats");
         assert_eq!(stats.num_records, 42);
         assert_eq!(stats.columns.len(), 4);
 
@@ -650,13 +1411,64 @@ mod tests {
         })
         .to_string();
 
-        let stats = map_stats_json_to_pb(Some(json)).expect("expected stats");
+        let stats = map_stats_json_to_pb(Some(json), None).expect("expected stats");
         let column = &stats.columns["always_null"];
         assert_eq!(column.min_value, None);
         assert_eq!(column.max_value, None);
         assert_eq!(column.null_count, 10);
     }
 
+    #[test]
+    fn map_stats_json_to_pb_keeps_only_the_requested_columns() {
+        let json = serde_json::json!({
+            "numRecords": 5,
+            "minValues": {"id": 1, "name": "a"},
+            "maxValues": {"id": 5, "name": "e"},
+            "nullCount": {"id": 0, "name": 1}
+        })
+        .to_string();
+        let only = ["id".to_string(), "missing".to_string()].into();
+
+        let stats = map_stats_json_to_pb(Some(json), Some(&only)).expect("expected stats");
+        assert_eq!(stats.num_records, 5);
+        assert_eq!(stats.columns.keys().collect::<Vec<_>>(), vec!["id"]);
+    }
+
+    #[test]
+    fn map_file_projection_defaults_to_everything_and_rejects_contradictions() {
+        assert_eq!(
+            map_file_projection(None).unwrap(),
+            FileProjection::default()
+        );
+        assert_eq!(
+            map_file_projection(Some(pb::FileProjection::default())).unwrap(),
+            FileProjection::default(),
+            "unset fields mean included"
+        );
+
+        let no_stats = map_file_projection(Some(pb::FileProjection {
+            include_stats: Some(false),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert!(!no_stats.include_stats);
+        assert!(no_stats.include_partition_values);
+
+        for bad in [
+            pb::FileProjection {
+                include_stats: Some(false),
+                stats_columns: vec!["id".to_string()],
+                ..Default::default()
+            },
+            pb::FileProjection {
+                stats_columns: vec![String::new()],
+                ..Default::default()
+            },
+        ] {
+            assert!(map_file_projection(Some(bad.clone())).is_err(), "{bad:?}");
+        }
+    }
+
     #[test]
     fn map_file_stats_and_map_stats_json_to_pb_round_trip() {
         let mut columns = HashMap::new();
@@ -674,7 +1486,7 @@ mod tests {
         };
 
         let json = map_file_stats(Some(original.clone())).expect("expected json");
-        let round_tripped = map_stats_json_to_pb(Some(json)).expect("expected stats");
+        let round_tripped = map_stats_json_to_pb(Some(json), None).expect("expected stats");
 
         assert_eq!(round_tripped.num_records, original.num_records);
         assert_eq!(round_tripped.columns["id"], original.columns["id"]);
@@ -682,8 +1494,7 @@ mod tests {
 
     #[test]
     fn map_active_file_to_pb_maps_every_field() {
-        let mut partition_values = HashMap::new();
-        partition_values.insert("region".to_string(), Some("US".to_string()));
+        let partition_values = vec![("region".to_string(), Some("US".to_string()))];
 
         let pb_add = map_active_file_to_pb(
             "part-0.parquet".to_string(),
@@ -694,6 +1505,8 @@ mod tests {
                 serde_json::json!({"numRecords": 5, "minValues": {}, "maxValues": {}, "nullCount": {}})
                     .to_string(),
             ),
+            None,
+            None,
         );
 
         assert_eq!(pb_add.path, "part-0.parquet");
@@ -709,14 +1522,330 @@ mod tests {
     }
 
     #[test]
-    fn map_active_file_to_pb_treats_null_partition_value_as_empty_string() {
-        let mut partition_values = HashMap::new();
-        partition_values.insert("region".to_string(), None);
+    fn map_active_file_to_pb_reports_a_null_partition_value_in_both_fields() {
+        let partition_values = vec![
+            ("region".to_string(), None),
+            ("day".to_string(), Some("2026-10-18".to_string())),
+        ];
 
-        let pb_add =
-            map_active_file_to_pb("part-0.parquet".to_string(), 1, 0, partition_values, None);
+        let pb_add = map_active_file_to_pb(
+            "part-0.parquet".to_string(),
+            1,
+            0,
+            partition_values,
+            None,
+            None,
+            None,
+        );
 
         assert_eq!(pb_add.partition_values.get("region"), Some(&String::new()));
+        assert_eq!(
+            pb_add.nullable_partition_values,
+            vec![
+                pb::PartitionValue {
+                    column: "region".to_string(),
+                    value: None,
+                },
+                pb::PartitionValue {
+                    column: "day".to_string(),
+                    value: Some("2026-10-18".to_string()),
+                },
+            ]
+        );
         assert!(pb_add.stats.is_none());
     }
+
+    #[test]
+    fn map_action_reads_nullable_partition_values_and_rejects_ambiguity() {
+        let add = |map: &[(&str, &str)], list: &[(&str, Option<&str>)]| pb::Action {
+            action: Some(PbAction::Add(pb::AddFile {
+                path: "part-0.parquet".to_string(),
+                partition_values: map
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                nullable_partition_values: list
+                    .iter()
+                    .map(|(column, value)| pb::PartitionValue {
+                        column: column.to_string(),
+                        value: value.map(str::to_string),
+                    })
+                    .collect(),
+                data_change: pb::DataChange::True as i32,
+                ..Default::default()
+            })),
+        };
+
+        let Action::Add(kernel) =
+            map_action(add(&[], &[("region", None), ("day", Some("2026-10-18"))])).unwrap()
+        else {
+            panic!("expected an Add");
+        };
+        assert_eq!(kernel.partition_values["region"], None);
+        assert_eq!(
+            kernel.partition_values["day"].as_deref(),
+            Some("2026-10-18")
+        );
+
+        let both = map_action(add(&[("day", "2026-10-18")], &[("region", None)])).unwrap_err();
+        assert!(both.contains("not both"), "{both}");
+        let twice = map_action(add(&[], &[("region", None), ("region", Some("US"))])).unwrap_err();
+        assert!(twice.contains("'region' appears twice"), "{twice}");
+    }
+
+    #[test]
+    fn map_history_entry_to_pb_round_trips_a_commit_info_written_by_commit() {
+        let mut operation_parameters = HashMap::new();
+        operation_parameters.insert("predicate".to_string(), "id > 5".to_string());
+        let mut user_metadata = HashMap::new();
+        user_metadata.insert("job".to_string(), "nightly".to_string());
+        let original = pb::CommitInfo {
+            engine_name: "spark".to_string(),
+            engine_version: "3.5.0".to_string(),
+            operation: pb::CommitOperation::Delete as i32,
+            operation_parameters,
+            user_metadata,
+            timestamp: 1_700_000_000_000,
+            idempotency_key: String::new(),
+        };
+
+        let written = map_commit_info(original.clone()).expect("expected commit info");
+        let entry = map_history_entry_to_pb(3, 1_700_000_000_001, Some(written));
+
+        assert_eq!(entry.version, 3);
+        assert_eq!(entry.timestamp, 1_700_000_000_001);
+        assert_eq!(entry.operation, "DELETE");
+        assert!(entry.raw_user_metadata.is_none());
+        assert_eq!(entry.commit_info, Some(original));
+    }
+
+    #[test]
+    fn map_history_entry_to_pb_keeps_what_the_wire_message_cannot_represent() {
+        let mut params = HashMap::new();
+        params.insert("mode".to_string(), Value::String("Append".to_string()));
+        params.insert("numFiles".to_string(), serde_json::json!(2));
+        let commit_info = CommitInfo {
+            operation: Some("CREATE TABLE".to_string()),
+            operation_parameters: Some(params),
+            engine_info: Some("delta-rs:0.32.4".to_string()),
+            user_metadata: Some("not json".to_string()),
+            ..Default::default()
+        };
+
+        let entry = map_history_entry_to_pb(0, 42, Some(commit_info));
+        let pb_info = entry.commit_info.expect("expected commit info");
+
+        assert_eq!(entry.operation, "CREATE TABLE");
+        assert_eq!(pb_info.operation, pb::CommitOperation::Unspecified as i32);
+        assert_eq!(pb_info.operation_parameters["mode"], "Append");
+        assert_eq!(pb_info.operation_parameters["numFiles"], "2");
+        assert_eq!(pb_info.engine_name, "delta-rs:0.32.4");
+        assert_eq!(pb_info.engine_version, "");
+        assert!(pb_info.user_metadata.is_empty());
+        assert_eq!(entry.raw_user_metadata.as_deref(), Some("not json"));
+        assert_eq!(pb_info.timestamp, 0);
+    }
+
+    #[test]
+    fn map_history_entry_to_pb_leaves_commit_info_unset_when_absent() {
+        let entry = map_history_entry_to_pb(7, 42, None);
+        assert_eq!(entry.version, 7);
+        assert!(entry.commit_info.is_none());
+        assert!(entry.operation.is_empty());
+    }
+
+    #[test]
+    fn map_action_to_pb_maps_an_add_with_its_recorded_data_change_and_tags() {
+        let mut tags = HashMap::new();
+        tags.insert("origin".to_string(), Some("backfill".to_string()));
+        let add = Add {
+            path: "part-0.parquet".to_string(),
+            size: 10,
+            modification_time: 5,
+            partition_values: HashMap::from([("region".to_string(), None)]),
+            data_change: false,
+            stats: Some(serde_json::json!({"numRecords": 3}).to_string()),
+            tags: Some(tags),
+            ..Default::default()
+        };
+
+        let Some(pb::Action {
+            action: Some(PbAction::Add(pb_add)),
+        }) = map_action_to_pb(Action::Add(add))
+        else {
+            panic!("expected an AddFile");
+        };
+        assert_eq!(pb_add.path, "part-0.parquet");
+        assert_eq!(pb_add.data_change, pb::DataChange::False as i32);
+        assert_eq!(pb_add.tags["origin"], "backfill");
+        assert_eq!(pb_add.partition_values["region"], "");
+        assert_eq!(pb_add.nullable_partition_values[0].value, None);
+        assert_eq!(pb_add.stats.expect("expected stats").num_records, 3);
+    }
+
+    #[test]
+    fn map_action_to_pb_round_trips_a_remove() {
+        let original = pb::RemoveFile {
+            path: "part-0.parquet".to_string(),
+            deletion_timestamp: Some(7),
+            data_change: pb::DataChange::True as i32,
+            deletion_vector: Some(pb::DeletionVectorDescriptor {
+                storage_type: pb::DeletionVectorStorageType::UuidRelativePath as i32,
+                path_or_inline_dv: "ab^-aqEH.-t@S}K{vb[*k^".to_string(),
+                offset: Some(4),
+                size_in_bytes: 40,
+                cardinality: 6,
+            }),
+        };
+        let kernel = map_action(pb::Action {
+            action: Some(PbAction::Remove(original.clone())),
+        })
+        .expect("expected a valid remove");
+
+        let Some(pb::Action {
+            action: Some(PbAction::Remove(round_tripped)),
+        }) = map_action_to_pb(kernel)
+        else {
+            panic!("expected a RemoveFile");
+        };
+        assert_eq!(round_tripped, original);
+    }
+
+    #[test]
+    fn map_deletion_vector_rejects_malformed_descriptors() {
+        let valid = pb::DeletionVectorDescriptor {
+            storage_type: pb::DeletionVectorStorageType::Inline as i32,
+            path_or_inline_dv: "wi5b=000010000siXQKl0rr91000f55c8Xg0@@D72lkbi5=-{L".to_string(),
+            offset: None,
+            size_in_bytes: 40,
+            cardinality: 6,
+        };
+        assert!(map_deletion_vector(Some(valid.clone())).unwrap().is_some());
+
+        for (bad, expected) in [
+            (
+                pb::DeletionVectorDescriptor {
+                    storage_type: pb::DeletionVectorStorageType::Unspecified as i32,
+                    ..valid.clone()
+                },
+                "unspecified",
+            ),
+            (
+                pb::DeletionVectorDescriptor {
+                    path_or_inline_dv: String::new(),
+                    ..valid.clone()
+                },
+                "empty",
+            ),
+            (
+                pb::DeletionVectorDescriptor {
+                    offset: Some(1),
+                    ..valid.clone()
+                },
+                "inline",
+            ),
+            (
+                pb::DeletionVectorDescriptor {
+                    cardinality: -1,
+                    ..valid.clone()
+                },
+                "negative",
+            ),
+        ] {
+            let err = map_deletion_vector(Some(bad)).unwrap_err();
+            assert!(err.contains(expected), "expected {expected:?} in: {err}");
+        }
+    }
+
+    #[test]
+    fn map_action_to_pb_skips_actions_the_wire_message_has_no_variant_for() {
+        let domain_metadata = Action::DomainMetadata(deltalake::kernel::DomainMetadata {
+            domain: "app".to_string(),
+            configuration: "{}".to_string(),
+            removed: false,
+        });
+        assert!(map_action_to_pb(domain_metadata).is_none());
+    }
+
+    #[test]
+    fn map_schema_round_trips_through_the_kernel_schema() {
+        let kernel = StructType::try_new([
+            StructField::not_null("id", DataType::LONG).with_metadata([
+                ("comment", MetadataValue::String("key".to_string())),
+                (
+                    "extra",
+                    MetadataValue::Other(serde_json::json!({"nested": [1.5]})),
+                ),
+            ]),
+            StructField::nullable("price", DataType::decimal(12, 4).unwrap()),
+            StructField::nullable(
+                "scores",
+                MapType::new(
+                    DataType::STRING,
+                    ArrayType::new(DataType::DOUBLE, false),
+                    true,
+                ),
+            ),
+            StructField::nullable(
+                "address",
+                StructType::try_new([StructField::nullable("city", DataType::STRING)]).unwrap(),
+            ),
+        ])
+        .unwrap();
+
+        let wire = map_schema_to_pb(&kernel);
+        assert_eq!(map_schema(wire).unwrap(), kernel);
+    }
+
+    #[test]
+    fn map_schema_rejects_an_unspecified_primitive_by_path() {
+        let schema = pb::Schema {
+            fields: vec![pb::StructField {
+                name: "tags".to_string(),
+                data_type: Some(pb::DataType {
+                    kind: Some(pb::data_type::Kind::Array(Box::new(pb::ArrayType {
+                        element_type: Some(Box::new(pb::DataType {
+                            kind: Some(pb::data_type::Kind::Primitive(
+                                pb::PrimitiveType::Unspecified as i32,
+                            )),
+                        })),
+                        contains_null: true,
+                    }))),
+                }),
+                nullable: true,
+                metadata: HashMap::new(),
+            }],
+        };
+        let err = map_schema(schema).unwrap_err();
+        assert!(err.contains("`tags.element`"), "got: {err}");
+    }
+
+    #[test]
+    fn map_protocol_writes_feature_lists_only_at_their_versions() {
+        let modern = pb::Protocol {
+            min_reader_version: 3,
+            min_writer_version: 7,
+            reader_features: vec!["columnMapping".to_string()],
+            writer_features: vec!["columnMapping".to_string(), "someFutureFeature".to_string()],
+        };
+        let protocol = map_protocol(modern.clone()).unwrap();
+        assert_eq!(map_protocol_to_pb(&protocol), modern);
+
+        // Version 3/7 with no features still gets (empty) lists; a legacy
+        // protocol gets none.
+        let empty = map_protocol(pb::Protocol {
+            min_reader_version: 3,
+            min_writer_version: 7,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(empty.reader_features(), Some(&[][..]));
+        let legacy = map_protocol(pb::Protocol {
+            min_reader_version: 1,
+            min_writer_version: 2,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(legacy.reader_features().is_none() && legacy.writer_features().is_none());
+    }
 }
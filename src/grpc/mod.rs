@@ -2,9 +2,16 @@
 //! generated from proto/delta_txn.proto and owns the actual request
 //! handling; `mapping` translates between the generated protobuf types and
 //! delta-rs's own kernel::Action/Add/etc. types (both directions -- proto
-//! to kernel for Commit, kernel to proto for ListActiveFiles); `auth`
-//! provides the optional API-key request interceptor main.rs wires in.
+//! to kernel for Commit, kernel to proto for ListActiveFiles);
+//! `continuation` defines the opaque token a ListActiveFiles stream can be
+//! resumed from; `staging` holds interactive transactions' staged actions
+//! between BeginTransaction and CommitTransaction; `commit_stream` bounds
+//! what in-flight CommitStream uploads buffer; `auth` provides the
+//! optional API-key request interceptor main.rs wires in.
 
 pub mod auth;
+pub mod commit_stream;
+pub mod continuation;
 pub mod mapping;
 pub mod server;
+pub mod staging;
@@ -1,19 +1,35 @@
 //! The DeltaTxnService gRPC implementation itself: GetTable (unary table
-//! inspection), Commit (unary, optimistic-concurrency-checked writes), and
-//! ListActiveFiles (server-streaming active-file listing). See each
+//! inspection), DescribeDetail (unary table-level aggregates), Commit
+//! (unary, optimistic-concurrency-checked writes), CommitStream (the same,
+//! with the actions client-streamed in chunks), EnableTableFeature
+//! (unary protocol upgrades), GetTransactionVersion (unary SetTransaction
+//! lookups), BeginTransaction/StageActions/CommitTransaction/
+//! AbortTransaction (unary staged transactions -- see grpc::staging),
+//! MultiTableCommit (unary atomically decided commits across tables -- see
+//! delta::multi_table), ListActiveFiles
+//! (server-streaming active-file listing), ListPartitions
+//! (server-streaming per-partition aggregates), GetVersionAtTimestamp
+//! (unary timestamp-to-version resolution), GetHistory (server-streaming
+//! commit history), GetChanges (server-streaming per-version log actions),
+//! and WatchTable (server-streaming new-version announcements). See each
 //! method's own doc comment for the specifics; this file's shared state
 //! (DeltaTxnGrpcServer) and helpers (build_metadata_and_protocol,
-//! map_open_or_snapshot_error) are what all three have in common.
+//! map_open_or_snapshot_error, open_table_for_read) are what they have in
+//! common.
 
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
-use deltalake::kernel::scalars::ScalarExt;
+use deltalake::kernel::{Metadata, StructType};
+use deltalake::protocol::DeltaOperation;
 use deltalake::table::state::DeltaTableState;
-use deltalake::{ensure_table_uri, DeltaTableError};
-use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
-use tonic::{Request, Response, Status};
-use tracing::warn;
+use deltalake::{ensure_table_uri, DeltaTable, DeltaTableError};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::codegen::Bytes;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{info, warn};
 use url::Url;
 
 use crate::config::storage::{
@@ -21,20 +37,59 @@ use crate::config::storage::{
 };
 use crate::delta::errors::DeltaTxnError;
 use crate::delta::{
-    commit::{commit_actions, create_table, find_metadata, find_protocol},
-    table::{open_table, table_exists},
+    commit::{
+        check_writable_protocol, commit_actions, commit_with_operation, create_table,
+        find_metadata, find_protocol, validate_actions, validate_create,
+    },
+    data_skipping::files_matching_predicate,
+    deletion_vectors::check_deletion_vectors,
+    detail::{serialize_partition_values, summarize_files, table_features},
+    history::{last_checkpoint_hint, version_at_timestamp, TableLog},
+    idempotency::{
+        find_idempotent_commit, idempotency_metadata, idempotency_search_start,
+        DEFAULT_IDEMPOTENCY_LOOKBACK,
+    },
+    multi_table::{
+        landed_actions, landed_version, removed_files, undone_version, CoordinatorLog, Lease,
+        MultiTableIntent, TableIntent, LEASE_TTL,
+    },
+    partition_filter::{PartitionFilter, PartitionFilters},
+    partition_values::check_add_partition_values,
+    row_tracking::{assign_row_ids, check_row_tracking, row_tracking_supported},
+    schema_evolution::{check_schema_evolution, SchemaEvolutionPolicy},
+    set_transaction::check_transactions,
+    table::{open_table, open_table_at_version, table_exists},
+    table_features::{check_protocol_upgrade, parse_table_feature, protocol_with_feature},
 };
-use crate::grpc::mapping::{map_actions, map_active_file_to_pb};
+use crate::grpc::commit_stream::{CommitStreamLimits, CommitStreamUploads};
+use crate::grpc::continuation::ActiveFilesCursor;
+use crate::grpc::mapping::{
+    map_action_to_pb, map_actions, map_active_file_to_pb, map_file_projection,
+    map_history_entry_to_pb, map_metadata_to_pb, map_partition_filters,
+    map_partition_summary_to_pb, map_protocol_to_pb, map_schema_to_pb, FileProjection,
+};
+use crate::grpc::staging::{Checkout, CheckoutOutcome, StagedTransactionManager, StagingLimits};
 use crate::locking::table_lock::TableLockManager;
+use crate::watch::table_watch::TableWatchManager;
 
-// How many files each ListActiveFilesBatch message carries. Large enough
+// How many files each ListActiveFilesBatch message carries (and, by the
+// same reasoning, how many actions each GetChangesBatch and partitions
+// each ListPartitionsBatch does). Large enough
 // that per-message gRPC framing overhead is negligible even for a
 // million-file table, small enough that memory use per in-flight message
 // stays modest and a client starts seeing files promptly instead of
 // waiting for one giant batch.
 const FILE_BATCH_SIZE: usize = 1000;
 
-// Backpressure-bounded: the sender task in list_active_files() only gets
+// How many partitions DescribeDetailResponse.partitions carries at most --
+// a unary response has to fit under gRPC's 4 MiB message limit however
+// many partitions the table has, and ListPartitions already streams the
+// full list for anyone who needs every one. DescribeDetailResponse's
+// num_partitions still counts them all.
+const DESCRIBE_DETAIL_MAX_PARTITIONS: usize = 1000;
+
+// Backpressure-bounded: the sender task in list_active_files()/
+// list_partitions()/get_history()/get_changes()/watch_table() only gets
 // this far ahead of whatever the client has actually consumed off the
 // stream.
 const STREAM_CHANNEL_CAPACITY: usize = 4;
@@ -42,11 +97,29 @@ const STREAM_CHANNEL_CAPACITY: usize = 4;
 type ListActiveFilesResultStream =
     Pin<Box<dyn Stream<Item = Result<ListActiveFilesResponse, Status>> + Send>>;
 
+type ListPartitionsResultStream =
+    Pin<Box<dyn Stream<Item = Result<ListPartitionsResponse, Status>> + Send>>;
+
+type GetHistoryResultStream =
+    Pin<Box<dyn Stream<Item = Result<GetHistoryResponse, Status>> + Send>>;
+
+type GetChangesResultStream =
+    Pin<Box<dyn Stream<Item = Result<GetChangesResponse, Status>> + Send>>;
+
+type WatchTableResultStream =
+    Pin<Box<dyn Stream<Item = Result<WatchTableResponse, Status>> + Send>>;
+
 // The tonic-prost-build-generated protobuf/gRPC types (request/response
 // messages, the DeltaTxnService server trait, etc.) -- see build.rs for
 // where this actually gets compiled from proto/delta_txn.proto.
 // `pub` so grpc::mapping and main.rs can name these types too, without
 // each needing to `include_proto!` its own (ODR-duplicate) copy.
+//
+// large_enum_variant: a response's `oneof payload` puts a one-off header
+// (table metadata, protocol) beside the many small batches after it.
+// Boxing the header would mean boxing it in generated code every handler
+// builds, to save a few hundred bytes per in-flight message.
+#[allow(clippy::large_enum_variant)]
 pub mod pb {
     tonic::include_proto!("delta.txn.v1");
 }
@@ -54,24 +127,46 @@ pub mod pb {
 use pb::delta_txn_service_server::{DeltaTxnService, DeltaTxnServiceServer};
 use pb::*;
 
-/// The DeltaTxnService implementation. Cheap to clone (all three fields
-/// are either already-Arc'd (TableLockManager) or small/immutable-after-
-/// construction), which matters because tonic clones the service per
-/// connection/request as needed.
+/// The DeltaTxnService implementation. Cheap to clone: every field is
+/// either already shared behind an Arc (TableLockManager,
+/// TableWatchManager, StagedTransactionManager, CommitStreamUploads, the
+/// coordinator log) or small and immutable after construction. That
+/// matters because tonic clones the service per connection and request.
 #[derive(Clone)]
 pub struct DeltaTxnGrpcServer {
     locks: TableLockManager,
+    /// Shared between Commit (which publishes each version it writes) and
+    /// every WatchTable stream (which subscribes) -- see watch::mod.
+    watches: TableWatchManager,
     /// Loaded once at startup (see config::storage::load_storage_options)
     /// and cloned per-call into open_table() -- see that function's own
     /// signature. Not re-read from the environment after construction;
     /// changing AWS_* env vars at runtime has no effect until restart.
     storage_opts: HashMap<String, String>,
     allowed_table_prefixes: Option<Vec<String>>,
+    /// Applied to every Commit carrying a TableMetadata action for an
+    /// existing table -- see with_schema_evolution().
+    schema_evolution: SchemaEvolutionPolicy,
+    /// BeginTransaction's open transactions and their staged actions --
+    /// see grpc::staging and with_staging_limits().
+    staged: StagedTransactionManager,
+    /// What in-flight CommitStream uploads hold -- see grpc::commit_stream
+    /// and with_commit_stream_limits().
+    streams: CommitStreamUploads,
+    /// Where MultiTableCommit records its decisions; `None` disables it --
+    /// see with_coordinator_uri() and delta::multi_table.
+    coordinator: Option<Arc<CoordinatorLog>>,
+    /// How far back a keyed Commit looks for its original at most -- see
+    /// with_idempotency_lookback().
+    idempotency_lookback: u64,
 }
 
 impl DeltaTxnGrpcServer {
     /// Reads DELTA_TXN_ALLOWED_TABLE_PREFIXES/AWS_* from the environment
     /// once (see config::storage) -- called exactly once in main.rs.
+    /// Deliberately not a `Default` impl: reading the process environment
+    /// (and warning about it) is not what a reader expects `default()` to do.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         let allowed_table_prefixes = load_allowed_table_prefixes();
         if allowed_table_prefixes.is_none() {
@@ -99,11 +194,56 @@ impl DeltaTxnGrpcServer {
     ) -> Self {
         Self {
             locks: TableLockManager::default(),
+            watches: TableWatchManager::default(),
             storage_opts,
             allowed_table_prefixes,
+            schema_evolution: SchemaEvolutionPolicy::default(),
+            staged: StagedTransactionManager::default(),
+            streams: CommitStreamUploads::default(),
+            coordinator: None,
+            idempotency_lookback: DEFAULT_IDEMPOTENCY_LOOKBACK,
         }
     }
 
+    /// Replaces the default (additive) schema-evolution policy. Separate
+    /// from with_config() because the policy comes from a fallible env
+    /// read (config::commit::load_schema_evolution_policy) main.rs does
+    /// itself, so a bad value stops startup there instead of here.
+    pub fn with_schema_evolution(mut self, policy: SchemaEvolutionPolicy) -> Self {
+        self.schema_evolution = policy;
+        self
+    }
+
+    /// Replaces the default staged-transaction limits
+    /// (config::commit::load_staging_limits).
+    pub fn with_staging_limits(mut self, limits: StagingLimits) -> Self {
+        self.staged = StagedTransactionManager::new(limits);
+        self
+    }
+
+    /// Replaces the default CommitStream limits
+    /// (config::commit::load_commit_stream_limits).
+    pub fn with_commit_stream_limits(mut self, limits: CommitStreamLimits) -> Self {
+        self.streams = CommitStreamUploads::new(limits);
+        self
+    }
+
+    /// Enables MultiTableCommit, with its coordinator log at
+    /// `coordinator_uri` (config::commit::load_coordinator_uri). Call
+    /// recover_multi_table_commits() before serving.
+    pub fn with_coordinator_uri(mut self, coordinator_uri: Url) -> Result<Self, DeltaTxnError> {
+        let log = CoordinatorLog::open(&coordinator_uri, self.storage_opts.clone())?;
+        self.coordinator = Some(Arc::new(log));
+        Ok(self)
+    }
+
+    /// Replaces DEFAULT_IDEMPOTENCY_LOOKBACK
+    /// (config::commit::load_idempotency_lookback).
+    pub fn with_idempotency_lookback(mut self, versions: u64) -> Self {
+        self.idempotency_lookback = versions;
+        self
+    }
+
     pub fn into_service(self) -> DeltaTxnServiceServer<Self> {
         DeltaTxnServiceServer::new(self)
     }
@@ -123,7 +263,7 @@ impl DeltaTxnGrpcServer {
     }
 
     /// Allowlist-checks and normalizes a client-supplied table_uri --
-    /// shared by all three handlers, which otherwise each needed the exact
+    /// shared by every handler, which otherwise each needed the exact
     /// same three-step sequence.
     ///
     /// The raw, client-supplied string is checked first, but only when it
@@ -158,204 +298,403 @@ impl DeltaTxnGrpcServer {
 
         Ok(normalized_table_uri)
     }
-}
-
-// Shared by get_table() and list_active_files()'s header: converts a
-// loaded snapshot's metadata/protocol into their wire form, including the
-// schema_string parse-and-reserialize step both need identically.
-fn build_metadata_and_protocol(
-    snapshot: &DeltaTableState,
-) -> Result<(TableMetadata, Protocol), Status> {
-    let metadata = snapshot.metadata();
-    let protocol = snapshot.protocol();
-
-    let schema_string = metadata
-        .parse_schema()
-        .map_err(|e| {
-            tracing::error!(error = %e, "failed to parse table schema");
-            Status::internal("internal error parsing table schema")
-        })
-        .and_then(|schema| {
-            serde_json::to_string(&schema).map_err(|e| {
-                tracing::error!(error = %e, "failed to serialize table schema");
-                Status::internal("internal error serializing table schema")
-            })
-        })?;
 
-    Ok((
-        TableMetadata {
-            id: metadata.id().to_string(),
-            name: metadata.name().unwrap_or_default().to_string(),
-            description: metadata.description().unwrap_or_default().to_string(),
-            schema_string,
-            partition_columns: metadata.partition_columns().to_vec(),
-            configuration: metadata.configuration().clone(),
-            created_time: metadata.created_time().unwrap_or_default(),
-        },
-        Protocol {
-            min_reader_version: protocol.min_reader_version(),
-            min_writer_version: protocol.min_writer_version(),
-        },
-    ))
-}
+    /// Commits whichever of `intent`'s parts haven't landed yet, in order,
+    /// then removes its record -- for a fresh commit, a retry and recovery
+    /// alike. `tables` are a fresh commit's prepared snapshots. A part
+    /// refused in a way retrying can't fix withdraws the commit if it's
+    /// the first (see MultiTableFinish::Withdrawn) and otherwise marks the
+    /// record failed (see fail_multi_table_commit()) and rolls it back, as
+    /// a record already marked failed is. The caller holds every part's
+    /// table lock and `lease`, renewed before each part so a server that
+    /// lost it stops before committing anything more. Each part's actions
+    /// are moved out of `intent` into its commit.
+    async fn roll_forward_multi_table_commit(
+        &self,
+        log: &CoordinatorLog,
+        intent: &mut MultiTableIntent,
+        mut lease: Lease,
+        tables: Option<Vec<DeltaTable>>,
+    ) -> Result<MultiTableFinish, Status> {
+        let key = intent.idempotency_key.clone();
+        if let Some(failure) = log.failure(&key).await.map_err(Status::from)? {
+            return self
+                .roll_back_multi_table_commit(log, intent, lease, failure)
+                .await;
+        }
+        let mut tables = tables.map(Vec::into_iter);
+        let mut versions = Vec::with_capacity(intent.tables.len());
+        for i in 0..intent.tables.len() {
+            let table = tables.as_mut().and_then(Iterator::next);
+            let actions = std::mem::take(&mut intent.tables[i].actions);
+            let (intent, part) = (&*intent, &intent.tables[i]);
+            if !log
+                .renew(&mut lease, LEASE_TTL)
+                .await
+                .map_err(Status::from)?
+            {
+                return Err(multi_table_commit_taken_over(&intent.id));
+            }
+            match self.apply_part(intent, part, actions, &lease, table).await {
+                Ok(version) => versions.push(version as i64),
+                // Parts land in order, so nothing of the commit has landed
+                // anywhere; nor can any other server's try at this part,
+                // built on the same read version. Withdrawn, as if never
+                // decided.
+                Err(e) if is_terminal_part_failure(&e) && versions.is_empty() => {
+                    log.complete(&key).await.map_err(Status::from)?;
+                    return Ok(MultiTableFinish::Withdrawn(e));
+                }
+                Err(e) if is_terminal_part_failure(&e) => {
+                    let failure = self
+                        .fail_multi_table_commit(log, intent, part, versions.len(), e)
+                        .await?;
+                    return self
+                        .roll_back_multi_table_commit(log, intent, lease, failure)
+                        .await;
+                }
+                Err(e) => {
+                    warn!(
+                        id = %intent.id,
+                        table_uri = %part.table_uri,
+                        error = %e,
+                        "multi-table commit left partly applied"
+                    );
+                    return Err(Status::unavailable(format!(
+                        "multi-table commit {} is decided but only {} of its {} tables are \
+                         committed; a server sharing the coordinator log finishes it once its \
+                         lease expires, or retry with the same idempotency_key",
+                        intent.id,
+                        versions.len(),
+                        intent.tables.len()
+                    )));
+                }
+            }
+        }
+        log.complete(&key).await.map_err(Status::from)?;
+        Ok(MultiTableFinish::Committed(versions))
+    }
 
-// The "table_uri doesn't exist at all" case is caught earlier by every
-// caller's own table_exists() check (returning Status::not_found before
-// this is ever reached) -- NotInitialized is left handled here defensively
-// for whatever narrower "log exists but couldn't be loaded into a usable
-// snapshot" state delta-rs might still produce that isn't captured by
-// table_exists's own is_delta_table_location() probe, not because it's the
-// primary path anymore.
-fn map_open_or_snapshot_error(e: DeltaTableError) -> Status {
-    match e {
-        DeltaTableError::NotInitialized => Status::failed_precondition("table not initialized"),
-        _ => {
-            tracing::error!(error = %e, "failed to load table snapshot");
-            Status::internal("internal error loading table snapshot")
+    /// Commits `part`'s `actions` unless it has already landed, returning
+    /// its version. A conflict may be the part itself, landed by another
+    /// server.
+    async fn apply_part(
+        &self,
+        intent: &MultiTableIntent,
+        part: &TableIntent,
+        mut actions: Vec<deltalake::kernel::Action>,
+        lease: &Lease,
+        table: Option<DeltaTable>,
+    ) -> Result<u64, DeltaTxnError> {
+        let table_uri = parse_intent_table_uri(intent, part)
+            .map_err(|status| DeltaTxnError::CoordinatorFailed(status.message().to_string()))?;
+        let landed = || {
+            landed_version(
+                &table_uri,
+                self.storage_opts.clone(),
+                &intent.id,
+                part.read_version,
+            )
+        };
+        let table = match table {
+            Some(table) => table,
+            None => match landed().await? {
+                Some(version) => return Ok(version),
+                None => {
+                    open_table_at_version(&table_uri, self.storage_opts.clone(), part.read_version)
+                        .await?
+                }
+            },
+        };
+        actions.push(intent.fence(lease));
+        match commit_actions(table, actions, intent.commit_metadata()).await {
+            Ok(version) => {
+                // See apply_commit()'s matching publish().
+                self.watches.publish(table_uri.as_str(), version as u64);
+                Ok(version as u64)
+            }
+            Err(e @ DeltaTxnError::CommitConflict(_)) => match landed().await? {
+                Some(version) => Ok(version),
+                None => Err(e),
+            },
+            Err(e) => Err(e),
         }
     }
-}
 
-// The actual open-table/build-header/stream-files work for
-// list_active_files(), run inside the spawned task (see that method's own
-// comment on why): every failure here becomes the one `Err` item sent
-// before the stream ends, since by the time this runs the RPC has already
-// returned `Ok(Response::new(stream))` to the client.
-async fn stream_active_files(
-    table_uri: Url,
-    storage_opts: HashMap<String, String>,
-    tx: tokio::sync::mpsc::Sender<Result<ListActiveFilesResponse, Status>>,
-) {
-    let result = stream_active_files_inner(table_uri, storage_opts, &tx).await;
-    if let Err(status) = result {
-        let _ = tx.send(Err(status)).await;
+    /// Marks `intent` failed in the coordinator log because `part` was
+    /// refused with `e` after `committed` other parts had landed, returning
+    /// the reason recorded. From here it is only ever rolled back.
+    async fn fail_multi_table_commit(
+        &self,
+        log: &CoordinatorLog,
+        intent: &MultiTableIntent,
+        part: &TableIntent,
+        committed: usize,
+        e: DeltaTxnError,
+    ) -> Result<String, Status> {
+        let failure = format!(
+            "table_uri '{}' refused its part after {} of its {} tables were committed: {}",
+            part.table_uri,
+            committed,
+            intent.tables.len(),
+            Status::from(e).message()
+        );
+        log.mark_failed(&intent.idempotency_key, &failure)
+            .await
+            .map_err(Status::from)?;
+        warn!(id = %intent.id, %failure, "multi-table commit failed partway");
+        Ok(failure)
     }
-}
 
-async fn stream_active_files_inner(
-    table_uri: Url,
-    storage_opts: HashMap<String, String>,
-    tx: &tokio::sync::mpsc::Sender<Result<ListActiveFilesResponse, Status>>,
-) -> Result<(), Status> {
-    if !table_exists(&table_uri, storage_opts.clone())
-        .await
-        .map_err(Status::from)?
-    {
-        return Err(Status::not_found(format!(
-            "table_uri '{table_uri}' does not exist"
-        )));
+    /// Takes every part of failed `intent` that landed back out of its
+    /// table, last first, then marks the record rolled back. Each undo is
+    /// found in its table's log the way a landed part is, so a rollback
+    /// cut short is carried on from where it stopped by whoever holds the
+    /// lease next.
+    async fn roll_back_multi_table_commit(
+        &self,
+        log: &CoordinatorLog,
+        intent: &MultiTableIntent,
+        mut lease: Lease,
+        failure: String,
+    ) -> Result<MultiTableFinish, Status> {
+        let key = &intent.idempotency_key;
+        if log.is_rolled_back(key).await.map_err(Status::from)? {
+            return Ok(MultiTableFinish::RolledBack(failure));
+        }
+        for part in intent.tables.iter().rev() {
+            if !log
+                .renew(&mut lease, LEASE_TTL)
+                .await
+                .map_err(Status::from)?
+            {
+                return Err(multi_table_commit_taken_over(&intent.id));
+            }
+            self.roll_back_part(intent, part, &lease).await?;
+        }
+        log.mark_rolled_back(key).await.map_err(Status::from)?;
+        warn!(id = %intent.id, %failure, "multi-table commit rolled back");
+        Ok(MultiTableFinish::RolledBack(failure))
     }
 
-    let table = open_table(&table_uri, storage_opts)
+    /// Takes `part` back out of its table, unless it never landed or has
+    /// been already: its added files removed and its removed ones added
+    /// back (TableIntent::undo_actions), committed with the fence and
+    /// MULTI_TABLE_ROLLBACK_FIELD so only one server's undo can land.
+    async fn roll_back_part(
+        &self,
+        intent: &MultiTableIntent,
+        part: &TableIntent,
+        lease: &Lease,
+    ) -> Result<(), Status> {
+        let table_uri = parse_intent_table_uri(intent, part)?;
+        let Some(landed) = landed_version(
+            &table_uri,
+            self.storage_opts.clone(),
+            &intent.id,
+            part.read_version,
+        )
         .await
-        .map_err(Status::from)?;
+        .map_err(Status::from)?
+        else {
+            return Ok(());
+        };
+        let undone = || undone_version(&table_uri, self.storage_opts.clone(), &intent.id, landed);
+        if undone().await.map_err(Status::from)?.is_some() {
+            return Ok(());
+        }
+        let landed_actions =
+            landed_actions(&table_uri, self.storage_opts.clone(), &intent.id, landed)
+                .await
+                .map_err(Status::from)?;
+        for _ in 0..MULTI_TABLE_ROLLBACK_ATTEMPTS {
+            let table = open_table(&table_uri, self.storage_opts.clone())
+                .await
+                .map_err(Status::from)?;
+            let mut actions = part
+                .undo_actions(&table, &landed_actions)
+                .map_err(Status::from)?;
+            actions.push(intent.fence(lease));
+            // To delta-rs a restore of those files to how they were at the
+            // part's read version -- which an appendOnly table allows too.
+            let operation = DeltaOperation::Restore {
+                version: Some(part.read_version),
+                datetime: None,
+            };
+            match commit_with_operation(table, actions, operation, intent.rollback_metadata()).await
+            {
+                Ok(version) => {
+                    // See apply_commit()'s matching publish().
+                    self.watches.publish(table_uri.as_str(), version as u64);
+                    return Ok(());
+                }
+                // Another writer's commit in between, or another server's
+                // undo of this part -- one whose lease lapsed mid-commit.
+                Err(DeltaTxnError::CommitConflict(_)) => {
+                    if undone().await.map_err(Status::from)?.is_some() {
+                        return Ok(());
+                    }
+                }
+                Err(e) => return Err(Status::from(e)),
+            }
+        }
+        Err(Status::unavailable(format!(
+            "multi-table commit {} is being rolled back but table_uri '{}' kept moving; a \
+             server sharing the coordinator log carries on once its lease expires, or retry \
+             with the same idempotency_key",
+            intent.id, part.table_uri
+        )))
+    }
 
-    let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
-    let (metadata, protocol) = build_metadata_and_protocol(snapshot)?;
+    /// Finishes every unleased MultiTableCommit the coordinator log holds
+    /// -- rolled forward, or back if it failed -- returning how many.
+    /// main.rs calls it before serving and every LEASE_TTL; a record that
+    /// can't be finished is logged and kept.
+    pub async fn recover_multi_table_commits(&self) -> usize {
+        let Some(log) = &self.coordinator else {
+            return 0;
+        };
+        let keys = match log.pending().await {
+            Ok(keys) => keys,
+            Err(e) => {
+                warn!(error = %e, "could not list the multi-table commit coordinator log");
+                return 0;
+            }
+        };
+        let mut finished = 0;
+        for key in keys {
+            match self.recover_multi_table_commit(log, &key).await {
+                Ok(true) => finished += 1,
+                Ok(false) => {}
+                Err(status) => {
+                    warn!(%key, error = %status, "could not recover multi-table commit");
+                }
+            }
+        }
+        finished
+    }
 
-    let header = ListActiveFilesResponse {
-        payload: Some(list_active_files_response::Payload::Header(
-            ListActiveFilesHeader {
-                // delta-rs's Snapshot::version() returns u64; the wire
-                // contract (delta_txn.proto) uses int64 throughout, and a
-                // real table version never approaches i64::MAX.
-                version: snapshot.version() as i64,
-                metadata: Some(metadata),
-                protocol: Some(protocol),
-            },
-        )),
-    };
-    if tx.send(Ok(header)).await.is_err() {
-        return Ok(()); // client already gone; nothing left to report.
+    // One record of recover_multi_table_commits(): `false` if there was
+    // nothing to do (held by another server, or already rolled back).
+    async fn recover_multi_table_commit(
+        &self,
+        log: &CoordinatorLog,
+        key: &str,
+    ) -> Result<bool, Status> {
+        let Some(mut intent) = log.get(key).await.map_err(Status::from)? else {
+            return Ok(false);
+        };
+        if log.is_rolled_back(key).await.map_err(Status::from)? {
+            return Ok(false);
+        }
+        let Some(lease) = log.claim(key, LEASE_TTL).await.map_err(Status::from)? else {
+            return Ok(false);
+        };
+        let locks = self
+            .locks
+            .locks_for(intent.tables.iter().map(|part| part.table_uri.as_str()));
+        let mut _guards = Vec::with_capacity(locks.len());
+        for lock in &locks {
+            _guards.push(lock.lock().await);
+        }
+        match self
+            .roll_forward_multi_table_commit(log, &mut intent, lease, None)
+            .await?
+        {
+            MultiTableFinish::Committed(versions) => {
+                info!(id = %intent.id, ?versions, "rolled forward multi-table commit");
+            }
+            MultiTableFinish::Withdrawn(e) => {
+                info!(id = %intent.id, error = %e, "withdrew multi-table commit");
+            }
+            MultiTableFinish::RolledBack(failure) => {
+                info!(id = %intent.id, %failure, "rolled back multi-table commit");
+            }
+        }
+        Ok(true)
     }
 
-    let log_store = table.log_store();
-    let eager_snapshot = snapshot.snapshot();
-    let mut file_stream = eager_snapshot.file_views(log_store.as_ref(), None);
+    /// The checks a commit to an existing table must pass against `table`,
+    /// shared by apply_commit() and multi_table_commit()'s prepare step.
+    async fn check_existing_table_commit(
+        &self,
+        table: &DeltaTable,
+        actions: &[deltalake::kernel::Action],
+        expected_version: Option<i64>,
+    ) -> Result<(), Status> {
+        if let Some(expected) = expected_version {
+            // delta-rs's DeltaTable::version() returns u64; expected/actual
+            // (and the wire contract, delta_txn.proto) are int64 -- see the
+            // matching comment on snapshot.version() below.
+            let current = table
+                .version()
+                .ok_or_else(|| Status::failed_precondition("table not initialized"))?
+                as i64;
+            if current != expected {
+                return Err(Status::from(DeltaTxnError::VersionConflict {
+                    expected,
+                    actual: current,
+                }));
+            }
+        }
 
-    let mut batch: Vec<AddFile> = Vec::with_capacity(FILE_BATCH_SIZE);
-    while let Some(file_result) = file_stream.next().await {
-        let file_view = file_result.map_err(|e| {
-            tracing::error!(error = %e, "failed while streaming active files");
-            Status::internal("internal error streaming active files")
-        })?;
-
-        // LogicalFileView::partition_values() -> Option<StructData> has no
-        // convenient accessor of its own (deltalake-core's maintainers say
-        // as much in their own internal StructDataExt doc comment) --
-        // walking .fields()/.values() in lockstep and serializing each
-        // non-null Scalar is exactly what deltalake-core's own (private)
-        // LogicalFileView::partition_values_map() helper does internally;
-        // replicated here since that helper isn't public.
-        let partition_values = file_view
-            .partition_values()
-            .map(|data| {
-                data.fields()
-                    .iter()
-                    .zip(data.values().iter())
-                    .map(|(field, value)| {
-                        (
-                            field.name().to_string(),
-                            if value.is_null() {
-                                None
-                            } else {
-                                Some(value.serialize())
-                            },
-                        )
-                    })
-                    .collect()
+        // Against the snapshot just opened under the lock, so the schema
+        // checked is the one this commit replaces -- short of another
+        // replica committing in between, which CommitBuilder's own
+        // metadata-change conflict check then catches. The commit's own
+        // new metadata, if it has one, is also what its adds' partition
+        // values are checked against; otherwise the current metadata.
+        let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
+        let new_metadata = find_metadata(actions)
+            .map(|metadata| {
+                parse_commit_schema(metadata).map(|schema| (schema, metadata.partition_columns()))
             })
-            .unwrap_or_default();
-
-        batch.push(map_active_file_to_pb(
-            file_view.path().to_string(),
-            file_view.size(),
-            file_view.modification_time(),
-            partition_values,
-            file_view.stats(),
-        ));
-
-        if batch.len() >= FILE_BATCH_SIZE {
-            let message = ListActiveFilesResponse {
-                payload: Some(list_active_files_response::Payload::Batch(
-                    ListActiveFilesBatch {
-                        files: std::mem::take(&mut batch),
-                    },
-                )),
-            };
-            if tx.send(Ok(message)).await.is_err() {
-                return Ok(());
+            .transpose()?;
+        match &new_metadata {
+            Some((proposed, partition_columns)) => {
+                check_schema_evolution(snapshot.schema().as_ref(), proposed, self.schema_evolution)
+                    .and_then(|()| check_add_partition_values(actions, proposed, partition_columns))
             }
+            None => check_add_partition_values(
+                actions,
+                snapshot.schema().as_ref(),
+                snapshot.metadata().partition_columns(),
+            ),
         }
-    }
-    if !batch.is_empty() {
-        let message = ListActiveFilesResponse {
-            payload: Some(list_active_files_response::Payload::Batch(
-                ListActiveFilesBatch { files: batch },
-            )),
-        };
-        let _ = tx.send(Ok(message)).await;
-    }
+        .map_err(Status::from)?;
 
-    Ok(())
-}
+        // The commit's own Protocol, if it has one, has to keep everything
+        // the current one supports, and then decides what its actions may
+        // use: deletion vectors, and whether row IDs are assigned at
+        // commit time (see commit::commit_with_row_ids) or must be absent.
+        if let Some(proposed) = find_protocol(actions) {
+            check_protocol_upgrade(snapshot.protocol(), proposed).map_err(Status::from)?;
+        }
+        let protocol = find_protocol(actions).unwrap_or(snapshot.protocol());
+        check_deletion_vectors(actions, protocol)
+            .and_then(|()| check_row_tracking(actions, protocol))
+            .map_err(Status::from)?;
 
-#[tonic::async_trait]
-impl DeltaTxnService for DeltaTxnGrpcServer {
-    /// Atomically applies `req.actions` to the table at `req.table_uri`,
-    /// serialized against every other concurrent Commit for the *same*
-    /// table_uri by TableLockManager (see that type's own doc comment --
-    /// an optimization, not a correctness requirement) and, once past
-    /// that, checked against `expected_version` (this service's own
-    /// pre-commit optimistic-concurrency check) before delta-rs's
-    /// CommitBuilder (see delta::commit::commit_actions, including its
-    /// own doc comment on the operation-type limitation) does the actual
-    /// atomic write with its own independent conflict retry.
-    async fn commit(
+        check_transactions(actions, snapshot, table.log_store().as_ref())
+            .await
+            .map_err(Status::from)?;
+
+        Ok(())
+    }
+
+    /// commit()'s body, shared with commit_stream() once it has assembled
+    /// its chunks into one CommitRequest, and with commit_transaction()
+    /// once it has checked out a staged transaction. A `staged` one's
+    /// actions stand in for `r.actions`, converted from where the checkout
+    /// holds them so they're never copied whole, and its read_version is
+    /// the version it was begun at: the commit is then conflict-checked
+    /// against every version since, rather than only against whatever
+    /// lands between opening the table here and writing.
+    async fn apply_commit(
         &self,
-        req: Request<CommitRequest>,
-    ) -> Result<Response<CommitResponse>, Status> {
-        let r = req.into_inner();
+        r: CommitRequest,
+        staged: Option<&Checkout>,
+    ) -> Result<CommitResponse, Status> {
         let table_uri = r.table_uri;
 
         let normalized_table_uri = self.normalize_and_check_table_uri(&table_uri)?;
@@ -364,7 +703,13 @@ impl DeltaTxnService for DeltaTxnGrpcServer {
         // malformed action list (e.g. an unspecified data_change) is a
         // pure client-input error that doesn't need either a network round
         // trip to storage or the per-table lock held while it's rejected.
-        let actions = map_actions(r.actions).map_err(|e| Status::invalid_argument(e))?;
+        let mut actions = match staged {
+            Some(checkout) => map_actions(checkout.actions().iter().cloned()),
+            None => map_actions(r.actions),
+        }
+        .map_err(Status::invalid_argument)?;
+        let read_version = staged.map(|checkout| checkout.read_version);
+        let idempotency_key = Some(r.idempotency_key.as_str()).filter(|key| !key.is_empty());
 
         // Held across the whole exists-check -> open-table -> version-check
         // -> commit sequence below, not just the commit call itself -- see
@@ -411,89 +756,1700 @@ impl DeltaTxnService for DeltaTxnGrpcServer {
                      include both a Protocol and a TableMetadata action",
                 ));
             };
+            check_add_partition_values(
+                &actions,
+                &parse_commit_schema(&metadata)?,
+                metadata.partition_columns(),
+            )
+            .and_then(|()| check_deletion_vectors(&actions, &protocol))
+            .and_then(|()| check_row_tracking(&actions, &protocol))
+            .and_then(|()| {
+                // A new table's first row IDs start at 0, at version 0.
+                if row_tracking_supported(&protocol) {
+                    assign_row_ids(&mut actions, None, 0)
+                } else {
+                    Ok(())
+                }
+            })
+            .map_err(Status::from)?;
+
+            if r.validate_only {
+                let version = validate_create(
+                    normalized_table_uri,
+                    actions,
+                    protocol,
+                    metadata,
+                    idempotency_metadata(idempotency_key),
+                )
+                .map_err(Status::from)?;
+                return Ok(CommitResponse {
+                    committed_version: version,
+                    idempotent_replay: false,
+                });
+            }
 
+            let watch_key = normalized_table_uri.as_str().to_string();
             let version = create_table(
                 normalized_table_uri,
                 self.storage_opts.clone(),
                 actions,
                 protocol,
                 metadata,
+                idempotency_metadata(idempotency_key),
             )
             .await
             .map_err(Status::from)?;
+            self.watches.publish(&watch_key, version as u64);
 
-            return Ok(Response::new(CommitResponse {
+            return Ok(CommitResponse {
                 committed_version: version,
-            }));
+                idempotent_replay: false,
+            });
         }
 
         let table = open_table(&normalized_table_uri, self.storage_opts.clone())
             .await
             .map_err(Status::from)?;
 
-        if let Some(expected) = r.expected_version {
-            // delta-rs's DeltaTable::version() returns u64; expected/actual
-            // (and the wire contract, delta_txn.proto) are int64 -- see the
-            // matching comment on snapshot.version() below.
-            let current = table
+        // A retry of a commit that already landed -- the create above
+        // included, its retry finding the table existing -- answers with
+        // the original's version before anything else is checked: the
+        // expected_version check in particular would otherwise reject it,
+        // the table having moved on by exactly that commit. Under the lock,
+        // so a retry racing its still-running original on this server
+        // waits for it (one on another replica doesn't -- see
+        // find_idempotent_commit).
+        if let Some(key) = idempotency_key {
+            // A staged transaction's commit can only have landed after the
+            // version it was begun at, same as one with expected_version.
+            let landed_after = r
+                .expected_version
+                .or(read_version.map(|version| version as i64));
+            let latest = table
                 .version()
-                .ok_or_else(|| Status::failed_precondition("table not initialized"))?
-                as i64;
-            if current != expected {
-                return Err(Status::from(DeltaTxnError::VersionConflict {
-                    expected,
-                    actual: current,
-                }));
+                .ok_or_else(|| Status::failed_precondition("table not initialized"))?;
+            let oldest = idempotency_search_start(landed_after, latest, self.idempotency_lookback);
+            if let Some(version) = find_idempotent_commit(
+                &normalized_table_uri,
+                self.storage_opts.clone(),
+                key,
+                oldest,
+            )
+            .await
+            .map_err(Status::from)?
+            {
+                return Ok(CommitResponse {
+                    committed_version: version as i64,
+                    idempotent_replay: true,
+                });
             }
+            // Not found: at most the newest idempotency_lookback versions
+            // were searched, the retry horizon. An original older than that
+            // is only caught by the expected_version check below, so a
+            // retry without one commits again.
         }
 
-        let version = commit_actions(table, actions)
-            .await
-            .map_err(|e| Status::from(e))?;
-
-        Ok(Response::new(CommitResponse {
-            committed_version: version,
-        }))
-    }
-
-    /// Returns a table's current version, metadata (including its schema),
-    /// and protocol -- no file listing (see list_active_files() for that).
-    /// A plain read: opens the table fresh, takes no lock (Delta readers
-    /// never need to block on writers -- see locking::mod's own doc
-    /// comment), and reflects whatever snapshot happens to be visible at
-    /// the moment it's called.
-    async fn get_table(
-        &self,
-        req: Request<GetTableRequest>,
-    ) -> Result<Response<GetTableResponse>, Status> {
-        let r = req.into_inner();
-        let table_uri = r.table_uri;
+        self.check_existing_table_commit(&table, &actions, r.expected_version)
+            .await?;
 
-        let normalized_table_uri = self.normalize_and_check_table_uri(&table_uri)?;
+        // Checked above against the latest snapshot, since that's what the
+        // commit lands on top of; but built on the one a staged transaction
+        // read, so CommitBuilder's conflict checker looks at every commit
+        // since Begin -- a file this transaction removes that one of them
+        // removed first fails it, where an unrelated append doesn't.
+        let table = match read_version {
+            Some(version) if Some(version) != table.version() => {
+                open_table_at_version(&normalized_table_uri, self.storage_opts.clone(), version)
+                    .await
+                    .map_err(Status::from)?
+            }
+            _ => table,
+        };
 
-        if !table_exists(&normalized_table_uri, self.storage_opts.clone())
-            .await
-            .map_err(Status::from)?
-        {
-            return Err(Status::not_found(format!(
-                "table_uri '{normalized_table_uri}' does not exist"
-            )));
+        // Everything above only read the table; a dry run stops short of
+        // the one step that writes.
+        if r.validate_only {
+            let (version, _) =
+                validate_actions(&table, actions, idempotency_metadata(idempotency_key))
+                    .await
+                    .map_err(Status::from)?;
+            return Ok(CommitResponse {
+                committed_version: version,
+                idempotent_replay: false,
+            });
         }
 
-        let table = open_table(&normalized_table_uri, self.storage_opts.clone())
+        let version = commit_actions(table, actions, idempotency_metadata(idempotency_key))
             .await
             .map_err(Status::from)?;
 
-        let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
-        let (metadata, protocol) = build_metadata_and_protocol(snapshot)?;
+        // Announced to this table's WatchTable streams right away rather
+        // than waiting for their poller's next look at the log tail.
+        // committed_version is never negative (it's a u64 version from
+        // delta-rs, cast for the wire), so the cast back is lossless.
+        self.watches
+            .publish(normalized_table_uri.as_str(), version as u64);
 
-        Ok(Response::new(GetTableResponse {
-            // See stream_active_files_inner's matching cast comment above.
-            version: snapshot.version() as i64,
-            metadata: Some(metadata),
-            protocol: Some(protocol),
-        }))
-    }
+        Ok(CommitResponse {
+            committed_version: version,
+            idempotent_replay: false,
+        })
+    }
+}
+
+// Shared by get_table(), describe_detail() and list_active_files()'s
+// header: converts a loaded snapshot's metadata/protocol into their wire
+// form, including the schema_string parse-and-reserialize step all three
+// need identically -- and, from the same parsed schema, its structured
+// form.
+fn build_metadata_and_protocol(
+    snapshot: &DeltaTableState,
+) -> Result<(TableMetadata, Protocol), Status> {
+    let metadata = snapshot.metadata();
+    let protocol = snapshot.protocol();
+
+    let schema = metadata.parse_schema().map_err(|e| {
+        tracing::error!(error = %e, "failed to parse table schema");
+        Status::internal("internal error parsing table schema")
+    })?;
+    let schema_string = serde_json::to_string(&schema).map_err(|e| {
+        tracing::error!(error = %e, "failed to serialize table schema");
+        Status::internal("internal error serializing table schema")
+    })?;
+
+    Ok((
+        TableMetadata {
+            schema_string,
+            schema: Some(map_schema_to_pb(&schema)),
+            ..map_metadata_to_pb(metadata)
+        },
+        map_protocol_to_pb(protocol),
+    ))
+}
+
+// A Commit's TableMetadata schema, for the checks commit() runs before
+// CommitBuilder. Unparseable schema JSON is the client's own input, so
+// INVALID_ARGUMENT rather than whatever delta-rs makes of it later.
+fn parse_commit_schema(metadata: &Metadata) -> Result<StructType, Status> {
+    metadata
+        .parse_schema()
+        .map_err(|e| Status::invalid_argument(format!("schema_string is not a valid schema: {e}")))
+}
+
+// The "table_uri doesn't exist at all" case is caught earlier by every
+// caller's own table_exists() check (returning Status::not_found before
+// this is ever reached) -- NotInitialized is left handled here defensively
+// for whatever narrower "log exists but couldn't be loaded into a usable
+// snapshot" state delta-rs might still produce that isn't captured by
+// table_exists's own is_delta_table_location() probe, not because it's the
+// primary path anymore.
+fn map_open_or_snapshot_error(e: DeltaTableError) -> Status {
+    match e {
+        DeltaTableError::NotInitialized => Status::failed_precondition("table not initialized"),
+        _ => {
+            tracing::error!(error = %e, "failed to load table snapshot");
+            Status::internal("internal error loading table snapshot")
+        }
+    }
+}
+
+// A read request's as_of, validated. Every read request has its own
+// generated `oneof as_of` type with identical variants, so each is taken
+// apart into the (version, timestamp) pair it carries (see as_of_parts!)
+// and checked in one place, ReadAsOf::parse; everything past the handler
+// boundary only deals with this. Checked synchronously in each handler,
+// before anything is opened -- a negative version or timestamp is a
+// malformed request, not something storage needs consulting about.
+#[derive(Debug, Clone, Copy)]
+enum ReadAsOf {
+    // The u64 delta::table::open_table_at_version takes.
+    Version(u64),
+    // Milliseconds since the Unix epoch, resolved to a version by
+    // delta::history::version_at_timestamp once the table is known to
+    // exist.
+    Timestamp(i64),
+}
+
+impl ReadAsOf {
+    // At most one of the two is set, being a oneof's; neither means the
+    // latest version.
+    fn parse(version: Option<i64>, timestamp: Option<i64>) -> Result<Option<Self>, Status> {
+        match (version, timestamp) {
+            (Some(version), _) => u64::try_from(version)
+                .map(|version| Some(Self::Version(version)))
+                .map_err(|_| {
+                    Status::invalid_argument(format!("version must be >= 0, got {version}"))
+                }),
+            (None, Some(timestamp)) => parse_requested_timestamp(timestamp)
+                .map(|timestamp| Some(Self::Timestamp(timestamp))),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+// The (version, timestamp) pair ReadAsOf::parse takes, out of `$request`'s
+// generated `as_of` oneof.
+macro_rules! as_of_parts {
+    ($as_of:expr, $request:ident) => {
+        match $as_of {
+            Some($request::AsOf::Version(version)) => (Some(version), None),
+            Some($request::AsOf::Timestamp(timestamp)) => (None, Some(timestamp)),
+            None => (None, None),
+        }
+    };
+}
+
+// Shared by ReadAsOf::parse and get_version_at_timestamp(), which takes
+// a bare timestamp rather than an as_of selector.
+fn parse_requested_timestamp(timestamp: i64) -> Result<i64, Status> {
+    if timestamp < 0 {
+        return Err(Status::invalid_argument(format!(
+            "timestamp must be >= 0, got {timestamp}"
+        )));
+    }
+    Ok(timestamp)
+}
+
+// The exists check every read path starts with -- see
+// delta::table::table_exists's own doc comment for why it's separate from
+// opening the table.
+async fn ensure_table_exists(
+    table_uri: &Url,
+    storage_opts: HashMap<String, String>,
+) -> Result<(), Status> {
+    if table_exists(table_uri, storage_opts)
+        .await
+        .map_err(Status::from)?
+    {
+        Ok(())
+    } else {
+        Err(Status::not_found(format!(
+            "table_uri '{table_uri}' does not exist"
+        )))
+    }
+}
+
+// Shared by get_table() and stream_active_files_inner(): the
+// exists-check-then-open sequence both read paths need, at either the
+// table's latest version or a time-travel one (see open_table_at_version's
+// own doc comment for how a missing vs. cleaned-up version is told apart,
+// and history::version_at_timestamp's for how a timestamp picks a version).
+async fn open_table_for_read(
+    table_uri: &Url,
+    storage_opts: HashMap<String, String>,
+    as_of: Option<ReadAsOf>,
+) -> Result<DeltaTable, Status> {
+    ensure_table_exists(table_uri, storage_opts.clone()).await?;
+
+    match as_of {
+        Some(ReadAsOf::Version(version)) => {
+            open_table_at_version(table_uri, storage_opts, version).await
+        }
+        Some(ReadAsOf::Timestamp(timestamp)) => {
+            let resolved = version_at_timestamp(table_uri, storage_opts.clone(), timestamp)
+                .await
+                .map_err(Status::from)?;
+            open_table_at_version(table_uri, storage_opts, resolved.version).await
+        }
+        None => open_table(table_uri, storage_opts).await,
+    }
+    .map_err(Status::from)
+}
+
+// ListActiveFilesRequest's options, validated as far as they can be
+// without the table (see list_active_files()): which snapshot to list,
+// which of its files to keep, and where to resume from. Binding the
+// filters and parsing the predicate both need the table's schema, and
+// checking that a continuation token still lines up needs its file list,
+// so those happen in stream_active_files_inner.
+struct ActiveFilesQuery {
+    as_of: Option<ReadAsOf>,
+    partition_filters: Vec<PartitionFilter>,
+    predicate: Option<String>,
+    resume_from: Option<ActiveFilesCursor>,
+    projection: FileProjection,
+}
+
+impl ActiveFilesQuery {
+    // `table_uri` is the request's own, already normalized and checked by
+    // list_active_files() -- a continuation token has to have been issued
+    // for that same table.
+    fn parse(r: ListActiveFilesRequest, table_uri: &Url) -> Result<Self, Status> {
+        let resume_from = if r.continuation_token.is_empty() {
+            None
+        } else {
+            let cursor = ActiveFilesCursor::from_token(&r.continuation_token)
+                .map_err(Status::invalid_argument)?;
+            if cursor.table_uri != table_uri.as_str() {
+                return Err(Status::invalid_argument(
+                    "continuation_token was issued for a different table",
+                ));
+            }
+            if r.as_of.is_some() {
+                return Err(Status::invalid_argument(
+                    "as_of cannot be combined with continuation_token, which already pins the version",
+                ));
+            }
+            Some(cursor)
+        };
+        let as_of = match &resume_from {
+            Some(cursor) => Some(ReadAsOf::Version(cursor.version)),
+            None => {
+                let (version, timestamp) = as_of_parts!(r.as_of, list_active_files_request);
+                ReadAsOf::parse(version, timestamp)?
+            }
+        };
+        Ok(Self {
+            as_of,
+            partition_filters: map_partition_filters(r.partition_filters)
+                .map_err(Status::invalid_argument)?,
+            // Blank means no predicate, same as an unset proto3 string.
+            predicate: Some(r.predicate).filter(|p| !p.trim().is_empty()),
+            resume_from,
+            projection: map_file_projection(r.projection).map_err(Status::invalid_argument)?,
+        })
+    }
+}
+
+// The actual open-table/build-header/stream-files work for
+// list_active_files(), run inside the spawned task (see that method's own
+// comment on why): every failure here becomes the one `Err` item sent
+// before the stream ends, since by the time this runs the RPC has already
+// returned `Ok(Response::new(stream))` to the client.
+async fn stream_active_files(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    query: ActiveFilesQuery,
+    tx: tokio::sync::mpsc::Sender<Result<ListActiveFilesResponse, Status>>,
+) {
+    let result = stream_active_files_inner(table_uri, storage_opts, query, &tx).await;
+    if let Err(status) = result {
+        let _ = tx.send(Err(status)).await;
+    }
+}
+
+async fn stream_active_files_inner(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    query: ActiveFilesQuery,
+    tx: &tokio::sync::mpsc::Sender<Result<ListActiveFilesResponse, Status>>,
+) -> Result<(), Status> {
+    let table = open_table_for_read(&table_uri, storage_opts, query.as_of).await?;
+
+    let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
+    let (metadata, protocol) = build_metadata_and_protocol(snapshot)?;
+
+    // Bound before the header goes out, so a filter or predicate that
+    // doesn't fit this table fails the stream outright rather than after a
+    // header that suggests a listing is coming.
+    let partition_filters = PartitionFilters::bind(
+        query.partition_filters,
+        snapshot.schema().as_ref(),
+        snapshot.metadata().partition_columns(),
+    )
+    .map_err(Status::from)?;
+    // The snapshot's already-loaded file list: listed straight from
+    // memory, in an order that's stable for a given version for as long as
+    // the log that version replays from stays the same -- which is what
+    // lets a continuation token name a position in it.
+    let eager_snapshot = snapshot.snapshot();
+    let log_data = eager_snapshot
+        .try_log_data()
+        .map_err(map_open_or_snapshot_error)?;
+    let version = snapshot.version();
+    let predicate_matches = query
+        .predicate
+        .map(|predicate| files_matching_predicate(eager_snapshot, &predicate))
+        .transpose()
+        .map_err(Status::from)?;
+
+    // A token's last_path must still be the file just before its position.
+    // If it isn't, the version's log now replays differently (a checkpoint
+    // written for it since, say) and the position means nothing anymore --
+    // better to say so than to silently skip or repeat files.
+    let start = match &query.resume_from {
+        None => 0,
+        Some(cursor) => {
+            let position = usize::try_from(cursor.position).unwrap_or(usize::MAX);
+            let still_in_place = log_data
+                .iter()
+                .nth(position - 1)
+                .is_some_and(|file_view| file_view.path() == cursor.last_path.as_str());
+            if !still_in_place {
+                return Err(Status::failed_precondition(format!(
+                    "the file listing of version {version} no longer matches this \
+                     continuation_token; restart the listing without one"
+                )));
+            }
+            position
+        }
+    };
+
+    let header = ListActiveFilesResponse {
+        payload: Some(list_active_files_response::Payload::Header(
+            ListActiveFilesHeader {
+                // delta-rs's Snapshot::version() returns u64; the wire
+                // contract (delta_txn.proto) uses int64 throughout, and a
+                // real table version never approaches i64::MAX.
+                version: snapshot.version() as i64,
+                metadata: Some(metadata),
+                protocol: Some(protocol),
+            },
+        )),
+    };
+    if tx.send(Ok(header)).await.is_err() {
+        return Ok(()); // client already gone; nothing left to report.
+    }
+
+    // Every file streamed is tagged with a continuation token: the pinned
+    // version plus the index just past it in log_data's file list, *before*
+    // the filters/predicate skip any -- so resuming means skipping to that
+    // index and carrying on exactly as the original stream would have.
+    let mut batch: Vec<AddFile> = Vec::with_capacity(FILE_BATCH_SIZE);
+    let mut batch_end = 0;
+    let batch_message = |files: Vec<AddFile>, end: usize| {
+        let last_path = files.last().map(|f| f.path.clone()).unwrap_or_default();
+        let cursor = ActiveFilesCursor::new(table_uri.to_string(), version, end as u64, last_path);
+        ListActiveFilesResponse {
+            payload: Some(list_active_files_response::Payload::Batch(
+                ListActiveFilesBatch {
+                    files,
+                    continuation_token: cursor.to_token(),
+                },
+            )),
+        }
+    };
+
+    for (index, file_view) in log_data.iter().enumerate().skip(start) {
+        if predicate_matches.as_ref().is_some_and(|keep| !keep[index]) {
+            continue;
+        }
+
+        // Filtered here, before the file is mapped or counted toward a
+        // batch -- a filtered-out file costs nothing beyond the log replay
+        // that found it.
+        let partition_data = file_view.partition_values();
+        if !partition_filters.matches(partition_data.as_ref()) {
+            continue;
+        }
+
+        // Skipped outright when projected away -- the stats especially,
+        // since LogicalFileView::stats() re-serializes them to JSON.
+        let partition_values = partition_data
+            .filter(|_| query.projection.include_partition_values)
+            .map(|data| serialize_partition_values(&data))
+            .unwrap_or_default();
+
+        batch.push(map_active_file_to_pb(
+            file_view.path().to_string(),
+            file_view.size(),
+            file_view.modification_time(),
+            partition_values,
+            query
+                .projection
+                .include_stats
+                .then(|| file_view.stats())
+                .flatten(),
+            query.projection.stats_columns.as_ref(),
+            file_view.deletion_vector_descriptor(),
+        ));
+        batch_end = index + 1;
+
+        if batch.len() >= FILE_BATCH_SIZE {
+            let message = batch_message(std::mem::take(&mut batch), batch_end);
+            if tx.send(Ok(message)).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+    if !batch.is_empty() {
+        let _ = tx.send(Ok(batch_message(batch, batch_end))).await;
+    }
+
+    Ok(())
+}
+
+// list_partitions()'s spawned-task body -- see stream_active_files for the
+// error-surfacing pattern this mirrors.
+async fn stream_partitions(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    as_of: Option<ReadAsOf>,
+    partition_filters: Vec<PartitionFilter>,
+    tx: tokio::sync::mpsc::Sender<Result<ListPartitionsResponse, Status>>,
+) {
+    let result =
+        stream_partitions_inner(table_uri, storage_opts, as_of, partition_filters, &tx).await;
+    if let Err(status) = result {
+        let _ = tx.send(Err(status)).await;
+    }
+}
+
+// The whole grouping happens before the header goes out: partitions come
+// out of delta::detail::summarize_files already aggregated and sorted, and
+// there's nothing to report per partition until every file has been seen.
+// Memory is per distinct partition, not per file, so this stays small
+// even for a table with millions of files.
+async fn stream_partitions_inner(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    as_of: Option<ReadAsOf>,
+    partition_filters: Vec<PartitionFilter>,
+    tx: &tokio::sync::mpsc::Sender<Result<ListPartitionsResponse, Status>>,
+) -> Result<(), Status> {
+    let table = open_table_for_read(&table_uri, storage_opts, as_of).await?;
+    let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
+    let partition_columns = snapshot.metadata().partition_columns().to_vec();
+
+    let partition_filters = PartitionFilters::bind(
+        partition_filters,
+        snapshot.schema().as_ref(),
+        &partition_columns,
+    )
+    .map_err(Status::from)?;
+    let summary = summarize_files(snapshot.snapshot(), &partition_filters).map_err(Status::from)?;
+
+    let header = ListPartitionsResponse {
+        payload: Some(list_partitions_response::Payload::Header(
+            ListPartitionsHeader {
+                version: snapshot.version() as i64,
+                partition_columns,
+            },
+        )),
+    };
+    if tx.send(Ok(header)).await.is_err() {
+        return Ok(()); // client already gone; nothing left to report.
+    }
+
+    let mut partitions = summary.partitions.into_iter().peekable();
+    while partitions.peek().is_some() {
+        let batch = ListPartitionsResponse {
+            payload: Some(list_partitions_response::Payload::Batch(
+                ListPartitionsBatch {
+                    partitions: partitions
+                        .by_ref()
+                        .take(FILE_BATCH_SIZE)
+                        .map(map_partition_summary_to_pb)
+                        .collect(),
+                },
+            )),
+        };
+        if tx.send(Ok(batch)).await.is_err() {
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
+
+// GetHistoryRequest's range and limit, validated -- see that message's own
+// comments for the rules. Checked synchronously in get_history(), before
+// the stream starts, same as ReadAsOf for the other read paths.
+#[derive(Debug, Clone, Copy)]
+struct HistoryRange {
+    start: u64,
+    end: u64,
+    limit: Option<usize>,
+}
+
+impl HistoryRange {
+    fn parse(
+        start_version: Option<i64>,
+        end_version: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<Self, Status> {
+        let bound = |name: &str, v: i64| {
+            u64::try_from(v)
+                .map_err(|_| Status::invalid_argument(format!("{name} must be >= 0, got {v}")))
+        };
+        let start = start_version
+            .map(|v| bound("start_version", v))
+            .transpose()?
+            .unwrap_or(0);
+        let end = end_version
+            .map(|v| bound("end_version", v))
+            .transpose()?
+            .unwrap_or(u64::MAX);
+        if start > end {
+            return Err(Status::invalid_argument(format!(
+                "start_version ({start}) must not be greater than end_version ({end})"
+            )));
+        }
+        let limit = limit
+            .map(|l| match usize::try_from(l) {
+                Ok(l) if l > 0 => Ok(l),
+                _ => Err(Status::invalid_argument(format!(
+                    "limit must be > 0 if set, got {l}"
+                ))),
+            })
+            .transpose()?;
+        Ok(Self { start, end, limit })
+    }
+}
+
+// get_history()'s spawned-task body -- see stream_active_files for the
+// error-surfacing pattern this mirrors.
+async fn stream_history(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    range: HistoryRange,
+    tx: tokio::sync::mpsc::Sender<Result<GetHistoryResponse, Status>>,
+) {
+    let result = stream_history_inner(table_uri, storage_opts, range, &tx).await;
+    if let Err(status) = result {
+        let _ = tx.send(Err(status)).await;
+    }
+}
+
+async fn stream_history_inner(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    range: HistoryRange,
+    tx: &tokio::sync::mpsc::Sender<Result<GetHistoryResponse, Status>>,
+) -> Result<(), Status> {
+    ensure_table_exists(&table_uri, storage_opts.clone()).await?;
+
+    // Listed from the range's start -- or, with a limit the versions since
+    // the latest checkpoint may meet on their own, from that checkpoint,
+    // the older ones only listed if they don't.
+    let hint = match range.limit {
+        Some(_) => last_checkpoint_hint(&table_uri, storage_opts.clone())
+            .await
+            .map_err(Status::from)?,
+        None => None,
+    };
+    let mut from = hint.map_or(range.start, |hint| hint.max(range.start));
+    let mut log = TableLog::open_from(&table_uri, storage_opts.clone(), from)
+        .await
+        .map_err(Status::from)?;
+    let mut upper = range.end;
+
+    // Newest first; the limit counts from that end. A version listed but
+    // gone by the time it's read (retention cleanup racing this stream)
+    // is skipped, not counted -- it's no longer part of the history.
+    let mut remaining = range.limit.unwrap_or(usize::MAX);
+    loop {
+        let versions = (from <= upper).then(|| log.commit_versions(from..=upper).rev());
+        for version in versions.into_iter().flatten() {
+            if remaining == 0 {
+                return Ok(());
+            }
+            let Some(entry) = log.read_commit(version).await.map_err(Status::from)? else {
+                continue;
+            };
+            let message =
+                map_history_entry_to_pb(entry.version, entry.timestamp, entry.commit_info);
+            if tx.send(Ok(message)).await.is_err() {
+                return Ok(()); // client already gone; nothing left to report.
+            }
+            remaining -= 1;
+        }
+        if remaining == 0 || from == range.start {
+            return Ok(());
+        }
+        upper = upper.min(from - 1);
+        from = range.start;
+        log = TableLog::open_from(&table_uri, storage_opts.clone(), from)
+            .await
+            .map_err(Status::from)?;
+    }
+}
+
+// GetChangesRequest's range, validated as far as it can be without the
+// log (see that message's own comments): the end_version-vs-latest checks
+// need the listing, so they happen in stream_changes_inner.
+fn parse_changes_range(
+    start_version: i64,
+    end_version: Option<i64>,
+) -> Result<(u64, Option<u64>), Status> {
+    let start = u64::try_from(start_version).map_err(|_| {
+        Status::invalid_argument(format!("start_version must be >= 0, got {start_version}"))
+    })?;
+    let end = end_version
+        .map(|end| {
+            u64::try_from(end).map_err(|_| {
+                Status::invalid_argument(format!("end_version must be >= 0, got {end}"))
+            })
+        })
+        .transpose()?;
+    if let Some(end) = end {
+        if end < start {
+            return Err(Status::invalid_argument(format!(
+                "end_version ({end}) must not be less than start_version ({start})"
+            )));
+        }
+    }
+    Ok((start, end))
+}
+
+// get_changes()'s spawned-task body -- see stream_active_files for the
+// error-surfacing pattern this mirrors.
+async fn stream_changes(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    start: u64,
+    end: Option<u64>,
+    tx: tokio::sync::mpsc::Sender<Result<GetChangesResponse, Status>>,
+) {
+    let result = stream_changes_inner(table_uri, storage_opts, start, end, &tx).await;
+    if let Err(status) = result {
+        let _ = tx.send(Err(status)).await;
+    }
+}
+
+async fn stream_changes_inner(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    start: u64,
+    end: Option<u64>,
+    tx: &tokio::sync::mpsc::Sender<Result<GetChangesResponse, Status>>,
+) -> Result<(), Status> {
+    ensure_table_exists(&table_uri, storage_opts.clone()).await?;
+    let log = TableLog::open(&table_uri, storage_opts)
+        .await
+        .map_err(Status::from)?;
+    let latest = log.latest_version().ok_or_else(|| {
+        Status::from(DeltaTxnError::OpenFailed(
+            "no commit files in _delta_log".to_string(),
+        ))
+    })?;
+
+    // u64 -> i64 casts throughout: see the matching comment on
+    // snapshot.version() in stream_active_files_inner.
+    let end = match end {
+        Some(end) if end > latest => {
+            return Err(Status::from(DeltaTxnError::VersionNotFound {
+                requested: end as i64,
+                latest: latest as i64,
+            }))
+        }
+        Some(end) => end,
+        None => latest,
+    };
+
+    // Checked for the whole range before the header goes out, so a range
+    // that reaches into cleaned-up history fails outright instead of
+    // streaming its surviving tail first. Still re-checked per version
+    // below: cleanup can race the stream itself.
+    let unavailable = |version: u64| {
+        Status::from(DeltaTxnError::VersionUnavailable {
+            requested: version as i64,
+            reason: "commit file missing from _delta_log".to_string(),
+        })
+    };
+    if let Some(missing) = (start..=end).find(|v| !log.has_commit(*v)) {
+        return Err(unavailable(missing));
+    }
+
+    let header = GetChangesResponse {
+        payload: Some(get_changes_response::Payload::Header(GetChangesHeader {
+            start_version: start as i64,
+            end_version: end as i64,
+        })),
+    };
+    if tx.send(Ok(header)).await.is_err() {
+        return Ok(()); // client already gone; nothing left to report.
+    }
+
+    for version in start..=end {
+        let commit = log
+            .read_actions(version)
+            .await
+            .map_err(Status::from)?
+            .ok_or_else(|| unavailable(version))?;
+
+        // At least one batch per version, even an empty one -- see
+        // GetChangesBatch's own comment on why.
+        let mut actions = commit
+            .actions
+            .into_iter()
+            .filter_map(map_action_to_pb)
+            .peekable();
+        let mut first_batch = true;
+        while first_batch || actions.peek().is_some() {
+            first_batch = false;
+            let message = GetChangesResponse {
+                payload: Some(get_changes_response::Payload::Batch(GetChangesBatch {
+                    version: version as i64,
+                    timestamp: commit.timestamp,
+                    actions: actions.by_ref().take(FILE_BATCH_SIZE).collect(),
+                })),
+            };
+            if tx.send(Ok(message)).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_from_version(from_version: Option<i64>) -> Result<Option<u64>, Status> {
+    from_version
+        .map(|v| {
+            u64::try_from(v).map_err(|_| {
+                Status::invalid_argument(format!("from_version must be >= 0, got {v}"))
+            })
+        })
+        .transpose()
+}
+
+// watch_table()'s spawned-task body -- see stream_active_files for the
+// error-surfacing pattern this mirrors.
+async fn stream_watch(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    watches: TableWatchManager,
+    from_version: Option<u64>,
+    tx: tokio::sync::mpsc::Sender<Result<WatchTableResponse, Status>>,
+) {
+    let result = stream_watch_inner(table_uri, storage_opts, watches, from_version, &tx).await;
+    if let Err(status) = result {
+        let _ = tx.send(Err(status)).await;
+    }
+}
+
+async fn stream_watch_inner(
+    table_uri: Url,
+    storage_opts: HashMap<String, String>,
+    watches: TableWatchManager,
+    from_version: Option<u64>,
+    tx: &tokio::sync::mpsc::Sender<Result<WatchTableResponse, Status>>,
+) -> Result<(), Status> {
+    ensure_table_exists(&table_uri, storage_opts.clone()).await?;
+
+    // Subscribed only once the table is known to exist, so a watch on a
+    // bad table_uri never starts a poller for it.
+    let mut latest = watches.subscribe(&table_uri, storage_opts);
+
+    // The next version to announce; `None` until the first known version
+    // arrives when the client didn't say where to start.
+    let mut next = from_version.map(|v| v + 1);
+    loop {
+        // Copied out first: the borrow guard must not be held across the
+        // sends below.
+        let known = *latest.borrow_and_update();
+        if let Some(known) = known {
+            // A version past `known` (from_version ahead of the table)
+            // makes this range empty; `next` stays put until the table
+            // catches up to it.
+            let start = next.unwrap_or(known);
+            for version in start..=known {
+                // See stream_active_files_inner's matching cast comment.
+                let message = WatchTableResponse {
+                    version: version as i64,
+                };
+                if tx.send(Ok(message)).await.is_err() {
+                    return Ok(()); // client already gone; nothing left to report.
+                }
+            }
+            next = Some(start.max(known + 1));
+        }
+
+        tokio::select! {
+            changed = latest.changed() => {
+                if changed.is_err() {
+                    // The manager only drops a table's channel once it has
+                    // no receivers left, and this one is still held -- so
+                    // this is a bug, not a condition to retry.
+                    return Err(Status::internal("table watch channel closed unexpectedly"));
+                }
+            }
+            // A watch otherwise never ends on its own; without this, a
+            // cancelled client's task (and its receiver, which keeps the
+            // table's poller alive) would linger until the next version.
+            _ = tx.closed() => return Ok(()),
+        }
+    }
+}
+
+// One TableCommit of a MultiTableCommit, validated: its table_uri as the
+// client sent it (for messages and results) and normalized (for locking
+// and opening the table).
+struct MultiTablePart {
+    table_uri: String,
+    normalized_table_uri: Url,
+    expected_version: Option<i64>,
+    actions: Vec<deltalake::kernel::Action>,
+}
+
+// A MultiTableCommit part's failure, naming the part: its checks are
+// Commit's, whose messages don't say which table they mean. The status's
+// details (e.g. the partition-value violations) are kept as they were.
+fn for_table(table_uri: &str) -> impl Fn(Status) -> Status + '_ {
+    move |status| {
+        Status::with_details(
+            status.code(),
+            format!("table_uri '{table_uri}': {}", status.message()),
+            Bytes::copy_from_slice(status.details()),
+        )
+    }
+}
+
+// Whether a MultiTableCommit part's commit failed in a way committing
+// the same actions on the same read version again would repeat: a
+// conflict with something committed since, a table delta-rs now refuses
+// to write, or a read version log cleanup has removed.
+fn is_terminal_part_failure(e: &DeltaTxnError) -> bool {
+    matches!(
+        e,
+        DeltaTxnError::CommitConflict(_)
+            | DeltaTxnError::UnsupportedTableFeatures(_)
+            | DeltaTxnError::VersionUnavailable { .. }
+    )
+}
+
+// What a rolled-back MultiTableCommit answers with, first time and every
+// retry.
+fn multi_table_commit_rolled_back(id: &str, failure: &str) -> Status {
+    Status::failed_precondition(format!(
+        "multi-table commit {id} was rolled back -- {failure} -- and every part that had \
+         landed has been taken back out of its table"
+    ))
+}
+
+// How many times roll_back_part() rereads a table that moved under it
+// before leaving the rollback to the next attempt.
+const MULTI_TABLE_ROLLBACK_ATTEMPTS: usize = 5;
+
+// What roll_forward_multi_table_commit() carried the commit through to.
+enum MultiTableFinish {
+    // Each part's version, in the intent's (lock) order.
+    Committed(Vec<i64>),
+    // Its first part was refused, and why; nothing of it landed and its
+    // record is gone.
+    Withdrawn(DeltaTxnError),
+    // A later part was refused, for the reason recorded; every part that
+    // landed has been taken back out, and the record stays to answer
+    // retries.
+    RolledBack(String),
+}
+
+// A coordinator record's table_uri, written by this service already
+// normalized.
+fn parse_intent_table_uri(intent: &MultiTableIntent, part: &TableIntent) -> Result<Url, Status> {
+    Url::parse(&part.table_uri).map_err(|e| {
+        Status::from(DeltaTxnError::CoordinatorFailed(format!(
+            "multi-table commit {} names an unparseable table_uri '{}': {e}",
+            intent.id, part.table_uri
+        )))
+    })
+}
+
+// What a server answers once another holds the record it'd apply --
+// one that took it over, or the original still applying it.
+fn multi_table_commit_taken_over(id: &str) -> Status {
+    Status::unavailable(format!(
+        "multi-table commit {id} is being finished by another server; retry with the same \
+         idempotency_key"
+    ))
+}
+
+// `versions` in request order, one per part.
+fn table_commit_results(parts: &[MultiTablePart], versions: &[i64]) -> Vec<TableCommitResult> {
+    parts
+        .iter()
+        .zip(versions)
+        .map(|(part, &committed_version)| TableCommitResult {
+            table_uri: part.table_uri.clone(),
+            committed_version,
+        })
+        .collect()
+}
+
+/// Whether a failed commit might succeed if tried again unchanged, as
+/// opposed to a conflict or a request the table refuses, which would fail
+/// the same way every time. INTERNAL isn't among them: delta-rs's
+/// deterministic failures (CommitFailed) come back as it too, and a
+/// transaction kept open for a retry that can't succeed holds its staged
+/// actions until the TTL. CommitTransaction keeps the transaction open
+/// only for these.
+fn is_transient(code: tonic::Code) -> bool {
+    matches!(
+        code,
+        tonic::Code::Unavailable
+            | tonic::Code::DeadlineExceeded
+            | tonic::Code::ResourceExhausted
+            | tonic::Code::Cancelled
+    )
+}
+
+/// The next CommitStream message's payload, or `None` once the client has
+/// closed the stream. A message with no payload is malformed; a stream
+/// that fails instead of closing, or sends nothing for `idle_timeout`, was
+/// abandoned midway, which ends the RPC as ABORTED -- nothing was
+/// committed, and retrying the whole upload is safe.
+async fn next_commit_stream_message(
+    stream: &mut Streaming<CommitStreamRequest>,
+    idle_timeout: Duration,
+) -> Result<Option<commit_stream_request::Payload>, Status> {
+    let Ok(message) = tokio::time::timeout(idle_timeout, stream.message()).await else {
+        warn!(
+            ?idle_timeout,
+            "CommitStream sat idle too long, abandoning it"
+        );
+        return Err(Status::aborted(format!(
+            "CommitStream sent nothing for {}s, so it was abandoned and nothing was committed",
+            idle_timeout.as_secs()
+        )));
+    };
+    match message {
+        Ok(Some(message)) => message
+            .payload
+            .map(Some)
+            .ok_or_else(|| Status::invalid_argument("CommitStream message has no payload")),
+        Ok(None) => Ok(None),
+        Err(status) => {
+            warn!(error = %status, "CommitStream abandoned before the client closed it");
+            Err(Status::aborted(format!(
+                "CommitStream ended before the client closed it, so nothing was committed: {}",
+                status.message()
+            )))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl DeltaTxnService for DeltaTxnGrpcServer {
+    /// Atomically applies `req.actions` to the table at `req.table_uri`,
+    /// serialized against every other concurrent Commit for the *same*
+    /// table_uri by TableLockManager (see that type's own doc comment --
+    /// an optimization, not a correctness requirement) and, once past
+    /// that, checked against `expected_version` (this service's own
+    /// pre-commit optimistic-concurrency check) before delta-rs's
+    /// CommitBuilder (see delta::commit::commit_actions, including its
+    /// own doc comment on the operation-type limitation) does the actual
+    /// atomic write with its own independent conflict retry.
+    async fn commit(
+        &self,
+        req: Request<CommitRequest>,
+    ) -> Result<Response<CommitResponse>, Status> {
+        self.apply_commit(req.into_inner(), None)
+            .await
+            .map(Response::new)
+    }
+
+    /// commit() with its actions uploaded in chunks: the header is read
+    /// and its table_uri checked first, then every chunk is appended to
+    /// the header's own actions until the client closes the stream, and
+    /// the assembled request goes through exactly the same path as a
+    /// unary Commit. Nothing is locked or opened while chunks arrive -- a
+    /// slow upload holds no table lock -- and a stream that breaks off
+    /// (the client cancelled, or its connection dropped) ends the RPC
+    /// before that path is reached, with nothing committed.
+    async fn commit_stream(
+        &self,
+        req: Request<Streaming<CommitStreamRequest>>,
+    ) -> Result<Response<CommitResponse>, Status> {
+        let mut stream = req.into_inner();
+        let idle_timeout = self.streams.limits().idle_timeout;
+        let mut request = match next_commit_stream_message(&mut stream, idle_timeout).await? {
+            Some(commit_stream_request::Payload::Header(header)) => header,
+            Some(_) => {
+                return Err(Status::invalid_argument(
+                    "the first CommitStream message must be its header",
+                ))
+            }
+            None => {
+                return Err(Status::invalid_argument(
+                    "CommitStream was closed without a header",
+                ))
+            }
+        };
+        self.normalize_and_check_table_uri(&request.table_uri)?;
+        // Held until the commit is done: everything received counts
+        // against the server's limit on in-flight uploads, and is given
+        // back however this returns.
+        let mut buffer = self.streams.buffer();
+        buffer.reserve(&request.actions)?;
+
+        let action_count = loop {
+            match next_commit_stream_message(&mut stream, idle_timeout).await? {
+                Some(commit_stream_request::Payload::Actions(chunk)) => {
+                    buffer.reserve(&chunk.actions)?;
+                    request.actions.extend(chunk.actions);
+                }
+                Some(commit_stream_request::Payload::End(end)) => break end.action_count,
+                Some(commit_stream_request::Payload::Header(_)) => {
+                    return Err(Status::invalid_argument(
+                        "CommitStream takes exactly one header",
+                    ))
+                }
+                // A client closing the stream is not the same as it having
+                // sent everything: an error path that closes it halfway
+                // through would otherwise commit whatever had arrived.
+                None => {
+                    return Err(Status::aborted(
+                        "CommitStream was closed without its end message, so nothing was \
+                         committed",
+                    ))
+                }
+            }
+        };
+        if next_commit_stream_message(&mut stream, idle_timeout)
+            .await?
+            .is_some()
+        {
+            return Err(Status::invalid_argument(
+                "CommitStream's end message must be its last",
+            ));
+        }
+        if action_count != request.actions.len() as i64 {
+            return Err(Status::invalid_argument(format!(
+                "CommitStream's end message counts {action_count} action(s), but {} arrived",
+                request.actions.len()
+            )));
+        }
+
+        self.apply_commit(request, None).await.map(Response::new)
+    }
+
+    /// Adds `req.feature` to an existing table's protocol: the new
+    /// Protocol is computed from the current one (see
+    /// table_features::protocol_with_feature) and committed alone under
+    /// `DeltaOperation::AddFeature`, with the same lock and
+    /// expected_version check as commit(). A table already supporting the
+    /// feature gets nothing committed.
+    async fn enable_table_feature(
+        &self,
+        req: Request<EnableTableFeatureRequest>,
+    ) -> Result<Response<EnableTableFeatureResponse>, Status> {
+        let r = req.into_inner();
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&r.table_uri)?;
+        let feature = parse_table_feature(&r.feature).map_err(Status::from)?;
+
+        let lock = self.locks.lock_for(normalized_table_uri.as_str());
+        let _guard = lock.lock().await;
+
+        ensure_table_exists(&normalized_table_uri, self.storage_opts.clone()).await?;
+        let table = open_table(&normalized_table_uri, self.storage_opts.clone())
+            .await
+            .map_err(Status::from)?;
+        let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
+        // See commit()'s matching u64 -> i64 comment.
+        let current = snapshot.version() as i64;
+        if let Some(expected) = r.expected_version {
+            if current != expected {
+                return Err(Status::from(DeltaTxnError::VersionConflict {
+                    expected,
+                    actual: current,
+                }));
+            }
+        }
+
+        let Some(protocol) = protocol_with_feature(snapshot.protocol(), &feature) else {
+            return Ok(Response::new(EnableTableFeatureResponse {
+                committed_version: None,
+                protocol: Some(map_protocol_to_pb(snapshot.protocol())),
+            }));
+        };
+        // Refused rather than enabled when delta-rs can't write the table
+        // with it -- the upgrade itself would commit, and every Commit to
+        // the table after it would fail.
+        check_writable_protocol(snapshot, &protocol).map_err(Status::from)?;
+        let response_protocol = map_protocol_to_pb(&protocol);
+        let version = commit_with_operation(
+            table,
+            vec![deltalake::kernel::Action::Protocol(protocol)],
+            DeltaOperation::AddFeature {
+                name: vec![feature],
+            },
+            HashMap::new(),
+        )
+        .await
+        .map_err(Status::from)?;
+        self.watches
+            .publish(normalized_table_uri.as_str(), version as u64);
+
+        Ok(Response::new(EnableTableFeatureResponse {
+            committed_version: Some(version),
+            protocol: Some(response_protocol),
+        }))
+    }
+
+    /// Looks up `req.app_id`'s latest SetTransaction version on the table's
+    /// current snapshot. A plain read, like get_table().
+    async fn get_transaction_version(
+        &self,
+        req: Request<GetTransactionVersionRequest>,
+    ) -> Result<Response<GetTransactionVersionResponse>, Status> {
+        let r = req.into_inner();
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&r.table_uri)?;
+        if r.app_id.is_empty() {
+            return Err(Status::invalid_argument("app_id is required"));
+        }
+
+        let table =
+            open_table_for_read(&normalized_table_uri, self.storage_opts.clone(), None).await?;
+        let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
+        let version = snapshot
+            .transaction_version(table.log_store().as_ref(), &r.app_id)
+            .await
+            .map_err(|e| Status::from(DeltaTxnError::OpenFailed(e.to_string())))?;
+
+        Ok(Response::new(GetTransactionVersionResponse {
+            version,
+            table_version: snapshot.version() as i64,
+        }))
+    }
+
+    /// Opens a staged transaction on an existing table at its current
+    /// version. A plain read, like get_table(): nothing is locked until
+    /// commit_transaction(), so a transaction left open blocks no writer.
+    async fn begin_transaction(
+        &self,
+        req: Request<BeginTransactionRequest>,
+    ) -> Result<Response<BeginTransactionResponse>, Status> {
+        let r = req.into_inner();
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&r.table_uri)?;
+        let table =
+            open_table_for_read(&normalized_table_uri, self.storage_opts.clone(), None).await?;
+        let read_version = table
+            .snapshot()
+            .map_err(map_open_or_snapshot_error)?
+            .version();
+        let txn_id = self.staged.begin(normalized_table_uri, read_version)?;
+
+        Ok(Response::new(BeginTransactionResponse {
+            txn_id,
+            read_version: read_version as i64,
+            idle_ttl_seconds: self.staged.limits().idle_ttl.as_secs() as i64,
+        }))
+    }
+
+    /// Adds a batch of actions to an open transaction. Each batch is
+    /// converted once on arrival, the same way commit() converts its
+    /// actions, so a malformed one is refused here rather than surfacing
+    /// only at commit time -- but what's staged is the batch as sent, and
+    /// converted again (with the rest) by commit_transaction().
+    async fn stage_actions(
+        &self,
+        req: Request<StageActionsRequest>,
+    ) -> Result<Response<StageActionsResponse>, Status> {
+        let r = req.into_inner();
+
+        map_actions(r.actions.iter().cloned()).map_err(Status::invalid_argument)?;
+        let staged_action_count = self.staged.stage(&r.txn_id, r.actions)?;
+
+        Ok(Response::new(StageActionsResponse {
+            staged_action_count: staged_action_count as i64,
+        }))
+    }
+
+    /// Commits everything a transaction staged as one version, through
+    /// the same path as commit() -- lock, checks, idempotency -- with the
+    /// transaction's id as its idempotency key and its read version as
+    /// what the conflict check starts from (see apply_commit()). Once the
+    /// commit lands the transaction keeps only its committed version, and
+    /// a retry whose original response was lost is answered with it as a
+    /// replay. One that fails in a way committing the same actions again
+    /// can't fix (a conflict, actions the table refuses, an internal
+    /// error) closes the transaction; a transient failure (see
+    /// is_transient) leaves it open, staged actions and all, for the
+    /// client to retry. Should
+    /// the original have landed after all, the retry finds it by its
+    /// idempotency key.
+    async fn commit_transaction(
+        &self,
+        req: Request<CommitTransactionRequest>,
+    ) -> Result<Response<CommitResponse>, Status> {
+        let r = req.into_inner();
+
+        let checkout = match self.staged.checkout(&r.txn_id)? {
+            CheckoutOutcome::Open(checkout) => checkout,
+            CheckoutOutcome::Committed(version) => {
+                return Ok(Response::new(CommitResponse {
+                    committed_version: version,
+                    idempotent_replay: true,
+                }));
+            }
+        };
+        let request = CommitRequest {
+            table_uri: checkout.table_uri.to_string(),
+            expected_version: None,
+            actions: Vec::new(),
+            app_metadata: HashMap::new(),
+            idempotency_key: r.txn_id,
+            validate_only: false,
+        };
+        let result = self.apply_commit(request, Some(&checkout)).await;
+        match &result {
+            Ok(response) => checkout.committed(response.committed_version),
+            Err(status) if is_transient(status.code()) => drop(checkout),
+            Err(_) => checkout.close(),
+        }
+        result.map(Response::new)
+    }
+
+    /// Drops an open transaction and everything it staged.
+    async fn abort_transaction(
+        &self,
+        req: Request<AbortTransactionRequest>,
+    ) -> Result<Response<AbortTransactionResponse>, Status> {
+        self.staged.abort(&req.into_inner().txn_id)?;
+        Ok(Response::new(AbortTransactionResponse {}))
+    }
+
+    /// Commits to several existing tables as one decision, by the
+    /// two-phase protocol MultiTableCommitRequest's own comment describes:
+    /// every part checked under all their locks, the decision recorded in
+    /// the coordinator log, then each table committed by
+    /// roll_forward_multi_table_commit().
+    async fn multi_table_commit(
+        &self,
+        req: Request<MultiTableCommitRequest>,
+    ) -> Result<Response<MultiTableCommitResponse>, Status> {
+        let r = req.into_inner();
+
+        let Some(log) = &self.coordinator else {
+            return Err(Status::failed_precondition(
+                "MultiTableCommit is disabled on this server -- it needs a coordinator log \
+                 location (DELTA_TXN_COORDINATOR_URI)",
+            ));
+        };
+        if r.commits.is_empty() {
+            return Err(Status::invalid_argument(
+                "MultiTableCommit needs at least one TableCommit",
+            ));
+        }
+        // Same up-front validation as apply_commit(), per part, before any
+        // lock is taken -- plus what a part may not do, since a rollback
+        // has to be able to take it back out with file actions alone.
+        let mut parts = Vec::with_capacity(r.commits.len());
+        for commit in r.commits {
+            let normalized_table_uri = self.normalize_and_check_table_uri(&commit.table_uri)?;
+            let actions = map_actions(commit.actions)
+                .map_err(|e| for_table(&commit.table_uri)(Status::invalid_argument(e)))?;
+            if let Some(kind) = actions.iter().find_map(|action| match action {
+                deltalake::kernel::Action::Metadata(_) => Some("TableMetadata"),
+                deltalake::kernel::Action::Protocol(_) => Some("Protocol"),
+                deltalake::kernel::Action::Txn(_) => Some("SetTransaction"),
+                _ => None,
+            }) {
+                return Err(for_table(&commit.table_uri)(Status::invalid_argument(
+                    format!(
+                        "a MultiTableCommit part can't carry a {kind} action -- a part may have \
+                         to be rolled back, which only file actions can be; commit it with \
+                         Commit instead"
+                    ),
+                )));
+            }
+            parts.push(MultiTablePart {
+                table_uri: commit.table_uri,
+                normalized_table_uri,
+                expected_version: commit.expected_version,
+                actions,
+            });
+        }
+        let mut order: Vec<usize> = (0..parts.len()).collect();
+        order.sort_by(|&a, &b| {
+            let uri = |i: usize| parts[i].normalized_table_uri.as_str();
+            uri(a).cmp(uri(b))
+        });
+        if let Some(pair) = order
+            .windows(2)
+            .find(|pair| parts[pair[0]].normalized_table_uri == parts[pair[1]].normalized_table_uri)
+        {
+            return Err(Status::invalid_argument(format!(
+                "table_uri '{}' appears more than once -- a MultiTableCommit commits to each \
+                 table once; combine its actions into one TableCommit",
+                parts[pair[1]].table_uri
+            )));
+        }
+        let idempotency_key = Some(r.idempotency_key).filter(|key| !key.is_empty());
+
+        let locks = self
+            .locks
+            .locks_for(parts.iter().map(|part| part.normalized_table_uri.as_str()));
+        let mut _guards = Vec::with_capacity(locks.len());
+        for lock in &locks {
+            _guards.push(lock.lock().await);
+        }
+
+        // A retry: either its original is still in the coordinator log --
+        // found directly, by its key -- and is finished now, or every part
+        // already landed. Like apply_commit()'s, answered before anything
+        // else is checked.
+        if let Some(key) = &idempotency_key {
+            let replay = match log.get(key).await.map_err(Status::from)? {
+                Some(mut intent) => {
+                    let mut intent_uris: Vec<&str> = intent
+                        .tables
+                        .iter()
+                        .map(|part| part.table_uri.as_str())
+                        .collect();
+                    let mut request_uris: Vec<&str> = parts
+                        .iter()
+                        .map(|part| part.normalized_table_uri.as_str())
+                        .collect();
+                    intent_uris.sort_unstable();
+                    request_uris.sort_unstable();
+                    if intent_uris != request_uris {
+                        return Err(Status::invalid_argument(format!(
+                            "idempotency_key '{key}' belongs to an unfinished multi-table commit \
+                             of a different set of tables"
+                        )));
+                    }
+                    // Rolled back ones are refused before any claim: there's
+                    // nothing left to do for them.
+                    if log.is_rolled_back(key).await.map_err(Status::from)? {
+                        let failure = log
+                            .failure(key)
+                            .await
+                            .map_err(Status::from)?
+                            .unwrap_or_default();
+                        return Err(multi_table_commit_rolled_back(&intent.id, &failure));
+                    }
+                    let lease = log
+                        .claim(key, LEASE_TTL)
+                        .await
+                        .map_err(Status::from)?
+                        .ok_or_else(|| multi_table_commit_taken_over(&intent.id))?;
+                    let versions = match self
+                        .roll_forward_multi_table_commit(log, &mut intent, lease, None)
+                        .await?
+                    {
+                        MultiTableFinish::Committed(versions) => versions,
+                        MultiTableFinish::Withdrawn(e) => {
+                            return Err(for_table(&intent.tables[0].table_uri)(Status::from(e)));
+                        }
+                        MultiTableFinish::RolledBack(failure) => {
+                            return Err(multi_table_commit_rolled_back(&intent.id, &failure));
+                        }
+                    };
+                    let by_uri: HashMap<&str, i64> = intent
+                        .tables
+                        .iter()
+                        .map(|part| part.table_uri.as_str())
+                        .zip(versions)
+                        .collect();
+                    Some(
+                        parts
+                            .iter()
+                            .map(|part| by_uri[part.normalized_table_uri.as_str()])
+                            .collect(),
+                    )
+                }
+                None => {
+                    let mut versions = Vec::with_capacity(parts.len());
+                    for part in &parts {
+                        let table_uri = &part.normalized_table_uri;
+                        ensure_table_exists(table_uri, self.storage_opts.clone()).await?;
+                        let latest = open_table(table_uri, self.storage_opts.clone())
+                            .await
+                            .map_err(Status::from)?
+                            .version()
+                            .unwrap_or(0);
+                        let oldest = idempotency_search_start(
+                            part.expected_version,
+                            latest,
+                            self.idempotency_lookback,
+                        );
+                        if let Some(version) = find_idempotent_commit(
+                            table_uri,
+                            self.storage_opts.clone(),
+                            key,
+                            oldest,
+                        )
+                        .await
+                        .map_err(Status::from)?
+                        {
+                            versions.push(version as i64);
+                        }
+                    }
+                    // Some parts but not all, with no record: the original
+                    // was recorded by another server after the lookup above,
+                    // or in a different coordinator log this one can't
+                    // finish -- and committing the whole request again would
+                    // apply those parts twice either way.
+                    if !versions.is_empty() && versions.len() < parts.len() {
+                        return Err(Status::unavailable(format!(
+                            "idempotency_key '{key}' belongs to a multi-table commit that is \
+                             partly applied but not in this server's coordinator log; retry \
+                             later, or check the coordinator log the original server uses"
+                        )));
+                    }
+                    (!versions.is_empty()).then_some(versions)
+                }
+            };
+            if let Some(versions) = replay {
+                return Ok(Response::new(MultiTableCommitResponse {
+                    results: table_commit_results(&parts, &versions),
+                    commit_id: String::new(),
+                    idempotent_replay: true,
+                }));
+            }
+        }
+
+        // Phase one: every part checked, in lock order, nothing written.
+        let commit_id = uuid::Uuid::new_v4().to_string();
+        let mut tables = Vec::with_capacity(parts.len());
+        let mut intent = MultiTableIntent {
+            idempotency_key: idempotency_key.unwrap_or_else(|| commit_id.clone()),
+            id: commit_id,
+            tables: Vec::with_capacity(parts.len()),
+        };
+        for &i in &order {
+            let MultiTablePart {
+                table_uri,
+                normalized_table_uri,
+                expected_version,
+                actions,
+            } = &mut parts[i];
+            if !table_exists(normalized_table_uri, self.storage_opts.clone())
+                .await
+                .map_err(Status::from)?
+            {
+                return Err(Status::failed_precondition(format!(
+                    "table_uri '{table_uri}' does not exist -- a MultiTableCommit only commits \
+                     to existing tables; create it with Commit first"
+                )));
+            }
+            let table = open_table(normalized_table_uri, self.storage_opts.clone())
+                .await
+                .map_err(Status::from)?;
+            self.check_existing_table_commit(&table, actions, *expected_version)
+                .await
+                .map_err(for_table(table_uri))?;
+            // Moved through the dry run into the record rather than copied:
+            // nothing reads a part's actions off the request after this.
+            let (_, actions) =
+                validate_actions(&table, std::mem::take(actions), intent.commit_metadata())
+                    .await
+                    .map_err(|e| for_table(table_uri)(Status::from(e)))?;
+            intent.tables.push(TableIntent {
+                table_uri: normalized_table_uri.to_string(),
+                read_version: table.version().unwrap_or_default(),
+                restore: removed_files(&table, &actions).map_err(Status::from)?,
+                actions,
+            });
+            tables.push(table);
+        }
+
+        // Phase two: decided once recorded; then applied table by table.
+        let lease = log
+            .record(&intent, LEASE_TTL)
+            .await
+            .map_err(Status::from)?
+            .ok_or_else(|| multi_table_commit_taken_over(&intent.id))?;
+        let versions = match self
+            .roll_forward_multi_table_commit(log, &mut intent, lease, Some(tables))
+            .await?
+        {
+            MultiTableFinish::Committed(versions) => versions,
+            // Refused before anything had landed (a conflict with another
+            // replica's commit, say): answered with that part's own error.
+            MultiTableFinish::Withdrawn(e) => {
+                return Err(for_table(&parts[order[0]].table_uri)(Status::from(e)))
+            }
+            MultiTableFinish::RolledBack(failure) => {
+                return Err(multi_table_commit_rolled_back(&intent.id, &failure))
+            }
+        };
+
+        let mut by_request_order = vec![0; parts.len()];
+        for (&i, version) in order.iter().zip(versions) {
+            by_request_order[i] = version;
+        }
+        Ok(Response::new(MultiTableCommitResponse {
+            results: table_commit_results(&parts, &by_request_order),
+            commit_id: intent.id,
+            idempotent_replay: false,
+        }))
+    }
+
+    /// Returns a table's current version, metadata (including its schema),
+    /// and protocol -- no file listing (see list_active_files() for that).
+    /// A plain read: opens the table fresh, takes no lock (Delta readers
+    /// never need to block on writers -- see locking::mod's own doc
+    /// comment), and reflects whatever snapshot happens to be visible at
+    /// the moment it's called -- or, with `req.as_of` set, the older
+    /// snapshot it selects instead.
+    async fn get_table(
+        &self,
+        req: Request<GetTableRequest>,
+    ) -> Result<Response<GetTableResponse>, Status> {
+        let r = req.into_inner();
+        let table_uri = r.table_uri;
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&table_uri)?;
+        let (version, timestamp) = as_of_parts!(r.as_of, get_table_request);
+        let as_of = ReadAsOf::parse(version, timestamp)?;
+
+        let table =
+            open_table_for_read(&normalized_table_uri, self.storage_opts.clone(), as_of).await?;
+
+        let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
+        let (metadata, protocol) = build_metadata_and_protocol(snapshot)?;
+
+        Ok(Response::new(GetTableResponse {
+            // See stream_active_files_inner's matching cast comment above.
+            version: snapshot.version() as i64,
+            metadata: Some(metadata),
+            protocol: Some(protocol),
+        }))
+    }
+
+    /// GetTable plus aggregates: one pass over the snapshot's file list
+    /// (delta::detail::summarize_files) for the counts and sizes, and one
+    /// LIST of the log (TableLog) for the latest checkpoint and the
+    /// version's own commit -- from the checkpoint `_last_checkpoint`
+    /// names, unless that's past the version. Same plain-read, no-lock,
+    /// `as_of` behaviour as get_table().
+    async fn describe_detail(
+        &self,
+        req: Request<DescribeDetailRequest>,
+    ) -> Result<Response<DescribeDetailResponse>, Status> {
+        let r = req.into_inner();
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&r.table_uri)?;
+        let (version, timestamp) = as_of_parts!(r.as_of, describe_detail_request);
+        let as_of = ReadAsOf::parse(version, timestamp)?;
+
+        let table =
+            open_table_for_read(&normalized_table_uri, self.storage_opts.clone(), as_of).await?;
+        let snapshot = table.snapshot().map_err(map_open_or_snapshot_error)?;
+        let (metadata, protocol) = build_metadata_and_protocol(snapshot)?;
+        let summary = summarize_files(snapshot.snapshot(), &PartitionFilters::default())
+            .map_err(Status::from)?;
+
+        let version = snapshot.version();
+        let from = last_checkpoint_hint(&normalized_table_uri, self.storage_opts.clone())
+            .await
+            .map_err(Status::from)?
+            .filter(|hint| *hint <= version)
+            .unwrap_or_default();
+        let log = TableLog::open_from(&normalized_table_uri, self.storage_opts.clone(), from)
+            .await
+            .map_err(Status::from)?;
+        let commit = log.read_commit(version).await.map_err(Status::from)?;
+
+        // u64 -> i64 throughout: see stream_active_files_inner's matching
+        // cast comment on snapshot.version().
+        Ok(Response::new(DescribeDetailResponse {
+            version: version as i64,
+            metadata: Some(metadata),
+            protocol: Some(protocol),
+            num_files: summary.num_files as i64,
+            size_in_bytes: summary.size_in_bytes as i64,
+            num_partitions: summary.partitions.len() as i64,
+            partitions: summary
+                .partitions
+                .into_iter()
+                .take(DESCRIBE_DETAIL_MAX_PARTITIONS)
+                .map(map_partition_summary_to_pb)
+                .collect(),
+            last_commit_timestamp: commit.as_ref().map(|c| c.timestamp),
+            last_operation: commit
+                .and_then(|c| c.commit_info)
+                .and_then(|info| info.operation),
+            latest_checkpoint_version: log.latest_checkpoint(version).map(|v| v as i64),
+            table_features: table_features(snapshot.protocol()),
+        }))
+    }
 
     type ListActiveFilesStream = ListActiveFilesResultStream;
 
@@ -502,9 +2458,9 @@ impl DeltaTxnService for DeltaTxnGrpcServer {
     // other handler), but the actual table open + snapshot + file
     // enumeration all happen inside a spawned task instead of before this
     // function returns. That's a real ownership constraint, not just
-    // style: EagerSnapshot::file_views()'s returned stream borrows from
-    // the snapshot, which borrows from the DeltaTable -- none of which can
-    // be moved across the spawn boundary as a borrow, so the whole
+    // style: the file list walked (EagerSnapshot::try_log_data()) borrows
+    // from the snapshot, which borrows from the DeltaTable -- none of which
+    // can be moved across the spawn boundary as a borrow, so the whole
     // open-through-stream sequence has to live inside one async block that
     // owns the DeltaTable itself. See stream_active_files_inner() for
     // where any failure in that sequence actually surfaces (as the
@@ -514,16 +2470,182 @@ impl DeltaTxnService for DeltaTxnGrpcServer {
         req: Request<ListActiveFilesRequest>,
     ) -> Result<Response<Self::ListActiveFilesStream>, Status> {
         let r = req.into_inner();
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&r.table_uri)?;
+        let query = ActiveFilesQuery::parse(r, &normalized_table_uri)?;
+
+        let storage_opts = self.storage_opts.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_CAPACITY);
+
+        tokio::spawn(stream_active_files(
+            normalized_table_uri,
+            storage_opts,
+            query,
+            tx,
+        ));
+
+        let stream: Self::ListActiveFilesStream = Box::pin(ReceiverStream::new(rx));
+        Ok(Response::new(stream))
+    }
+
+    type ListPartitionsStream = ListPartitionsResultStream;
+
+    // Server-streaming, structured exactly like list_active_files(): the
+    // request is validated here, the table opened and its files grouped in
+    // a spawned task (stream_partitions_inner).
+    async fn list_partitions(
+        &self,
+        req: Request<ListPartitionsRequest>,
+    ) -> Result<Response<Self::ListPartitionsStream>, Status> {
+        let r = req.into_inner();
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&r.table_uri)?;
+        let (version, timestamp) = as_of_parts!(r.as_of, list_partitions_request);
+        let as_of = ReadAsOf::parse(version, timestamp)?;
+        let partition_filters =
+            map_partition_filters(r.partition_filters).map_err(Status::invalid_argument)?;
+
+        let storage_opts = self.storage_opts.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_CAPACITY);
+
+        tokio::spawn(stream_partitions(
+            normalized_table_uri,
+            storage_opts,
+            as_of,
+            partition_filters,
+            tx,
+        ));
+
+        let stream: Self::ListPartitionsStream = Box::pin(ReceiverStream::new(rx));
+        Ok(Response::new(stream))
+    }
+
+    type GetHistoryStream = GetHistoryResultStream;
+
+    // Server-streaming, structured exactly like list_active_files():
+    // request validation here, the log listing and commit-file reads in a
+    // spawned task (see stream_history_inner). Reads commit files one at a
+    // time as the client consumes the stream, so backpressure bounds how
+    // far ahead of the client the server reads, and a client that stops
+    // after the first few entries doesn't cost a read of the whole log --
+    // nor, with a limit, a listing of it (see stream_history_inner).
+    // Takes no lock, same as every other read path.
+    async fn get_history(
+        &self,
+        req: Request<GetHistoryRequest>,
+    ) -> Result<Response<Self::GetHistoryStream>, Status> {
+        let r = req.into_inner();
         let table_uri = r.table_uri;
 
         let normalized_table_uri = self.normalize_and_check_table_uri(&table_uri)?;
+        let range = HistoryRange::parse(r.start_version, r.end_version, r.limit)?;
 
         let storage_opts = self.storage_opts.clone();
         let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_CAPACITY);
 
-        tokio::spawn(stream_active_files(normalized_table_uri, storage_opts, tx));
+        tokio::spawn(stream_history(
+            normalized_table_uri,
+            storage_opts,
+            range,
+            tx,
+        ));
 
-        let stream: Self::ListActiveFilesStream = Box::pin(ReceiverStream::new(rx));
+        let stream: Self::GetHistoryStream = Box::pin(ReceiverStream::new(rx));
+        Ok(Response::new(stream))
+    }
+
+    type GetChangesStream = GetChangesResultStream;
+
+    // Server-streaming, structured exactly like get_history(): request
+    // validation here, everything touching storage in a spawned task (see
+    // stream_changes_inner), one commit file read per version as the
+    // client consumes the stream. Takes no lock, same as every other read
+    // path -- a version in the requested range is immutable once
+    // committed, so there is nothing for a concurrent Commit to race.
+    async fn get_changes(
+        &self,
+        req: Request<GetChangesRequest>,
+    ) -> Result<Response<Self::GetChangesStream>, Status> {
+        let r = req.into_inner();
+        let table_uri = r.table_uri;
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&table_uri)?;
+        let (start, end) = parse_changes_range(r.start_version, r.end_version)?;
+
+        let storage_opts = self.storage_opts.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_CAPACITY);
+
+        tokio::spawn(stream_changes(
+            normalized_table_uri,
+            storage_opts,
+            start,
+            end,
+            tx,
+        ));
+
+        let stream: Self::GetChangesStream = Box::pin(ReceiverStream::new(rx));
+        Ok(Response::new(stream))
+    }
+
+    type WatchTableStream = WatchTableResultStream;
+
+    // Server-streaming, structured like get_changes(): request validation
+    // here, the existence check and the subscription itself in a spawned
+    // task (see stream_watch_inner). Unlike every other stream, this one
+    // runs until the client cancels it. It never reads the log itself --
+    // the table's shared poller (see watch::table_watch) and Commit's own
+    // publish() feed it -- so each additional watcher of an
+    // already-watched table costs no extra storage traffic at all.
+    async fn watch_table(
+        &self,
+        req: Request<WatchTableRequest>,
+    ) -> Result<Response<Self::WatchTableStream>, Status> {
+        let r = req.into_inner();
+        let table_uri = r.table_uri;
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&table_uri)?;
+        let from_version = parse_from_version(r.from_version)?;
+
+        let storage_opts = self.storage_opts.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_CAPACITY);
+
+        tokio::spawn(stream_watch(
+            normalized_table_uri,
+            storage_opts,
+            self.watches.clone(),
+            from_version,
+            tx,
+        ));
+
+        let stream: Self::WatchTableStream = Box::pin(ReceiverStream::new(rx));
         Ok(Response::new(stream))
     }
+
+    /// Resolves a timestamp to the version that was latest at that moment
+    /// (see delta::history::version_at_timestamp for the resolution rules
+    /// and their cost) without opening the table itself -- so no snapshot
+    /// is loaded at all, only the `_delta_log/` listing and the handful of
+    /// commit files the binary search reads. Same lock-free read semantics
+    /// as get_table().
+    async fn get_version_at_timestamp(
+        &self,
+        req: Request<GetVersionAtTimestampRequest>,
+    ) -> Result<Response<GetVersionAtTimestampResponse>, Status> {
+        let r = req.into_inner();
+        let table_uri = r.table_uri;
+
+        let normalized_table_uri = self.normalize_and_check_table_uri(&table_uri)?;
+        let timestamp = parse_requested_timestamp(r.timestamp)?;
+
+        ensure_table_exists(&normalized_table_uri, self.storage_opts.clone()).await?;
+        let resolved =
+            version_at_timestamp(&normalized_table_uri, self.storage_opts.clone(), timestamp)
+                .await
+                .map_err(Status::from)?;
+
+        Ok(Response::new(GetVersionAtTimestampResponse {
+            version: resolved.version as i64,
+            commit_timestamp: resolved.commit_timestamp,
+        }))
+    }
 }
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use prost::Message;
+use tonic::Status;
+use url::Url;
+
+use crate::grpc::server::pb;
+
+/// How long an idle staged transaction is kept, and how much uncommitted
+/// data the server holds at most across them -- see
+/// config::commit::load_staging_limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StagingLimits {
+    /// Measured from a transaction's last BeginTransaction/StageActions,
+    /// not from Begin: a writer that keeps staging keeps it alive.
+    pub idle_ttl: Duration,
+    /// Across every open transaction: staged actions' encoded size, plus
+    /// each transaction's table_uri.
+    pub max_bytes: usize,
+}
+
+impl Default for StagingLimits {
+    fn default() -> Self {
+        Self {
+            idle_ttl: Duration::from_secs(30 * 60),
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// A transaction between BeginTransaction and CommitTransaction: the table
+/// it was begun on, the version read then, and everything staged so far.
+/// Kept for an idle TTL after its commit lands too, with nothing staged
+/// and `committed_version` set, so a CommitTransaction retried after a
+/// lost response gets that version back rather than NOT_FOUND.
+#[derive(Debug)]
+struct StagedTransaction {
+    table_uri: Url,
+    read_version: u64,
+    actions: Vec<pb::Action>,
+    bytes: usize,
+    expires_at: Instant,
+    /// Set while a CommitTransaction is applying it -- see checkout().
+    committing: bool,
+    committed_version: Option<i64>,
+}
+
+#[derive(Default)]
+struct Staged {
+    transactions: HashMap<String, StagedTransaction>,
+    bytes: usize,
+}
+
+impl Staged {
+    // Expired transactions go whenever the map is next touched, rather
+    // than on a timer: nothing depends on them going sooner, and a scan of
+    // the open transactions is cheap next to the RPC that triggered it.
+    fn evict_expired(&mut self, now: Instant) {
+        let mut freed = 0;
+        self.transactions.retain(|_, txn| {
+            let live = txn.committing || txn.expires_at > now;
+            if !live {
+                freed += txn.bytes;
+            }
+            live
+        });
+        self.bytes -= freed;
+    }
+
+    fn reserve(&mut self, bytes: usize, max_bytes: usize) -> Result<(), Status> {
+        if self.bytes + bytes > max_bytes {
+            return Err(Status::resource_exhausted(format!(
+                "staging {bytes} more bytes would exceed the server's {max_bytes}-byte limit for \
+                 staged transactions -- commit or abort open ones, or stage less at a time"
+            )));
+        }
+        self.bytes += bytes;
+        Ok(())
+    }
+}
+
+/// In-process store behind BeginTransaction/StageActions/
+/// CommitTransaction/AbortTransaction. A transaction lives on the replica
+/// that began it: another replica doesn't know its id, and a restart
+/// forgets every open one.
+#[derive(Clone, Default)]
+pub struct StagedTransactionManager {
+    staged: Arc<Mutex<Staged>>,
+    limits: StagingLimits,
+}
+
+fn not_found(txn_id: &str) -> Status {
+    Status::not_found(format!(
+        "no open transaction '{txn_id}' -- it was committed, aborted, expired after sitting \
+         idle, or begun on another server"
+    ))
+}
+
+fn committing(txn_id: &str) -> Status {
+    Status::failed_precondition(format!(
+        "transaction '{txn_id}' is being committed -- wait for that CommitTransaction to finish"
+    ))
+}
+
+fn committed(txn_id: &str, version: i64) -> Status {
+    Status::failed_precondition(format!(
+        "transaction '{txn_id}' was already committed as version {version}"
+    ))
+}
+
+// The one check stage() and abort() share: `txn_id` is open and nothing is
+// committing it.
+fn check_open(txn: Option<&StagedTransaction>, txn_id: &str) -> Result<(), Status> {
+    match txn {
+        None => Err(not_found(txn_id)),
+        Some(txn) if txn.committing => Err(committing(txn_id)),
+        Some(StagedTransaction {
+            committed_version: Some(version),
+            ..
+        }) => Err(committed(txn_id, *version)),
+        Some(_) => Ok(()),
+    }
+}
+
+impl StagedTransactionManager {
+    pub fn new(limits: StagingLimits) -> Self {
+        Self {
+            staged: Arc::default(),
+            limits,
+        }
+    }
+
+    pub fn limits(&self) -> StagingLimits {
+        self.limits
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Staged> {
+        // Nothing panics while holding it, but a poisoned map is still a
+        // consistent one: every update below completes or doesn't start.
+        let mut staged = self.staged.lock().unwrap_or_else(|e| e.into_inner());
+        staged.evict_expired(Instant::now());
+        staged
+    }
+
+    /// Opens a transaction on `table_uri` at `read_version`, returning its
+    /// id.
+    pub fn begin(&self, table_uri: Url, read_version: u64) -> Result<String, Status> {
+        let bytes = table_uri.as_str().len();
+        let mut staged = self.lock();
+        staged.reserve(bytes, self.limits.max_bytes)?;
+        let txn_id = uuid::Uuid::new_v4().to_string();
+        staged.transactions.insert(
+            txn_id.clone(),
+            StagedTransaction {
+                table_uri,
+                read_version,
+                actions: Vec::new(),
+                bytes,
+                expires_at: Instant::now() + self.limits.idle_ttl,
+                committing: false,
+                committed_version: None,
+            },
+        );
+        Ok(txn_id)
+    }
+
+    /// Appends `actions` to `txn_id`'s, returning how many it now holds.
+    /// Refused whole if it would take the server past its byte limit, the
+    /// transaction keeping what it already had.
+    pub fn stage(&self, txn_id: &str, actions: Vec<pb::Action>) -> Result<usize, Status> {
+        let bytes = actions.iter().map(Message::encoded_len).sum();
+        let mut staged = self.lock();
+        check_open(staged.transactions.get(txn_id), txn_id)?;
+        staged.reserve(bytes, self.limits.max_bytes)?;
+        let txn = staged
+            .transactions
+            .get_mut(txn_id)
+            .expect("checked just above, under the same lock");
+        txn.actions.extend(actions);
+        txn.bytes += bytes;
+        txn.expires_at = Instant::now() + self.limits.idle_ttl;
+        Ok(txn.actions.len())
+    }
+
+    /// Marks `txn_id` as being committed and moves what it staged into
+    /// the returned Checkout, for CommitTransaction to commit -- or, if
+    /// its commit already landed, answers with that version instead. The
+    /// transaction's entry stays where it is, still counting its bytes
+    /// against the limit, and is neither staged to, aborted, committed
+    /// again nor expired until the Checkout is finished with: committed()
+    /// once the commit lands, close() once it fails in a way retrying
+    /// can't fix, or dropped on any other failure (or the client going
+    /// away mid-commit), which moves the actions back and leaves the
+    /// transaction open to retry.
+    pub fn checkout(&self, txn_id: &str) -> Result<CheckoutOutcome, Status> {
+        let mut staged = self.lock();
+        let txn = staged
+            .transactions
+            .get_mut(txn_id)
+            .ok_or_else(|| not_found(txn_id))?;
+        if txn.committing {
+            return Err(committing(txn_id));
+        }
+        if let Some(version) = txn.committed_version {
+            return Ok(CheckoutOutcome::Committed(version));
+        }
+        txn.committing = true;
+        Ok(CheckoutOutcome::Open(Checkout {
+            manager: self.clone(),
+            txn_id: txn_id.to_string(),
+            table_uri: txn.table_uri.clone(),
+            read_version: txn.read_version,
+            actions: std::mem::take(&mut txn.actions),
+            finished: false,
+        }))
+    }
+
+    /// Removes `txn_id` and drops what it staged -- AbortTransaction.
+    pub fn abort(&self, txn_id: &str) -> Result<(), Status> {
+        let mut staged = self.lock();
+        check_open(staged.transactions.get(txn_id), txn_id)?;
+        staged.remove(txn_id);
+        Ok(())
+    }
+}
+
+impl Staged {
+    fn remove(&mut self, txn_id: &str) {
+        if let Some(txn) = self.transactions.remove(txn_id) {
+            self.bytes -= txn.bytes;
+        }
+    }
+}
+
+/// What StagedTransactionManager::checkout found.
+pub enum CheckoutOutcome {
+    /// An open transaction, now being committed.
+    Open(Checkout),
+    /// A transaction whose commit already landed, as this version.
+    Committed(i64),
+}
+
+/// A transaction checked out for CommitTransaction, holding what it staged
+/// -- see StagedTransactionManager::checkout.
+pub struct Checkout {
+    manager: StagedTransactionManager,
+    txn_id: String,
+    pub table_uri: Url,
+    pub read_version: u64,
+    actions: Vec<pb::Action>,
+    finished: bool,
+}
+
+impl Checkout {
+    pub fn actions(&self) -> &[pb::Action] {
+        &self.actions
+    }
+
+    /// The commit landed as `version`: what was staged goes, and its bytes
+    /// with it, but the transaction stays behind for another idle TTL to
+    /// answer a retried CommitTransaction with that version.
+    pub fn committed(mut self, version: i64) {
+        let idle_ttl = self.manager.limits.idle_ttl;
+        let mut staged = self.manager.lock();
+        let mut freed = 0;
+        if let Some(txn) = staged.transactions.get_mut(&self.txn_id) {
+            freed = txn.bytes - txn.table_uri.as_str().len();
+            txn.bytes -= freed;
+            txn.committing = false;
+            txn.committed_version = Some(version);
+            txn.expires_at = Instant::now() + idle_ttl;
+        }
+        staged.bytes -= freed;
+        self.finished = true;
+    }
+
+    /// The commit failed for good: the transaction goes, and its bytes
+    /// with it.
+    pub fn close(mut self) {
+        self.manager.lock().remove(&self.txn_id);
+        self.finished = true;
+    }
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        // Back to open, with what it staged and a fresh idle TTL: the
+        // client's retry is what comes next.
+        let idle_ttl = self.manager.limits.idle_ttl;
+        if let Some(txn) = self.manager.lock().transactions.get_mut(&self.txn_id) {
+            txn.actions = std::mem::take(&mut self.actions);
+            txn.committing = false;
+            txn.expires_at = Instant::now() + idle_ttl;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(path: &str) -> pb::Action {
+        pb::Action {
+            action: Some(pb::action::Action::Add(pb::AddFile {
+                path: path.to_string(),
+                ..Default::default()
+            })),
+        }
+    }
+
+    fn table() -> Url {
+        Url::parse("file:///tables/events/").unwrap()
+    }
+
+    #[test]
+    fn staged_bytes_are_bounded_and_released() {
+        let one_add = add("part-0.parquet").encoded_len();
+        let manager = StagedTransactionManager::new(StagingLimits {
+            max_bytes: table().as_str().len() + 2 * one_add,
+            ..Default::default()
+        });
+        let txn_id = manager.begin(table(), 3).unwrap();
+        assert_eq!(
+            manager.stage(&txn_id, vec![add("part-0.parquet")]).unwrap(),
+            1
+        );
+        assert_eq!(
+            manager.stage(&txn_id, vec![add("part-1.parquet")]).unwrap(),
+            2
+        );
+        let err = manager
+            .stage(&txn_id, vec![add("part-2.parquet")])
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+        // A second transaction can't even begin while the first holds it all.
+        assert!(manager.begin(table(), 3).is_err());
+
+        let CheckoutOutcome::Open(checkout) = manager.checkout(&txn_id).unwrap() else {
+            panic!("the transaction is open");
+        };
+        assert_eq!((checkout.read_version, checkout.actions().len()), (3, 2));
+        checkout.committed(4);
+        assert!(matches!(
+            manager.checkout(&txn_id),
+            Ok(CheckoutOutcome::Committed(4))
+        ));
+        assert_eq!(
+            manager.stage(&txn_id, Vec::new()).unwrap_err().code(),
+            tonic::Code::FailedPrecondition
+        );
+        assert_eq!(manager.lock().bytes, table().as_str().len());
+        manager
+            .begin(table(), 4)
+            .expect("the staged bytes were released");
+    }
+
+    #[test]
+    fn a_closed_checkout_removes_the_transaction() {
+        let manager = StagedTransactionManager::new(StagingLimits::default());
+        let txn_id = manager.begin(table(), 0).unwrap();
+        manager.stage(&txn_id, vec![add("part-0.parquet")]).unwrap();
+        let CheckoutOutcome::Open(checkout) = manager.checkout(&txn_id).unwrap() else {
+            panic!("the transaction is open");
+        };
+        checkout.close();
+        assert_eq!(
+            manager.checkout(&txn_id).err().unwrap().code(),
+            tonic::Code::NotFound
+        );
+        assert_eq!(manager.lock().bytes, 0);
+    }
+
+    #[test]
+    fn a_checkout_dropped_without_closing_leaves_the_transaction_open() {
+        let manager = StagedTransactionManager::new(StagingLimits::default());
+        let txn_id = manager.begin(table(), 0).unwrap();
+        manager.stage(&txn_id, vec![add("part-0.parquet")]).unwrap();
+
+        let CheckoutOutcome::Open(checkout) = manager.checkout(&txn_id).unwrap() else {
+            panic!("the transaction is open");
+        };
+        // What was staged moved into the checkout rather than being copied.
+        assert!(manager.lock().transactions[&txn_id].actions.is_empty());
+        assert_eq!(checkout.actions().len(), 1);
+        // Mid-commit it can't be changed, committed twice or aborted...
+        for err in [
+            manager.stage(&txn_id, Vec::new()).unwrap_err(),
+            manager.checkout(&txn_id).err().unwrap(),
+            manager.abort(&txn_id).unwrap_err(),
+        ] {
+            assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+        }
+        // ...nor expire, however long the commit takes.
+        let past = Instant::now() - Duration::from_secs(1);
+        manager
+            .lock()
+            .transactions
+            .get_mut(&txn_id)
+            .unwrap()
+            .expires_at = past;
+        assert!(manager.lock().transactions.contains_key(&txn_id));
+
+        // A failed commit: still open, with everything it staged and a
+        // fresh idle TTL.
+        drop(checkout);
+        let staged = manager.lock();
+        let txn = &staged.transactions[&txn_id];
+        assert!(!txn.committing);
+        assert_eq!(txn.actions.len(), 1);
+        assert!(txn.expires_at > Instant::now());
+    }
+
+    #[test]
+    fn idle_transactions_expire() {
+        let manager = StagedTransactionManager::new(StagingLimits {
+            idle_ttl: Duration::ZERO,
+            ..Default::default()
+        });
+        let txn_id = manager.begin(table(), 0).unwrap();
+        let err = manager
+            .stage(&txn_id, vec![add("part-0.parquet")])
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+        assert_eq!(manager.lock().bytes, 0);
+    }
+}
@@ -3,7 +3,8 @@
 //! picture). `delta` wraps delta-rs (opening tables, committing actions);
 //! `grpc` is the DeltaTxnService implementation and its protobuf<->kernel
 //! type mapping; `locking` provides optional per-table_uri commit
-//! serialization; `config`/`telemetry` are startup configuration and
+//! serialization; `watch` backs WatchTable's per-table version feed;
+//! `config`/`telemetry` are startup configuration and
 //! observability. main.rs (not part of this library crate) wires all of
 //! it into a running server.
 
@@ -12,3 +13,4 @@ pub mod delta;
 pub mod grpc;
 pub mod locking;
 pub mod telemetry;
+pub mod watch;
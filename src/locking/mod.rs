@@ -1,6 +1,8 @@
 //! Optional in-process per-table_uri commit locking (TableLockManager),
 //! held for the duration of one Commit RPC's version-check-then-write
-//! sequence (see grpc::server::DeltaTxnGrpcServer::commit()). Purely an
+//! sequence (see grpc::server::DeltaTxnGrpcServer::commit()), or for every
+//! table a MultiTableCommit touches, taken in a fixed order (see
+//! TableLockManager::locks_for). Purely an
 //! optimization: it reduces how often two concurrent commits to the same
 //! table race each other into a storage-level conflict delta-rs's own
 //! optimistic-concurrency retry has to resolve the hard way -- it is not
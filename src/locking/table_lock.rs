@@ -22,6 +22,23 @@ impl TableLockManager {
         }
     }
 
+    /// lock_for() for several tables at once, in sorted table_uri order
+    /// (duplicates dropped) -- the order their guards must then be taken
+    /// in, one after another. Every holder of more than one lock going
+    /// through here is what keeps two of them from each holding a lock
+    /// the other is waiting on: whichever gets the lowest shared table_uri
+    /// first gets the rest too. A single lock_for() holder can't take part
+    /// in a cycle at all.
+    pub fn locks_for<'a>(&self, table_uris: impl IntoIterator<Item = &'a str>) -> Vec<TableLock> {
+        let mut table_uris: Vec<&str> = table_uris.into_iter().collect();
+        table_uris.sort_unstable();
+        table_uris.dedup();
+        table_uris
+            .into_iter()
+            .map(|table_uri| self.lock_for(table_uri))
+            .collect()
+    }
+
     // Correctness-critical: the ref_count==0 check and the map removal must
     // happen as one atomic step under this key's DashMap shard lock, not as
     // two separate operations (a `.load()` followed later by a `.remove()`,
@@ -174,4 +191,40 @@ mod tests {
             manager.locks.len()
         );
     }
+
+    // Two kinds of multi-table holder asking for the same pair of tables in
+    // opposite orders: taken as asked, each can end up holding one lock
+    // and waiting forever on the other's.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn locks_for_in_opposite_orders_never_deadlocks() {
+        let manager = TableLockManager::default();
+        let mut handles = Vec::new();
+        for i in 0..16 {
+            let manager = manager.clone();
+            handles.push(tokio::spawn(async move {
+                let tables = if i % 2 == 0 {
+                    ["facts", "dims"]
+                } else {
+                    ["dims", "facts"]
+                };
+                for _ in 0..50 {
+                    let locks = manager.locks_for(tables);
+                    let mut guards = Vec::new();
+                    for lock in &locks {
+                        guards.push(lock.lock().await);
+                        tokio::task::yield_now().await;
+                    }
+                }
+            }));
+        }
+        let all = async {
+            for handle in handles {
+                handle.await.unwrap();
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(30), all)
+            .await
+            .expect("lock holders deadlocked");
+        assert_eq!(manager.locks_for(["b", "a", "b"]).len(), 2);
+    }
 }
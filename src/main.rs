@@ -10,7 +10,12 @@ use tokio::signal;
 use tonic::transport::{Identity, Server, ServerTlsConfig};
 use tracing::info;
 
+use delta_txn_service::config::commit::{
+    load_commit_stream_limits, load_coordinator_uri, load_idempotency_lookback,
+    load_schema_evolution_policy, load_staging_limits,
+};
 use delta_txn_service::config::grpc::load_grpc_config;
+use delta_txn_service::delta::multi_table::LEASE_TTL;
 use delta_txn_service::grpc::auth::make_auth_interceptor;
 use delta_txn_service::grpc::server::pb::delta_txn_service_server::DeltaTxnServiceServer;
 use delta_txn_service::grpc::server::DeltaTxnGrpcServer;
@@ -183,6 +188,27 @@ fn spawn_storage_health_probe(
     });
 }
 
+/// Re-runs recover_multi_table_commits every LEASE_TTL for the life of the
+/// process: startup alone only covers a server that comes back, and a
+/// replica that's scaled away or crash-loops never does. Whichever replica
+/// sharing the coordinator log gets to an expired lease first finishes
+/// that commit. Each pass logs whatever it couldn't do itself and the next
+/// tick tries again.
+fn spawn_multi_table_recovery(svc: DeltaTxnGrpcServer) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(LEASE_TTL);
+        // The first tick is immediate, and startup has just done a pass.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let finished = svc.recover_multi_table_commits().await;
+            if finished > 0 {
+                info!(finished, "finished unfinished multi-table commits");
+            }
+        }
+    });
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Must run before anything that might tracing::info!/error! -- it's
@@ -194,7 +220,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let grpc_config = load_grpc_config()?;
 
-    let svc = DeltaTxnGrpcServer::new();
+    let schema_evolution = load_schema_evolution_policy()?;
+    info!(policy = %schema_evolution, "schema evolution policy");
+
+    let staging_limits = load_staging_limits()?;
+    info!(
+        idle_ttl_secs = staging_limits.idle_ttl.as_secs(),
+        max_bytes = staging_limits.max_bytes,
+        "staged transaction limits"
+    );
+
+    let commit_stream_limits = load_commit_stream_limits()?;
+    info!(
+        idle_timeout_secs = commit_stream_limits.idle_timeout.as_secs(),
+        max_bytes = commit_stream_limits.max_bytes,
+        "commit stream limits"
+    );
+
+    let idempotency_lookback = load_idempotency_lookback()?;
+    info!(versions = idempotency_lookback, "idempotency key lookback");
+
+    let mut svc = DeltaTxnGrpcServer::new()
+        .with_schema_evolution(schema_evolution)
+        .with_staging_limits(staging_limits)
+        .with_commit_stream_limits(commit_stream_limits)
+        .with_idempotency_lookback(idempotency_lookback);
+    match load_coordinator_uri()? {
+        Some(coordinator_uri) => {
+            info!(%coordinator_uri, "multi-table commit coordinator log");
+            svc = svc.with_coordinator_uri(coordinator_uri)?;
+            // Before serving: a MultiTableCommit left unfinished (by this
+            // server before a restart, or another sharing the log) is
+            // finished before anything new can touch its tables. Not a
+            // reason to refuse to start if it can't be -- the periodic pass
+            // carries on.
+            let finished = svc.recover_multi_table_commits().await;
+            if finished > 0 {
+                info!(finished, "finished unfinished multi-table commits");
+            }
+            spawn_multi_table_recovery(svc.clone());
+        }
+        None => info!("DELTA_TXN_COORDINATOR_URI is not set: MultiTableCommit is disabled"),
+    }
     // Tonic's per-service interceptor (metadata-only, runs after tower's
     // own Layer stack below has already routed the request to this
     // service) -- not a tower Layer itself, so it composes with
@@ -0,0 +1,12 @@
+//! In-process per-table_uri version watching (TableWatchManager), backing
+//! the WatchTable RPC (see grpc::server::DeltaTxnGrpcServer::watch_table()).
+//! Every WatchTable stream for the same table shares one watch channel and
+//! one background poller of that table's log tail, so N clients watching a
+//! table cost one LIST per poll interval rather than N; Commit publishes
+//! the versions it writes straight into the channel, so those reach
+//! watchers without waiting for the next poll. Like locking, purely
+//! in-process: a commit made through a *different* replica of this
+//! service is, from here, just another external writer the poller picks
+//! up.
+
+pub mod table_watch;
@@ -0,0 +1,183 @@
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::warn;
+use url::Url;
+
+use crate::delta::history::latest_version_after;
+
+// How often a watched table's log tail is checked for commits made by
+// writers other than this server. Commits through this server's own Commit
+// skip the wait entirely (see TableWatchManager::publish), so this only
+// bounds how stale an *external* commit's announcement can be -- and, with
+// one poller per table however many clients watch it, how often that
+// table's `_delta_log/` gets listed.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// The newest version known for a table, or `None` until the first poll (or
+// publish) has found one. Only ever moves forward -- see advance().
+type LatestVersion = watch::Sender<Option<u64>>;
+
+#[derive(Clone, Default)]
+pub struct TableWatchManager {
+    watches: Arc<DashMap<String, Arc<LatestVersion>>>,
+}
+
+impl TableWatchManager {
+    /// A receiver tracking `table_uri`'s newest version. The first
+    /// subscriber for a table starts its poller; the poller stops itself
+    /// (and drops the table's entry) once the last receiver is gone.
+    /// `table_uri` must already be normalized -- it's the key Commit's
+    /// publish() looks the channel up by, too.
+    pub fn subscribe(
+        &self,
+        table_uri: &Url,
+        storage_opts: HashMap<String, String>,
+    ) -> watch::Receiver<Option<u64>> {
+        match self.watches.entry(table_uri.as_str().to_string()) {
+            dashmap::mapref::entry::Entry::Occupied(occupied) => occupied.get().subscribe(),
+            dashmap::mapref::entry::Entry::Vacant(vacant) => {
+                let (sender, receiver) = watch::channel(None);
+                let sender = Arc::new(sender);
+                vacant.insert(sender.clone());
+                tokio::spawn(
+                    self.clone()
+                        .poll_log_tail(table_uri.clone(), storage_opts, sender),
+                );
+                receiver
+            }
+        }
+    }
+
+    /// Announces `version` to every current watcher of `table_uri` (same
+    /// normalized key as subscribe()), if there are any -- called by Commit
+    /// right after a successful write. A no-op for an unwatched table.
+    pub fn publish(&self, table_uri: &str, version: u64) {
+        if let Some(sender) = self.watches.get(table_uri) {
+            advance(&sender, version);
+        }
+    }
+
+    async fn poll_log_tail(
+        self,
+        table_uri: Url,
+        storage_opts: HashMap<String, String>,
+        sender: Arc<LatestVersion>,
+    ) {
+        loop {
+            if self.remove_if_unwatched(table_uri.as_str(), &sender) {
+                return;
+            }
+
+            // Starts from whatever is already known, including versions
+            // publish() announced since the last poll, so each poll lists
+            // only what's newer than that.
+            let known = *sender.borrow();
+            match latest_version_after(&table_uri, storage_opts.clone(), known).await {
+                Ok(Some(version)) => advance(&sender, version),
+                Ok(None) => {}
+                // Transient storage errors just mean this poll found
+                // nothing; watchers keep their stream and the next poll
+                // tries again.
+                Err(e) => warn!(table_uri = %table_uri, error = %e, "watch poll failed"),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                // Wakes early once the last watcher disconnects, so an
+                // unwatched table's poller exits promptly instead of
+                // listing its log one more time.
+                _ = sender.closed() => {}
+            }
+        }
+    }
+
+    // Same atomic check-and-remove under the key's DashMap shard lock as
+    // TableLockManager::remove_if_unused (see its comment for the race a
+    // separate check-then-remove would have): a concurrent subscribe() for
+    // this key either ran before this -- its receiver is counted, and the
+    // poller keeps going -- or runs after, finds no entry, and starts a
+    // fresh channel and poller of its own. It can never attach itself to a
+    // channel whose poller has already exited.
+    fn remove_if_unwatched(&self, key: &str, sender: &Arc<LatestVersion>) -> bool {
+        match self.watches.entry(key.to_string()) {
+            dashmap::mapref::entry::Entry::Occupied(occupied) => {
+                if !Arc::ptr_eq(occupied.get(), sender) {
+                    return true; // superseded; not this poller's entry anymore.
+                }
+                if sender.receiver_count() == 0 {
+                    occupied.remove();
+                    return true;
+                }
+                false
+            }
+            dashmap::mapref::entry::Entry::Vacant(_) => true,
+        }
+    }
+}
+
+// Moves the announced version forward, never back: a poll that started
+// before a Commit's publish() can finish after it with an older answer.
+fn advance(sender: &LatestVersion, version: u64) {
+    sender.send_if_modified(|latest| {
+        if *latest < Some(version) {
+            *latest = Some(version);
+            true
+        } else {
+            false
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager_with_entry(key: &str) -> (TableWatchManager, Arc<LatestVersion>) {
+        let manager = TableWatchManager::default();
+        let (sender, _) = watch::channel(None);
+        let sender = Arc::new(sender);
+        manager.watches.insert(key.to_string(), sender.clone());
+        (manager, sender)
+    }
+
+    #[test]
+    fn publish_only_ever_moves_the_version_forward() {
+        let (manager, sender) = manager_with_entry("t");
+        let mut receiver = sender.subscribe();
+
+        manager.publish("t", 3);
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(*receiver.borrow_and_update(), Some(3));
+
+        manager.publish("t", 2);
+        manager.publish("t", 3);
+        assert!(
+            !receiver.has_changed().unwrap(),
+            "an older or repeated version must not wake watchers"
+        );
+        assert_eq!(*receiver.borrow(), Some(3));
+    }
+
+    #[test]
+    fn publish_to_an_unwatched_table_is_a_no_op() {
+        let manager = TableWatchManager::default();
+        manager.publish("nobody-watching", 7);
+        assert!(manager.watches.is_empty());
+    }
+
+    #[test]
+    fn the_entry_is_removed_only_once_its_last_watcher_is_gone() {
+        let (manager, sender) = manager_with_entry("t");
+        let receiver = sender.subscribe();
+
+        assert!(!manager.remove_if_unwatched("t", &sender));
+        assert_eq!(manager.watches.len(), 1);
+
+        drop(receiver);
+        assert!(manager.remove_if_unwatched("t", &sender));
+        assert!(manager.watches.is_empty());
+    }
+}
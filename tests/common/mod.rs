@@ -23,17 +23,21 @@
 use std::collections::HashMap;
 use std::net::SocketAddr;
 
+use delta_txn_service::delta::schema_evolution::SchemaEvolutionPolicy;
 use delta_txn_service::grpc::auth::make_auth_interceptor;
 // `pub use`, not `use`: test files reference these as `common::pb::...`
 // rather than each needing their own `use delta_txn_service::grpc::...`.
+use delta_txn_service::grpc::commit_stream::CommitStreamLimits;
 pub use delta_txn_service::grpc::server::pb;
 use delta_txn_service::grpc::server::pb::delta_txn_service_client::DeltaTxnServiceClient;
 use delta_txn_service::grpc::server::pb::delta_txn_service_server::DeltaTxnServiceServer;
 use delta_txn_service::grpc::server::DeltaTxnGrpcServer;
+use delta_txn_service::grpc::staging::StagingLimits;
 use tempfile::TempDir;
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
 use tonic::transport::{Channel, Server};
+use url::Url;
 
 /// What to configure the server under test with -- everything defaults to
 /// "off" (no auth, no allowlist, no object-store credentials), matching an
@@ -49,6 +53,23 @@ pub struct TestServerConfig {
     /// majority of this suite) needs. Only `tests/e2e_minio.rs` sets this,
     /// to supply `AWS_ENDPOINT_URL`/credentials for a real MinIO backend.
     pub storage_opts: HashMap<String, String>,
+    /// Forwarded to `DeltaTxnGrpcServer::with_schema_evolution` -- the
+    /// default (additive) unless a test is exercising another policy.
+    pub schema_evolution: SchemaEvolutionPolicy,
+    /// Forwarded to `DeltaTxnGrpcServer::with_staging_limits` -- the
+    /// production defaults unless a test wants to hit one.
+    pub staging_limits: StagingLimits,
+    /// Forwarded to `DeltaTxnGrpcServer::with_commit_stream_limits`, the
+    /// same way.
+    pub commit_stream_limits: CommitStreamLimits,
+    /// Forwarded to `DeltaTxnGrpcServer::with_coordinator_uri`, followed
+    /// by the same `recover_multi_table_commits` main.rs runs before
+    /// serving -- `None` (MultiTableCommit disabled) unless a test is
+    /// exercising it.
+    pub coordinator_uri: Option<Url>,
+    /// Forwarded to `DeltaTxnGrpcServer::with_idempotency_lookback` when
+    /// set -- the production default otherwise.
+    pub idempotency_lookback: Option<u64>,
 }
 
 /// A running `DeltaTxnGrpcServer` plus the tempdir its `file://` tables
@@ -91,8 +112,20 @@ impl TestServer {
             .local_addr()
             .expect("failed to read test server listener's bound address");
 
-        let svc =
-            DeltaTxnGrpcServer::with_config(config.storage_opts, config.allowed_table_prefixes);
+        let mut svc =
+            DeltaTxnGrpcServer::with_config(config.storage_opts, config.allowed_table_prefixes)
+                .with_schema_evolution(config.schema_evolution)
+                .with_staging_limits(config.staging_limits)
+                .with_commit_stream_limits(config.commit_stream_limits);
+        if let Some(versions) = config.idempotency_lookback {
+            svc = svc.with_idempotency_lookback(versions);
+        }
+        if let Some(coordinator_uri) = config.coordinator_uri {
+            svc = svc
+                .with_coordinator_uri(coordinator_uri)
+                .expect("the coordinator log should open");
+            svc.recover_multi_table_commits().await;
+        }
         let svc =
             DeltaTxnServiceServer::with_interceptor(svc, make_auth_interceptor(config.api_key));
 
@@ -125,7 +158,15 @@ impl TestServer {
     /// tempdir -- give each test (or each table within a test) its own
     /// label so concurrent tests never collide on the same Delta log.
     pub fn new_table_uri(&self, label: &str) -> String {
-        format!("file://{}", self.tmp_dir.path().join(label).display())
+        format!("file://{}", self.table_path(label).display())
+    }
+
+    /// The local filesystem path behind `new_table_uri(label)` -- for the
+    /// handful of tests that need to reach into a table's `_delta_log`
+    /// directly (e.g. simulating log retention cleanup), rather than only
+    /// ever going through the server.
+    pub fn table_path(&self, label: &str) -> std::path::PathBuf {
+        self.tmp_dir.path().join(label)
     }
 
     /// A plain, unauthenticated client -- what most tests want. For a
@@ -181,6 +222,8 @@ pub fn sample_protocol() -> pb::Protocol {
     pb::Protocol {
         min_reader_version: 1,
         min_writer_version: 2,
+        reader_features: Vec::new(),
+        writer_features: Vec::new(),
     }
 }
 
@@ -204,6 +247,7 @@ pub fn sample_metadata(table_name: &str) -> pb::TableMetadata {
         partition_columns: Vec::new(),
         configuration: HashMap::new(),
         created_time: 0,
+        schema: None,
     }
 }
 
@@ -221,6 +265,57 @@ pub fn create_table_actions(table_name: &str) -> Vec<pb::Action> {
     ]
 }
 
+/// Like `create_table_actions`, but for a table partitioned by
+/// `day` (date), `hour` (integer) and `region` (string), on top of the
+/// sample schema's own `id`/`amount` columns -- for the tests exercising
+/// partition-aware reads.
+pub fn create_partitioned_table_actions(table_name: &str) -> Vec<pb::Action> {
+    let schema_string = serde_json::json!({
+        "type": "struct",
+        "fields": [
+            {"name": "id", "type": "long", "nullable": false, "metadata": {}},
+            {"name": "amount", "type": "double", "nullable": true, "metadata": {}},
+            {"name": "day", "type": "date", "nullable": true, "metadata": {}},
+            {"name": "hour", "type": "integer", "nullable": true, "metadata": {}},
+            {"name": "region", "type": "string", "nullable": true, "metadata": {}}
+        ]
+    })
+    .to_string();
+    vec![
+        pb::Action {
+            action: Some(pb::action::Action::Protocol(sample_protocol())),
+        },
+        pb::Action {
+            action: Some(pb::action::Action::MetaData(pb::TableMetadata {
+                schema_string,
+                partition_columns: vec![
+                    "day".to_string(),
+                    "hour".to_string(),
+                    "region".to_string(),
+                ],
+                ..sample_metadata(table_name)
+            })),
+        },
+    ]
+}
+
+/// `add_file_action` for a file in the given partition of a
+/// `create_partitioned_table_actions` table: `(day, hour, region)`, each in
+/// Delta's partition-value serialization. An empty string is a null
+/// partition value.
+pub fn partitioned_add_file_action(path: &str, partition: (&str, &str, &str)) -> pb::Action {
+    let mut action = add_file_action(path, 1);
+    if let Some(pb::action::Action::Add(add)) = &mut action.action {
+        let (day, hour, region) = partition;
+        add.partition_values = HashMap::from([
+            ("day".to_string(), day.to_string()),
+            ("hour".to_string(), hour.to_string()),
+            ("region".to_string(), region.to_string()),
+        ]);
+    }
+    action
+}
+
 pub fn add_file_action(path: &str, num_records: i64) -> pb::Action {
     let mut columns = HashMap::new();
     columns.insert(
@@ -243,6 +338,10 @@ pub fn add_file_action(path: &str, num_records: i64) -> pb::Action {
                 columns,
             }),
             tags: HashMap::new(),
+            nullable_partition_values: Vec::new(),
+            deletion_vector: None,
+            base_row_id: None,
+            default_row_commit_version: None,
         })),
     }
 }
@@ -253,6 +352,7 @@ pub fn remove_file_action(path: &str) -> pb::Action {
             path: path.to_string(),
             deletion_timestamp: Some(1_700_000_002_000),
             data_change: pb::DataChange::True as i32,
+            deletion_vector: None,
         })),
     }
 }
@@ -267,5 +367,90 @@ pub fn commit_request(
         expected_version,
         actions,
         app_metadata: HashMap::new(),
+        idempotency_key: String::new(),
+        validate_only: false,
     }
 }
+
+/// The table's current version, by GetTable.
+pub async fn table_version(client: &mut DeltaTxnServiceClient<Channel>, table_uri: &str) -> i64 {
+    client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect("GetTable should succeed")
+        .into_inner()
+        .version
+}
+
+/// Every active file's path at the table's current version, sorted -- by
+/// ListActiveFiles, batches collected and the header skipped.
+pub async fn active_paths(
+    client: &mut DeltaTxnServiceClient<Channel>,
+    table_uri: &str,
+) -> Vec<String> {
+    listed_paths(
+        client,
+        pb::ListActiveFilesRequest {
+            table_uri: table_uri.to_string(),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// `active_paths` for any ListActiveFiles request -- filtered, with a
+/// predicate, pinned to a version.
+pub async fn listed_paths(
+    client: &mut DeltaTxnServiceClient<Channel>,
+    req: pb::ListActiveFilesRequest,
+) -> Vec<String> {
+    let mut stream = client
+        .list_active_files(req)
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+    let mut paths = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::list_active_files_response::Payload::Batch(batch)) = msg.payload {
+            paths.extend(batch.files.into_iter().map(|file| file.path));
+        }
+    }
+    paths.sort();
+    paths
+}
+
+/// Creates a `create_partitioned_table_actions` table at `table_uri` and
+/// appends `files` to it as version 1.
+pub async fn create_partitioned_table(
+    client: &mut DeltaTxnServiceClient<Channel>,
+    table_uri: &str,
+    files: Vec<pb::Action>,
+) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_partitioned_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .commit(commit_request(table_uri, Some(0), files))
+        .await
+        .expect("append should succeed");
+}
+
+/// One file per partition of a `create_partitioned_table_actions` table,
+/// named after it -- one of them in a null `region`.
+pub fn one_file_per_partition() -> Vec<pb::Action> {
+    vec![
+        partitioned_add_file_action("d16-h9-eu.parquet", ("2026-10-16", "9", "eu")),
+        partitioned_add_file_action("d17-h9-us.parquet", ("2026-10-17", "9", "us")),
+        partitioned_add_file_action("d17-h10-eu.parquet", ("2026-10-17", "10", "eu")),
+        partitioned_add_file_action("d17-h23-null.parquet", ("2026-10-17", "23", "")),
+        partitioned_add_file_action("d18-h0-us.parquet", ("2026-10-18", "0", "us")),
+    ]
+}
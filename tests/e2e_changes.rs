@@ -0,0 +1,283 @@
+//! GetChanges: the raw log actions of a version range, streamed version by
+//! version behind a header -- including the multi-batch case for a single
+//! large version, the caught-up incremental consumer, and the ways a range
+//! is rejected.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_table_actions, pb, remove_file_action, TestServer,
+};
+use pb::action::Action as PbAction;
+use pb::get_changes_response::Payload;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn changes_request(table_uri: &str, start: i64, end: Option<i64>) -> pb::GetChangesRequest {
+    pb::GetChangesRequest {
+        table_uri: table_uri.to_string(),
+        start_version: start,
+        end_version: end,
+    }
+}
+
+/// Drains a GetChanges stream into its header and batches, asserting the
+/// header-first framing along the way.
+async fn changes(
+    client: &mut Client,
+    req: pb::GetChangesRequest,
+) -> (pb::GetChangesHeader, Vec<pb::GetChangesBatch>) {
+    let mut stream = client
+        .get_changes(req)
+        .await
+        .expect("GetChanges should succeed")
+        .into_inner();
+
+    let header = match stream.message().await.unwrap().unwrap().payload {
+        Some(Payload::Header(h)) => h,
+        other => panic!("expected header as the first message, got {other:?}"),
+    };
+    let mut batches = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        match msg.payload {
+            Some(Payload::Batch(batch)) => batches.push(batch),
+            other => panic!("expected only batches after the header, got {other:?}"),
+        }
+    }
+    (header, batches)
+}
+
+fn add_paths(batch: &pb::GetChangesBatch) -> Vec<String> {
+    batch
+        .actions
+        .iter()
+        .filter_map(|a| match &a.action {
+            Some(PbAction::Add(add)) => Some(add.path.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// v0 create, v1 adds a+b, v2 removes a.
+async fn table_with_changes(client: &mut Client, table_uri: &str) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("clicks"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .commit(commit_request(
+            table_uri,
+            Some(0),
+            vec![
+                add_file_action("a.parquet", 1),
+                add_file_action("b.parquet", 2),
+            ],
+        ))
+        .await
+        .expect("append should succeed");
+    client
+        .commit(commit_request(
+            table_uri,
+            Some(1),
+            vec![remove_file_action("a.parquet")],
+        ))
+        .await
+        .expect("remove should succeed");
+}
+
+#[tokio::test]
+async fn get_changes_streams_each_versions_actions_in_order() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("clicks");
+    let mut client = server.connect().await;
+    table_with_changes(&mut client, &table_uri).await;
+
+    let (header, batches) = changes(&mut client, changes_request(&table_uri, 1, None)).await;
+    assert_eq!((header.start_version, header.end_version), (1, 2));
+    assert_eq!(
+        batches.iter().map(|b| b.version).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    let mut added = add_paths(&batches[0]);
+    added.sort();
+    assert_eq!(added, vec!["a.parquet", "b.parquet"]);
+    let stats = batches[0]
+        .actions
+        .iter()
+        .find_map(|a| match &a.action {
+            Some(PbAction::Add(add)) if add.path == "b.parquet" => add.stats.clone(),
+            _ => None,
+        })
+        .expect("the add's stats should come back");
+    assert_eq!(stats.num_records, 2);
+
+    let removed: Vec<_> = batches[1]
+        .actions
+        .iter()
+        .filter_map(|a| match &a.action {
+            Some(PbAction::Remove(r)) => Some((r.path.as_str(), r.data_change)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        removed,
+        vec![("a.parquet", pb::DataChange::True as i32)],
+        "the remove, with its recorded data_change"
+    );
+
+    assert!(batches.iter().all(|b| b.timestamp > 0));
+    assert!(
+        batches.iter().all(|b| b
+            .actions
+            .iter()
+            .any(|a| matches!(a.action, Some(PbAction::CommitInfo(_))))),
+        "every commit this service makes carries a CommitInfo"
+    );
+}
+
+#[tokio::test]
+async fn get_changes_from_version_zero_includes_protocol_and_metadata() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("clicks");
+    let mut client = server.connect().await;
+    table_with_changes(&mut client, &table_uri).await;
+
+    let (header, batches) = changes(&mut client, changes_request(&table_uri, 0, Some(0))).await;
+    assert_eq!((header.start_version, header.end_version), (0, 0));
+    assert_eq!(batches.len(), 1);
+    let actions = &batches[0].actions;
+    assert!(actions
+        .iter()
+        .any(|a| matches!(a.action, Some(PbAction::Protocol(_)))));
+    let metadata = actions
+        .iter()
+        .find_map(|a| match &a.action {
+            Some(PbAction::MetaData(m)) => Some(m),
+            _ => None,
+        })
+        .expect("version 0 should carry the table's metadata");
+    assert_eq!(metadata.name, "clicks");
+}
+
+#[tokio::test]
+async fn a_version_with_more_actions_than_one_batch_spans_several() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("clicks");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("clicks"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    // FILE_BATCH_SIZE (server.rs) is 1000.
+    const FILE_COUNT: usize = 1500;
+    let actions = (0..FILE_COUNT)
+        .map(|n| add_file_action(&format!("part-{n}.parquet"), 1))
+        .collect();
+    client
+        .commit(commit_request(&table_uri, Some(0), actions))
+        .await
+        .expect("large append should succeed");
+
+    let (_, batches) = changes(&mut client, changes_request(&table_uri, 1, None)).await;
+    assert!(batches.len() > 1, "one version should span several batches");
+    assert!(batches.iter().all(|b| b.version == 1));
+    let added: usize = batches.iter().map(|b| add_paths(b).len()).sum();
+    assert_eq!(added, FILE_COUNT);
+}
+
+#[tokio::test]
+async fn a_caught_up_consumer_gets_just_the_header() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("clicks");
+    let mut client = server.connect().await;
+    table_with_changes(&mut client, &table_uri).await;
+
+    let (header, batches) = changes(&mut client, changes_request(&table_uri, 3, None)).await;
+    assert_eq!((header.start_version, header.end_version), (3, 2));
+    assert!(batches.is_empty());
+}
+
+#[tokio::test]
+async fn malformed_ranges_are_invalid_arguments() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("clicks");
+    let mut client = server.connect().await;
+    table_with_changes(&mut client, &table_uri).await;
+
+    for req in [
+        changes_request(&table_uri, -1, None),
+        changes_request(&table_uri, 2, Some(1)),
+    ] {
+        // Rejected synchronously, before the stream even starts.
+        let err = client
+            .get_changes(req.clone())
+            .await
+            .expect_err("a malformed range must fail the RPC call itself");
+        assert_eq!(err.code(), Code::InvalidArgument, "request: {req:?}");
+    }
+}
+
+/// Opens a GetChanges stream expected to fail, returning the error it
+/// carries as its first item.
+async fn changes_error(client: &mut Client, req: pb::GetChangesRequest) -> tonic::Status {
+    let mut stream = client
+        .get_changes(req)
+        .await
+        .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
+        .into_inner();
+    stream
+        .message()
+        .await
+        .expect_err("the stream's first (and only) item should be an error")
+}
+
+#[tokio::test]
+async fn an_end_version_past_the_latest_is_not_found() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("clicks");
+    let mut client = server.connect().await;
+    table_with_changes(&mut client, &table_uri).await;
+
+    let err = changes_error(&mut client, changes_request(&table_uri, 0, Some(5))).await;
+    assert_eq!(err.code(), Code::NotFound);
+    assert!(
+        err.message().contains("latest version is 2"),
+        "got: {}",
+        err.message()
+    );
+}
+
+#[tokio::test]
+async fn a_range_reaching_into_cleaned_up_history_is_a_failed_precondition() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("clicks");
+    let mut client = server.connect().await;
+    table_with_changes(&mut client, &table_uri).await;
+
+    let log_dir = server.table_path("clicks").join("_delta_log");
+    std::fs::remove_file(log_dir.join(format!("{:020}.json", 1)))
+        .expect("commit file should exist before cleanup");
+
+    let err = changes_error(&mut client, changes_request(&table_uri, 0, None)).await;
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert!(
+        err.message().contains("version 1"),
+        "got: {}",
+        err.message()
+    );
+
+    // A range entirely after the gap is unaffected.
+    let (_, batches) = changes(&mut client, changes_request(&table_uri, 2, None)).await;
+    assert_eq!(batches.len(), 1);
+}
@@ -0,0 +1,294 @@
+//! CommitStream: a compaction-sized action list uploaded in chunks and
+//! committed as one version, a table created through it, malformed
+//! streams rejected, and a stream abandoned midway, closed without its end
+//! message, left idle or grown past the byte limit committing nothing.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{
+    active_paths, add_file_action, commit_request, create_table_actions, pb, remove_file_action,
+    table_version, TestServer, TestServerConfig,
+};
+use delta_txn_service::grpc::commit_stream::CommitStreamLimits;
+use pb::commit_stream_request::Payload;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn header(request: pb::CommitRequest) -> pb::CommitStreamRequest {
+    pb::CommitStreamRequest {
+        payload: Some(Payload::Header(request)),
+    }
+}
+
+fn chunk(actions: Vec<pb::Action>) -> pb::CommitStreamRequest {
+    pb::CommitStreamRequest {
+        payload: Some(Payload::Actions(pb::CommitActionChunk { actions })),
+    }
+}
+
+fn end(action_count: usize) -> pb::CommitStreamRequest {
+    pb::CommitStreamRequest {
+        payload: Some(Payload::End(pb::CommitStreamEnd {
+            action_count: action_count as i64,
+        })),
+    }
+}
+
+async fn commit_stream(
+    client: &mut Client,
+    messages: Vec<pb::CommitStreamRequest>,
+) -> Result<pb::CommitResponse, tonic::Status> {
+    client
+        .commit_stream(tokio_stream::iter(messages))
+        .await
+        .map(|response| response.into_inner())
+}
+
+#[tokio::test]
+async fn a_chunked_compaction_commits_as_one_version() {
+    const SMALL_FILES: usize = 1200;
+    const CHUNK: usize = 250;
+
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+
+    // Created through the stream too: the header's own actions, no chunks.
+    let created = commit_stream(
+        &mut client,
+        vec![
+            header(commit_request(
+                &table_uri,
+                None,
+                create_table_actions("events"),
+            )),
+            end(create_table_actions("events").len()),
+        ],
+    )
+    .await
+    .expect("a streamed create should succeed");
+    assert_eq!(created.committed_version, 0);
+
+    let small: Vec<String> = (0..SMALL_FILES)
+        .map(|i| format!("small-{i:05}.parquet"))
+        .collect();
+    let appends = small.iter().map(|path| add_file_action(path, 1)).collect();
+    client
+        .commit(commit_request(&table_uri, Some(0), appends))
+        .await
+        .expect("appending the small files should succeed");
+
+    // Every small file replaced by three big ones, removes and adds split
+    // across chunks however they fall.
+    let mut compaction: Vec<pb::Action> =
+        small.iter().map(|path| remove_file_action(path)).collect();
+    compaction.extend((0..3).map(|i| add_file_action(&format!("big-{i}.parquet"), 400)));
+    let mut messages = vec![header(commit_request(&table_uri, Some(1), Vec::new()))];
+    messages.extend(compaction.chunks(CHUNK).map(|c| chunk(c.to_vec())));
+    messages.push(end(compaction.len()));
+    assert!(messages.len() > 5);
+
+    let response = commit_stream(&mut client, messages)
+        .await
+        .expect("the streamed compaction should commit");
+    assert_eq!(response.committed_version, 2);
+    assert_eq!(
+        active_paths(&mut client, &table_uri).await,
+        ["big-0.parquet", "big-1.parquet", "big-2.parquet"]
+    );
+}
+
+#[tokio::test]
+async fn malformed_streams_are_rejected() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let first = || header(commit_request(&table_uri, Some(0), Vec::new()));
+    let adds = || chunk(vec![add_file_action("part-0.parquet", 1)]);
+    let cases = [
+        ("an empty stream", Vec::new(), "without a header"),
+        ("a chunk before the header", vec![adds(), first()], "header"),
+        ("two headers", vec![first(), adds(), first()], "exactly one"),
+        (
+            "a message with no payload",
+            vec![first(), pb::CommitStreamRequest { payload: None }],
+            "no payload",
+        ),
+        (
+            "a chunk after the end",
+            vec![first(), end(0), adds()],
+            "must be its last",
+        ),
+        (
+            "an end counting actions that never arrived",
+            vec![first(), adds(), end(2)],
+            "counts 2 action(s), but 1 arrived",
+        ),
+    ];
+    for (case, messages, expected) in cases {
+        let err = commit_stream(&mut client, messages).await.expect_err(case);
+        assert_eq!(err.code(), Code::InvalidArgument, "{case}");
+        assert!(
+            err.message().contains(expected),
+            "{case}: {}",
+            err.message()
+        );
+    }
+
+    // A chunk's actions are checked like Commit's, once they're assembled.
+    let mut unspecified = add_file_action("part-0.parquet", 1);
+    if let Some(pb::action::Action::Add(add)) = &mut unspecified.action {
+        add.data_change = pb::DataChange::Unspecified as i32;
+    }
+    let err = commit_stream(
+        &mut client,
+        vec![first(), adds(), chunk(vec![unspecified]), end(2)],
+    )
+    .await
+    .expect_err("an unconvertible action fails the whole stream");
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    assert_eq!(table_version(&mut client, &table_uri).await, 0);
+}
+
+#[tokio::test]
+async fn an_abandoned_stream_commits_nothing() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut streaming_client = client.clone();
+    let call = tokio::spawn(async move {
+        streaming_client
+            .commit_stream(tokio_stream::wrappers::ReceiverStream::new(rx))
+            .await
+    });
+    tx.send(header(commit_request(&table_uri, Some(0), Vec::new())))
+        .await
+        .unwrap();
+    tx.send(chunk(vec![add_file_action("part-0.parquet", 1)]))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    // The client goes away mid-upload, never closing the stream.
+    call.abort();
+    drop(tx);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(table_version(&mut client, &table_uri).await, 0);
+    assert!(active_paths(&mut client, &table_uri).await.is_empty());
+
+    // A client that closes the stream early -- its own error path, say --
+    // hasn't sent everything, whatever arrived so far.
+    let err = commit_stream(
+        &mut client,
+        vec![
+            header(commit_request(&table_uri, Some(0), Vec::new())),
+            chunk(vec![remove_file_action("part-0.parquet")]),
+        ],
+    )
+    .await
+    .expect_err("a stream closed without its end message");
+    assert_eq!(err.code(), Code::Aborted);
+    assert!(err.message().contains("end message"), "{}", err.message());
+    assert_eq!(table_version(&mut client, &table_uri).await, 0);
+
+    // Nothing was left locked: the retried upload goes through.
+    let response = commit_stream(
+        &mut client,
+        vec![
+            header(commit_request(&table_uri, Some(0), Vec::new())),
+            chunk(vec![add_file_action("part-0.parquet", 1)]),
+            end(1),
+        ],
+    )
+    .await
+    .expect("the retried upload should commit");
+    assert_eq!(response.committed_version, 1);
+}
+
+#[tokio::test]
+async fn streams_are_bounded_in_size_and_idle_time() {
+    let server = TestServer::start(TestServerConfig {
+        commit_stream_limits: CommitStreamLimits {
+            idle_timeout: Duration::from_secs(1),
+            max_bytes: 4096,
+        },
+        ..Default::default()
+    })
+    .await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let too_many: Vec<_> = (0..100)
+        .map(|i| add_file_action(&format!("part-{i}.parquet"), 1))
+        .collect();
+    let count = too_many.len();
+    let err = commit_stream(
+        &mut client,
+        vec![
+            header(commit_request(&table_uri, Some(0), Vec::new())),
+            chunk(too_many),
+            end(count),
+        ],
+    )
+    .await
+    .expect_err("more than the server holds");
+    assert_eq!(err.code(), Code::ResourceExhausted);
+
+    // A stream that goes quiet is given up on rather than held open.
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    tx.send(header(commit_request(&table_uri, Some(0), Vec::new())))
+        .await
+        .unwrap();
+    let err = client
+        .commit_stream(tokio_stream::wrappers::ReceiverStream::new(rx))
+        .await
+        .expect_err("an idle stream is abandoned");
+    assert_eq!(err.code(), Code::Aborted);
+    assert!(err.message().contains("sent nothing"), "{}", err.message());
+    drop(tx);
+
+    // The rejected streams' bytes were given back.
+    let response = commit_stream(
+        &mut client,
+        vec![
+            header(commit_request(&table_uri, Some(0), Vec::new())),
+            chunk(vec![add_file_action("part-0.parquet", 1)]),
+            end(1),
+        ],
+    )
+    .await
+    .expect("a small upload still fits");
+    assert_eq!(response.committed_version, 1);
+    assert_eq!(table_version(&mut client, &table_uri).await, 1);
+}
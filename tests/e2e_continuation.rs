@@ -0,0 +1,207 @@
+//! Resumable ListActiveFiles: every batch carries a continuation token, and
+//! a request carrying one picks up right after that batch at the version
+//! the original stream was pinned to -- even once the table has moved on --
+//! plus the ways a token is rejected.
+
+mod common;
+
+use common::{add_file_action, commit_request, create_table_actions, pb, TestServer};
+use delta_txn_service::grpc::continuation::ActiveFilesCursor;
+use pb::list_active_files_response::Payload;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+// FILE_BATCH_SIZE (server.rs) is 1000, so this lists as three batches.
+const FILE_COUNT: usize = 2500;
+
+async fn large_table(client: &mut Client, table_uri: &str) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    let actions = (0..FILE_COUNT)
+        .map(|n| add_file_action(&format!("part-{n}.parquet"), 1))
+        .collect();
+    client
+        .commit(commit_request(table_uri, Some(0), actions))
+        .await
+        .expect("large append should succeed");
+}
+
+fn resume_request(table_uri: &str, token: Vec<u8>) -> pb::ListActiveFilesRequest {
+    pb::ListActiveFilesRequest {
+        table_uri: table_uri.to_string(),
+        continuation_token: token,
+        ..Default::default()
+    }
+}
+
+/// Drains a ListActiveFiles stream into its header's version and batches.
+async fn list(
+    client: &mut Client,
+    req: pb::ListActiveFilesRequest,
+) -> (i64, Vec<pb::ListActiveFilesBatch>) {
+    let mut stream = client
+        .list_active_files(req)
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+    let version = match stream.message().await.unwrap().unwrap().payload {
+        Some(Payload::Header(h)) => h.version,
+        other => panic!("expected header as the first message, got {other:?}"),
+    };
+    let mut batches = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        match msg.payload {
+            Some(Payload::Batch(batch)) => batches.push(batch),
+            other => panic!("expected only batches after the header, got {other:?}"),
+        }
+    }
+    (version, batches)
+}
+
+fn paths(batches: &[pb::ListActiveFilesBatch]) -> Vec<String> {
+    batches
+        .iter()
+        .flat_map(|b| b.files.iter().map(|f| f.path.clone()))
+        .collect()
+}
+
+#[tokio::test]
+async fn a_resumed_listing_continues_after_the_batch_at_the_same_version() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    large_table(&mut client, &table_uri).await;
+
+    // The "interrupted" stream: only its first batch is consumed.
+    let mut stream = client
+        .list_active_files(resume_request(&table_uri, Vec::new()))
+        .await
+        .unwrap()
+        .into_inner();
+    stream.message().await.unwrap().expect("header");
+    let first = match stream.message().await.unwrap().unwrap().payload {
+        Some(Payload::Batch(batch)) => batch,
+        other => panic!("expected a batch, got {other:?}"),
+    };
+    drop(stream);
+    assert!(!first.continuation_token.is_empty());
+
+    // The table moves on meanwhile.
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(1),
+            vec![add_file_action("late.parquet", 1)],
+        ))
+        .await
+        .expect("append should succeed");
+
+    let (version, rest) = list(
+        &mut client,
+        resume_request(&table_uri, first.continuation_token.clone()),
+    )
+    .await;
+    assert_eq!(version, 1, "resumed at the original stream's version");
+
+    let mut all = paths(std::slice::from_ref(&first));
+    all.extend(paths(&rest));
+    assert_eq!(all.len(), FILE_COUNT, "no file skipped or repeated");
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), FILE_COUNT);
+    assert!(!all.contains(&"late.parquet".to_string()));
+}
+
+#[tokio::test]
+async fn every_batch_carries_a_token_and_the_last_one_resumes_to_nothing() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    large_table(&mut client, &table_uri).await;
+
+    let (_, batches) = list(&mut client, resume_request(&table_uri, Vec::new())).await;
+    assert_eq!(batches.len(), 3);
+    assert!(batches.iter().all(|b| !b.continuation_token.is_empty()));
+
+    // Resuming from the middle batch lists exactly the last one again.
+    let (_, rest) = list(
+        &mut client,
+        resume_request(&table_uri, batches[1].continuation_token.clone()),
+    )
+    .await;
+    assert_eq!(paths(&rest), paths(&batches[2..]));
+
+    let last = batches.last().unwrap().continuation_token.clone();
+    let (_, rest) = list(&mut client, resume_request(&table_uri, last)).await;
+    assert!(rest.is_empty());
+}
+
+#[tokio::test]
+async fn malformed_or_misapplied_tokens_fail_the_call_itself() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let other_uri = server.new_table_uri("other");
+    let mut client = server.connect().await;
+    large_table(&mut client, &table_uri).await;
+    large_table(&mut client, &other_uri).await;
+
+    let (_, batches) = list(&mut client, resume_request(&table_uri, Vec::new())).await;
+    let token = batches[0].continuation_token.clone();
+
+    for req in [
+        resume_request(&table_uri, b"not a token".to_vec()),
+        resume_request(&other_uri, token.clone()),
+        pb::ListActiveFilesRequest {
+            as_of: Some(pb::list_active_files_request::AsOf::Version(1)),
+            ..resume_request(&table_uri, token.clone())
+        },
+    ] {
+        let err = client
+            .list_active_files(req.clone())
+            .await
+            .expect_err("a bad token must fail the RPC call itself");
+        assert_eq!(err.code(), Code::InvalidArgument, "request: {req:?}");
+    }
+}
+
+#[tokio::test]
+async fn a_token_that_no_longer_lines_up_is_a_failed_precondition() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    large_table(&mut client, &table_uri).await;
+
+    let (_, batches) = list(&mut client, resume_request(&table_uri, Vec::new())).await;
+    // As if the version's file list had been reordered since the token was
+    // issued: the file before its position is no longer the one it names.
+    let cursor = ActiveFilesCursor::from_token(&batches[0].continuation_token).unwrap();
+    let moved = ActiveFilesCursor::new(
+        cursor.table_uri,
+        cursor.version,
+        cursor.position,
+        "somewhere-else.parquet".to_string(),
+    );
+
+    let mut stream = client
+        .list_active_files(resume_request(&table_uri, moved.to_token()))
+        .await
+        .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
+        .into_inner();
+    let err = stream
+        .message()
+        .await
+        .expect_err("the stream's first (and only) item should be an error");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert!(
+        err.message().contains("restart the listing"),
+        "got: {}",
+        err.message()
+    );
+}
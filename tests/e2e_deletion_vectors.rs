@@ -0,0 +1,205 @@
+//! Deletion vectors on AddFile/RemoveFile: committed to a table with the
+//! deletionVectors feature and read back by ListActiveFiles and
+//! GetChanges, refused on a table without it, and malformed descriptors
+//! rejected up front.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_table_actions, pb, remove_file_action, TestServer,
+};
+use pb::action::Action as PbAction;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+/// The deletion-vector table's first commit, written straight into its
+/// `_delta_log` the way another Delta writer would have created it.
+fn create_deletion_vector_table(server: &TestServer, label: &str) -> String {
+    let log_dir = server.table_path(label).join("_delta_log");
+    std::fs::create_dir_all(&log_dir).unwrap();
+    let schema_string = serde_json::json!({
+        "type": "struct",
+        "fields": [
+            {"name": "id", "type": "long", "nullable": false, "metadata": {}},
+            {"name": "amount", "type": "double", "nullable": true, "metadata": {}}
+        ]
+    })
+    .to_string();
+    let commit = [
+        serde_json::json!({"protocol": {
+            "minReaderVersion": 3,
+            "minWriterVersion": 7,
+            "readerFeatures": ["deletionVectors"],
+            "writerFeatures": ["deletionVectors"]
+        }}),
+        serde_json::json!({"metaData": {
+            "id": format!("test-table-{label}"),
+            "format": {"provider": "parquet", "options": {}},
+            "schemaString": schema_string,
+            "partitionColumns": [],
+            "configuration": {"delta.enableDeletionVectors": "true"},
+            "createdTime": 1_700_000_000_000_i64
+        }}),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .collect::<Vec<_>>()
+    .join("\n");
+    std::fs::write(log_dir.join(format!("{:020}.json", 0)), commit).unwrap();
+    server.new_table_uri(label)
+}
+
+fn deletion_vector() -> pb::DeletionVectorDescriptor {
+    pb::DeletionVectorDescriptor {
+        storage_type: pb::DeletionVectorStorageType::UuidRelativePath as i32,
+        path_or_inline_dv: "ab^-aqEH.-t@S}K{vb[*k^".to_string(),
+        offset: Some(1),
+        size_in_bytes: 36,
+        cardinality: 2,
+    }
+}
+
+fn with_deletion_vector(
+    mut action: pb::Action,
+    dv: Option<pb::DeletionVectorDescriptor>,
+) -> pb::Action {
+    match &mut action.action {
+        Some(PbAction::Add(add)) => add.deletion_vector = dv,
+        Some(PbAction::Remove(remove)) => remove.deletion_vector = dv,
+        _ => {}
+    }
+    action
+}
+
+async fn active_files(client: &mut Client, table_uri: &str) -> Vec<pb::AddFile> {
+    let mut stream = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri: table_uri.to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+    let mut files = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::list_active_files_response::Payload::Batch(batch)) = msg.payload {
+            files.extend(batch.files);
+        }
+    }
+    files
+}
+
+#[tokio::test]
+async fn a_deletion_vector_round_trips_on_a_table_with_the_feature() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = create_deletion_vector_table(&server, "events");
+    let mut client = server.connect().await;
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![add_file_action("part-0.parquet", 10)],
+        ))
+        .await
+        .expect("a plain append should succeed");
+    // Merge-on-read DELETE: retire the file as it was, re-add it with the
+    // vector marking the deleted rows.
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(1),
+            vec![
+                remove_file_action("part-0.parquet"),
+                with_deletion_vector(
+                    add_file_action("part-0.parquet", 10),
+                    Some(deletion_vector()),
+                ),
+            ],
+        ))
+        .await
+        .expect("an add with a deletion vector should be accepted");
+
+    let files = active_files(&mut client, &table_uri).await;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].deletion_vector, Some(deletion_vector()));
+
+    let mut stream = client
+        .get_changes(pb::GetChangesRequest {
+            table_uri: table_uri.clone(),
+            start_version: 2,
+            end_version: Some(2),
+        })
+        .await
+        .expect("GetChanges should succeed")
+        .into_inner();
+    let mut recorded = None;
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::get_changes_response::Payload::Batch(batch)) = msg.payload {
+            recorded = recorded.or(batch.actions.into_iter().find_map(|a| match a.action {
+                Some(PbAction::Add(add)) => add.deletion_vector,
+                _ => None,
+            }));
+        }
+    }
+    assert_eq!(recorded, Some(deletion_vector()));
+}
+
+#[tokio::test]
+async fn a_deletion_vector_on_a_table_without_the_feature_is_refused() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let err = client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![with_deletion_vector(
+                add_file_action("part-0.parquet", 10),
+                Some(deletion_vector()),
+            )],
+        ))
+        .await
+        .expect_err("a deletion vector needs the deletionVectors feature");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert!(
+        err.message().contains("'deletionVectors'"),
+        "{}",
+        err.message()
+    );
+}
+
+#[tokio::test]
+async fn a_malformed_deletion_vector_is_rejected() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = create_deletion_vector_table(&server, "events");
+    let mut client = server.connect().await;
+
+    let inline_with_offset = pb::DeletionVectorDescriptor {
+        storage_type: pb::DeletionVectorStorageType::Inline as i32,
+        ..deletion_vector()
+    };
+    let err = client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![with_deletion_vector(
+                add_file_action("part-0.parquet", 10),
+                Some(inline_with_offset),
+            )],
+        ))
+        .await
+        .expect_err("an inline deletion vector can't have an offset");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(err.message().contains("offset"), "{}", err.message());
+}
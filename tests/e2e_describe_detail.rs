@@ -0,0 +1,285 @@
+//! DescribeDetail: file and byte totals overall and per partition, the
+//! last commit, the latest checkpoint and the table features -- at the
+//! latest version or a pinned older one.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_partitioned_table_actions, create_table_actions,
+    partitioned_add_file_action, pb, TestServer,
+};
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn detail_request(table_uri: &str) -> pb::DescribeDetailRequest {
+    pb::DescribeDetailRequest {
+        table_uri: table_uri.to_string(),
+        as_of: None,
+    }
+}
+
+async fn describe(
+    client: &mut Client,
+    req: pb::DescribeDetailRequest,
+) -> pb::DescribeDetailResponse {
+    client
+        .describe_detail(req)
+        .await
+        .expect("DescribeDetail should succeed")
+        .into_inner()
+}
+
+/// A partition's values as (column, value) pairs, for comparing.
+fn values(partition: &pb::PartitionDetail) -> Vec<(&str, Option<&str>)> {
+    partition
+        .partition_values
+        .iter()
+        .map(|v| (v.column.as_str(), v.value.as_deref()))
+        .collect()
+}
+
+#[tokio::test]
+async fn totals_and_per_partition_aggregates_cover_every_active_file() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_partitioned_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![
+                partitioned_add_file_action("a.parquet", ("2026-10-17", "9", "us")),
+                partitioned_add_file_action("b.parquet", ("2026-10-17", "9", "us")),
+                partitioned_add_file_action("c.parquet", ("2026-10-17", "9", "")),
+                partitioned_add_file_action("d.parquet", ("2026-10-16", "23", "eu")),
+            ],
+        ))
+        .await
+        .expect("append should succeed");
+
+    let detail = describe(&mut client, detail_request(&table_uri)).await;
+    assert_eq!(detail.version, 1);
+    assert_eq!(detail.metadata.expect("metadata").name, "events");
+    assert_eq!(detail.num_files, 4);
+    // common's add actions are 1024 bytes each.
+    assert_eq!(detail.size_in_bytes, 4 * 1024);
+
+    assert_eq!(detail.num_partitions, 3);
+    let partitions: Vec<_> = detail
+        .partitions
+        .iter()
+        .map(|p| (values(p), p.num_files, p.size_in_bytes))
+        .collect();
+    assert_eq!(
+        partitions,
+        vec![
+            (
+                vec![
+                    ("day", Some("2026-10-16")),
+                    ("hour", Some("23")),
+                    ("region", Some("eu"))
+                ],
+                1,
+                1024
+            ),
+            (
+                vec![
+                    ("day", Some("2026-10-17")),
+                    ("hour", Some("9")),
+                    ("region", None)
+                ],
+                1,
+                1024
+            ),
+            (
+                vec![
+                    ("day", Some("2026-10-17")),
+                    ("hour", Some("9")),
+                    ("region", Some("us"))
+                ],
+                2,
+                2048
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn the_partition_list_is_capped_but_the_count_is_not() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_partitioned_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    let adds = (0..1001)
+        .map(|i| {
+            let region = format!("r{i:04}");
+            partitioned_add_file_action(&format!("{region}.parquet"), ("2026-10-17", "9", &region))
+        })
+        .collect();
+    client
+        .commit(commit_request(&table_uri, Some(0), adds))
+        .await
+        .expect("append should succeed");
+
+    let detail = describe(&mut client, detail_request(&table_uri)).await;
+    assert_eq!(detail.num_files, 1001);
+    assert_eq!(detail.num_partitions, 1001);
+    assert_eq!(detail.partitions.len(), 1000);
+    // The first 1000 in partition-value order; r1000 is the one left out.
+    assert_eq!(
+        values(detail.partitions.last().unwrap())[2],
+        ("region", Some("r0999"))
+    );
+}
+
+#[tokio::test]
+async fn the_last_commit_checkpoint_and_features_are_reported() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![add_file_action("a.parquet", 1)],
+        ))
+        .await
+        .expect("append should succeed");
+
+    let detail = describe(&mut client, detail_request(&table_uri)).await;
+    assert!(detail.partitions.is_empty(), "unpartitioned");
+    assert_eq!(detail.num_partitions, 0);
+    assert!(detail.last_commit_timestamp.is_some_and(|ts| ts > 0));
+    assert_eq!(detail.last_operation.as_deref(), Some("WRITE"));
+    assert_eq!(detail.latest_checkpoint_version, None);
+    assert!(
+        detail.table_features.is_empty(),
+        "a legacy (1, 2) protocol lists no features"
+    );
+
+    let table = deltalake::open_table(url::Url::parse(&table_uri).unwrap())
+        .await
+        .expect("test should be able to open its own table directly");
+    deltalake::checkpoints::create_checkpoint(&table, None)
+        .await
+        .expect("checkpoint should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(1),
+            vec![add_file_action("b.parquet", 1)],
+        ))
+        .await
+        .expect("append should succeed");
+
+    let detail = describe(&mut client, detail_request(&table_uri)).await;
+    assert_eq!(detail.version, 2);
+    assert_eq!(detail.latest_checkpoint_version, Some(1));
+
+    // With _last_checkpoint now past them, older versions still report the
+    // checkpoint (or none) at or before them.
+    let table = deltalake::open_table(url::Url::parse(&table_uri).unwrap())
+        .await
+        .unwrap();
+    deltalake::checkpoints::create_checkpoint(&table, None)
+        .await
+        .expect("checkpoint should succeed");
+    for (version, checkpoint) in [(2, Some(2)), (1, Some(1)), (0, None)] {
+        let detail = describe(
+            &mut client,
+            pb::DescribeDetailRequest {
+                as_of: Some(pb::describe_detail_request::AsOf::Version(version)),
+                ..detail_request(&table_uri)
+            },
+        )
+        .await;
+        assert_eq!(
+            detail.latest_checkpoint_version, checkpoint,
+            "version {version}"
+        );
+        assert!(detail.last_operation.is_some(), "version {version}");
+    }
+}
+
+#[tokio::test]
+async fn as_of_describes_an_older_version() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    for (version, path) in ["a.parquet", "b.parquet"].into_iter().enumerate() {
+        client
+            .commit(commit_request(
+                &table_uri,
+                Some(version as i64),
+                vec![add_file_action(path, 1)],
+            ))
+            .await
+            .expect("append should succeed");
+    }
+
+    let detail = describe(
+        &mut client,
+        pb::DescribeDetailRequest {
+            as_of: Some(pb::describe_detail_request::AsOf::Version(1)),
+            ..detail_request(&table_uri)
+        },
+    )
+    .await;
+    assert_eq!((detail.version, detail.num_files), (1, 1));
+
+    let detail = describe(
+        &mut client,
+        pb::DescribeDetailRequest {
+            as_of: Some(pb::describe_detail_request::AsOf::Version(0)),
+            ..detail_request(&table_uri)
+        },
+    )
+    .await;
+    assert_eq!((detail.num_files, detail.size_in_bytes), (0, 0));
+    assert!(detail.last_operation.is_some(), "the create's own commit");
+}
+
+#[tokio::test]
+async fn describing_a_missing_table_is_not_found() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("never-created");
+    let mut client = server.connect().await;
+
+    let err = client
+        .describe_detail(detail_request(&table_uri))
+        .await
+        .expect_err("a missing table can't be described");
+    assert_eq!(err.code(), Code::NotFound);
+}
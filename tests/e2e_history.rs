@@ -0,0 +1,263 @@
+//! GetHistory: a table's commit history streamed back newest first, with
+//! each version's CommitInfo mapped onto the same pb::CommitInfo a Commit
+//! request carries -- plus the range/limit semantics and the ways a
+//! request is rejected.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::{add_file_action, commit_request, create_table_actions, pb, TestServer};
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn history_request(table_uri: &str) -> pb::GetHistoryRequest {
+    pb::GetHistoryRequest {
+        table_uri: table_uri.to_string(),
+        ..Default::default()
+    }
+}
+
+async fn history(client: &mut Client, req: pb::GetHistoryRequest) -> Vec<pb::GetHistoryResponse> {
+    let mut stream = client
+        .get_history(req)
+        .await
+        .expect("GetHistory should succeed")
+        .into_inner();
+    let mut entries = Vec::new();
+    while let Some(entry) = stream.message().await.expect("stream should not error") {
+        entries.push(entry);
+    }
+    entries
+}
+
+fn versions(entries: &[pb::GetHistoryResponse]) -> Vec<i64> {
+    entries.iter().map(|e| e.version).collect()
+}
+
+/// Creates a table and lands `appends` plain single-file appends on it.
+async fn table_with_appends(client: &mut Client, table_uri: &str, appends: i64) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    for n in 0..appends {
+        client
+            .commit(commit_request(
+                table_uri,
+                Some(n),
+                vec![add_file_action(&format!("part-{n}.parquet"), 1)],
+            ))
+            .await
+            .expect("append should succeed");
+    }
+}
+
+#[tokio::test]
+async fn history_returns_each_versions_commit_info_newest_first() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 0).await;
+
+    let commit_info = pb::CommitInfo {
+        engine_name: "nightly-compactor".to_string(),
+        engine_version: "1.4.2".to_string(),
+        operation: pb::CommitOperation::Delete as i32,
+        operation_parameters: HashMap::from([("predicate".to_string(), "day < 7".to_string())]),
+        user_metadata: HashMap::from([("ticket".to_string(), "OPS-12".to_string())]),
+        timestamp: 1_790_000_000_000,
+        idempotency_key: String::new(),
+    };
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![
+                pb::Action {
+                    action: Some(pb::action::Action::CommitInfo(commit_info.clone())),
+                },
+                add_file_action("part-0.parquet", 1),
+            ],
+        ))
+        .await
+        .expect("commit with a CommitInfo should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(1),
+            vec![add_file_action("part-1.parquet", 1)],
+        ))
+        .await
+        .expect("plain append should succeed");
+
+    let entries = history(&mut client, history_request(&table_uri)).await;
+    assert_eq!(versions(&entries), vec![2, 1, 0]);
+
+    // The client-supplied CommitInfo comes back exactly as it was sent.
+    assert_eq!(entries[1].commit_info.as_ref(), Some(&commit_info));
+    assert_eq!(entries[1].operation, "DELETE");
+    assert_eq!(entries[1].timestamp, commit_info.timestamp);
+
+    // Commits without one get delta-rs's own, including operations
+    // CommitOperation has no value for.
+    assert_eq!(entries[2].operation, "CREATE TABLE");
+    assert_eq!(
+        entries[2].commit_info.as_ref().unwrap().operation,
+        pb::CommitOperation::Unspecified as i32
+    );
+    assert_eq!(entries[0].operation, "WRITE");
+    assert_eq!(
+        entries[0].commit_info.as_ref().unwrap().operation,
+        pb::CommitOperation::Write as i32
+    );
+    assert!(entries.iter().all(|e| e.timestamp > 0));
+}
+
+#[tokio::test]
+async fn history_honours_the_version_range_and_limit() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 3).await;
+
+    let bounded = history(
+        &mut client,
+        pb::GetHistoryRequest {
+            start_version: Some(1),
+            end_version: Some(2),
+            ..history_request(&table_uri)
+        },
+    )
+    .await;
+    assert_eq!(versions(&bounded), vec![2, 1]);
+
+    let limited = history(
+        &mut client,
+        pb::GetHistoryRequest {
+            limit: Some(2),
+            ..history_request(&table_uri)
+        },
+    )
+    .await;
+    assert_eq!(
+        versions(&limited),
+        vec![3, 2],
+        "limit counts from the newest end"
+    );
+
+    let clipped = history(
+        &mut client,
+        pb::GetHistoryRequest {
+            start_version: Some(2),
+            end_version: Some(100),
+            ..history_request(&table_uri)
+        },
+    )
+    .await;
+    assert_eq!(versions(&clipped), vec![3, 2]);
+}
+
+#[tokio::test]
+async fn a_limit_reaches_back_past_the_latest_checkpoint_when_it_has_to() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 3).await;
+    let table = deltalake::open_table(url::Url::parse(&table_uri).unwrap())
+        .await
+        .expect("test should be able to open its own table directly");
+    deltalake::checkpoints::create_checkpoint(&table, None)
+        .await
+        .expect("checkpoint should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(3),
+            vec![add_file_action("part-4.parquet", 1)],
+        ))
+        .await
+        .expect("append should succeed");
+
+    for (limit, expected) in [(2, vec![4, 3]), (4, vec![4, 3, 2, 1])] {
+        let limited = history(
+            &mut client,
+            pb::GetHistoryRequest {
+                limit: Some(limit),
+                ..history_request(&table_uri)
+            },
+        )
+        .await;
+        assert_eq!(versions(&limited), expected);
+    }
+}
+
+#[tokio::test]
+async fn history_omits_versions_cleaned_up_by_log_retention() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 2).await;
+
+    let log_dir = server.table_path("events").join("_delta_log");
+    std::fs::remove_file(log_dir.join(format!("{:020}.json", 0)))
+        .expect("commit file should exist before cleanup");
+
+    let entries = history(&mut client, history_request(&table_uri)).await;
+    assert_eq!(versions(&entries), vec![2, 1]);
+}
+
+#[tokio::test]
+async fn malformed_history_requests_are_invalid_arguments() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 0).await;
+
+    let malformed = [
+        pb::GetHistoryRequest {
+            start_version: Some(-1),
+            ..history_request(&table_uri)
+        },
+        pb::GetHistoryRequest {
+            start_version: Some(3),
+            end_version: Some(2),
+            ..history_request(&table_uri)
+        },
+        pb::GetHistoryRequest {
+            limit: Some(0),
+            ..history_request(&table_uri)
+        },
+    ];
+    for req in malformed {
+        // Rejected synchronously, before the stream even starts.
+        let err = client
+            .get_history(req.clone())
+            .await
+            .expect_err("a malformed request must fail the RPC call itself");
+        assert_eq!(err.code(), Code::InvalidArgument, "request: {req:?}");
+    }
+}
+
+#[tokio::test]
+async fn history_of_a_missing_table_is_not_found() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("never-created");
+    let mut client = server.connect().await;
+
+    let mut stream = client
+        .get_history(history_request(&table_uri))
+        .await
+        .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
+        .into_inner();
+    let err = stream
+        .message()
+        .await
+        .expect_err("the stream's first (and only) item should be an error");
+    assert_eq!(err.code(), Code::NotFound);
+}
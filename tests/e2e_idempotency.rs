@@ -0,0 +1,228 @@
+//! Commit's idempotency_key: a retried Commit whose original already
+//! landed answers with the original's version instead of appending its
+//! files again -- including when the retry reaches a freshly started
+//! server -- and the key shows up in GetHistory's CommitInfo. Only the
+//! newest versions are searched -- the retry horizon -- and a key not
+//! found there commits, unless a stale expected_version fails it first.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_table_actions, pb, table_version, TestServer,
+    TestServerConfig,
+};
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn keyed(request: pb::CommitRequest, key: &str) -> pb::CommitRequest {
+    pb::CommitRequest {
+        idempotency_key: key.to_string(),
+        ..request
+    }
+}
+
+async fn commit(client: &mut Client, request: pb::CommitRequest) -> pb::CommitResponse {
+    client
+        .commit(request)
+        .await
+        .expect("commit should succeed")
+        .into_inner()
+}
+
+#[tokio::test]
+async fn a_retried_commit_returns_the_original_version() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+
+    let create = keyed(
+        commit_request(&table_uri, None, create_table_actions("events")),
+        "create-1",
+    );
+    let first = commit(&mut client, create.clone()).await;
+    assert_eq!(
+        first,
+        pb::CommitResponse {
+            committed_version: 0,
+            idempotent_replay: false,
+        }
+    );
+    // The create's retry finds the table existing and its own commit there.
+    assert_eq!(
+        commit(&mut client, create).await,
+        pb::CommitResponse {
+            committed_version: 0,
+            idempotent_replay: true,
+        }
+    );
+
+    let append = keyed(
+        commit_request(
+            &table_uri,
+            Some(0),
+            vec![add_file_action("part-0.parquet", 10)],
+        ),
+        "append-1",
+    );
+    assert_eq!(
+        commit(&mut client, append.clone()).await.committed_version,
+        1
+    );
+    commit(
+        &mut client,
+        commit_request(
+            &table_uri,
+            Some(1),
+            vec![add_file_action("part-1.parquet", 10)],
+        ),
+    )
+    .await;
+
+    // Its expected_version is stale by now; the replay answers anyway.
+    assert_eq!(
+        commit(&mut client, append.clone()).await,
+        pb::CommitResponse {
+            committed_version: 1,
+            idempotent_replay: true,
+        }
+    );
+    // Without expected_version the whole log is searched.
+    let unversioned = pb::CommitRequest {
+        expected_version: None,
+        ..append
+    };
+    assert_eq!(commit(&mut client, unversioned).await.committed_version, 1);
+    assert_eq!(table_version(&mut client, &table_uri).await, 2);
+
+    // A different key is a different request.
+    let other = keyed(
+        commit_request(
+            &table_uri,
+            None,
+            vec![add_file_action("part-2.parquet", 10)],
+        ),
+        "append-2",
+    );
+    assert_eq!(
+        commit(&mut client, other).await,
+        pb::CommitResponse {
+            committed_version: 3,
+            idempotent_replay: false,
+        }
+    );
+
+    let mut stream = client
+        .get_history(pb::GetHistoryRequest {
+            table_uri: table_uri.clone(),
+            ..Default::default()
+        })
+        .await
+        .expect("GetHistory should succeed")
+        .into_inner();
+    let mut keys = Vec::new();
+    while let Some(entry) = stream.message().await.expect("stream should not error") {
+        keys.push(entry.commit_info.expect("a CommitInfo").idempotency_key);
+    }
+    assert_eq!(keys, ["append-2", "", "append-1", "create-1"]);
+}
+
+#[tokio::test]
+async fn the_key_survives_a_server_restart() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    commit(
+        &mut client,
+        commit_request(&table_uri, None, create_table_actions("events")),
+    )
+    .await;
+
+    let append = keyed(
+        commit_request(
+            &table_uri,
+            None,
+            vec![add_file_action("part-0.parquet", 10)],
+        ),
+        "append-1",
+    );
+    assert_eq!(
+        commit(&mut client, append.clone()).await.committed_version,
+        1
+    );
+
+    // A second server knows nothing the first kept in memory, same as the
+    // first one restarted.
+    let restarted = TestServer::start(Default::default()).await;
+    let mut client = restarted.connect().await;
+    assert_eq!(
+        commit(&mut client, append).await,
+        pb::CommitResponse {
+            committed_version: 1,
+            idempotent_replay: true,
+        }
+    );
+    assert_eq!(table_version(&mut client, &table_uri).await, 1);
+}
+
+#[tokio::test]
+async fn a_keyed_commit_only_looks_back_to_its_retry_horizon() {
+    let server = TestServer::start(TestServerConfig {
+        idempotency_lookback: Some(2),
+        ..Default::default()
+    })
+    .await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    commit(
+        &mut client,
+        commit_request(&table_uri, None, create_table_actions("events")),
+    )
+    .await;
+
+    let append = keyed(
+        commit_request(&table_uri, None, vec![add_file_action("a.parquet", 10)]),
+        "append-1",
+    );
+    assert_eq!(
+        commit(&mut client, append.clone()).await.committed_version,
+        1
+    );
+    commit(
+        &mut client,
+        commit_request(&table_uri, None, vec![add_file_action("b.parquet", 10)]),
+    )
+    .await;
+    // Versions 1 and 2 are the newest two: still found.
+    assert!(commit(&mut client, append.clone()).await.idempotent_replay);
+
+    commit(
+        &mut client,
+        commit_request(&table_uri, None, vec![add_file_action("c.parquet", 10)]),
+    )
+    .await;
+    // Version 1 is past the window now. With the original's
+    // expected_version the search still stops at the window, and the stale
+    // version fails the retry rather than applying it twice.
+    let retry = pb::CommitRequest {
+        expected_version: Some(0),
+        ..append.clone()
+    };
+    let err = client
+        .commit(retry)
+        .await
+        .expect_err("a retry past the window with a stale expected_version");
+    assert_eq!(err.code(), Code::Aborted);
+    assert_eq!(table_version(&mut client, &table_uri).await, 3);
+    // Without it, the window is the retry horizon: not found, so it
+    // commits again -- as does a new key on a table longer than the window.
+    let again = commit(&mut client, append.clone()).await;
+    assert!(!again.idempotent_replay);
+    assert_eq!(again.committed_version, 4);
+    assert_eq!(
+        commit(&mut client, keyed(append, "append-2"))
+            .await
+            .committed_version,
+        5
+    );
+}
@@ -0,0 +1,201 @@
+//! ListPartitions: the active file set grouped by partition values, with
+//! per-partition file counts, sizes and record counts -- nulls kept
+//! distinct, files without a record count accounted for, narrowed by
+//! partition filters -- and the unpartitioned and rejected cases.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_partitioned_table, create_table_actions,
+    partitioned_add_file_action, pb, TestServer,
+};
+use pb::list_partitions_response::Payload;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+/// Like common's partitioned_add_file_action, with `num_records` rows.
+fn partitioned_add(path: &str, partition: (&str, &str, &str), num_records: i64) -> pb::Action {
+    let mut action = partitioned_add_file_action(path, partition);
+    if let Some(pb::action::Action::Add(add)) = &mut action.action {
+        add.stats.as_mut().unwrap().num_records = num_records;
+    }
+    action
+}
+
+/// Files spread over three partitions, one of them in a null region, with
+/// record counts -- and one without stats.
+async fn partitioned_table(client: &mut Client, table_uri: &str) {
+    let mut without_stats =
+        partitioned_add_file_action("no-stats.parquet", ("2026-10-17", "9", "us"));
+    if let Some(pb::action::Action::Add(add)) = &mut without_stats.action {
+        add.stats = None;
+    }
+    create_partitioned_table(
+        client,
+        table_uri,
+        vec![
+            partitioned_add("a.parquet", ("2026-10-17", "9", "us"), 10),
+            partitioned_add("b.parquet", ("2026-10-17", "9", "us"), 5),
+            without_stats,
+            partitioned_add("c.parquet", ("2026-10-17", "9", ""), 7),
+            partitioned_add("d.parquet", ("2026-10-18", "0", "eu"), 3),
+        ],
+    )
+    .await;
+}
+
+fn partitions_request(table_uri: &str) -> pb::ListPartitionsRequest {
+    pb::ListPartitionsRequest {
+        table_uri: table_uri.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Drains a ListPartitions stream into its header and every partition.
+async fn list_partitions(
+    client: &mut Client,
+    req: pb::ListPartitionsRequest,
+) -> (pb::ListPartitionsHeader, Vec<pb::PartitionDetail>) {
+    let mut stream = client
+        .list_partitions(req)
+        .await
+        .expect("ListPartitions should succeed")
+        .into_inner();
+    let header = match stream.message().await.unwrap().unwrap().payload {
+        Some(Payload::Header(h)) => h,
+        other => panic!("expected header as the first message, got {other:?}"),
+    };
+    let mut partitions = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        match msg.payload {
+            Some(Payload::Batch(batch)) => partitions.extend(batch.partitions),
+            other => panic!("expected only batches after the header, got {other:?}"),
+        }
+    }
+    (header, partitions)
+}
+
+fn region(partition: &pb::PartitionDetail) -> Option<&str> {
+    partition
+        .partition_values
+        .iter()
+        .find(|v| v.column == "region")
+        .expect("every partition column is present")
+        .value
+        .as_deref()
+}
+
+#[tokio::test]
+async fn each_distinct_partition_is_listed_once_with_its_totals() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    partitioned_table(&mut client, &table_uri).await;
+
+    let (header, partitions) = list_partitions(&mut client, partitions_request(&table_uri)).await;
+    assert_eq!(header.version, 1);
+    assert_eq!(header.partition_columns, vec!["day", "hour", "region"]);
+
+    let summary: Vec<_> = partitions
+        .iter()
+        .map(|p| {
+            (
+                region(p),
+                p.num_files,
+                p.size_in_bytes,
+                p.num_records,
+                p.num_files_without_record_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            // Null sorts first within its day/hour, and stays a null --
+            // an unset value, not "".
+            (None, 1, 1024, 7, 0),
+            (Some("us"), 3, 3 * 1024, 15, 1),
+            (Some("eu"), 1, 1024, 3, 0),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn partition_filters_narrow_the_listing() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    partitioned_table(&mut client, &table_uri).await;
+
+    let req = pb::ListPartitionsRequest {
+        partition_filters: vec![pb::PartitionFilter {
+            column: "day".to_string(),
+            predicate: Some(pb::partition_filter::Predicate::Equals(
+                "2026-10-18".to_string(),
+            )),
+        }],
+        ..partitions_request(&table_uri)
+    };
+    let (_, partitions) = list_partitions(&mut client, req).await;
+    assert_eq!(partitions.len(), 1);
+    assert_eq!(region(&partitions[0]), Some("eu"));
+}
+
+#[tokio::test]
+async fn an_unpartitioned_table_lists_just_the_header() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![add_file_action("a.parquet", 1)],
+        ))
+        .await
+        .expect("append should succeed");
+
+    let (header, partitions) = list_partitions(&mut client, partitions_request(&table_uri)).await;
+    assert!(header.partition_columns.is_empty());
+    assert!(partitions.is_empty());
+}
+
+#[tokio::test]
+async fn a_filter_that_doesnt_fit_the_table_fails_the_stream() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    partitioned_table(&mut client, &table_uri).await;
+
+    let req = pb::ListPartitionsRequest {
+        partition_filters: vec![pb::PartitionFilter {
+            column: "id".to_string(),
+            predicate: Some(pb::partition_filter::Predicate::IsNull(true)),
+        }],
+        ..partitions_request(&table_uri)
+    };
+    let mut stream = client
+        .list_partitions(req)
+        .await
+        .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
+        .into_inner();
+    let err = stream
+        .message()
+        .await
+        .expect_err("the stream's first (and only) item should be an error");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(
+        err.message().contains("is not a partition column"),
+        "got: {}",
+        err.message()
+    );
+}
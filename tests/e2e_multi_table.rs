@@ -0,0 +1,532 @@
+//! MultiTableCommit: a fact and a dimension table committed together, a
+//! retry answered from the tables' logs, the app_id fencing its parts
+//! expiring under a table's SetTransaction retention, a failing part
+//! committing nothing anywhere, malformed, non-file and disabled requests
+//! rejected, a commit a stopped server left partway rolled forward by the
+//! next one (past a record it can't read), one a live server still holds
+//! left to it -- its landed part already visible, readers not being
+//! isolated -- one whose first part conflicts withdrawn, and one whose
+//! remaining part conflicts rolled back out of the table it landed in.
+
+mod common;
+
+use common::{
+    active_paths, add_file_action, commit_request, create_table_actions, pb, remove_file_action,
+    sample_metadata, table_version, TestServer, TestServerConfig,
+};
+use std::time::Duration;
+
+use delta_txn_service::delta::commit::commit_actions;
+use delta_txn_service::delta::multi_table::{
+    removed_files, CoordinatorLog, MultiTableIntent, TableIntent, LEASE_TTL,
+};
+use delta_txn_service::delta::table::open_table;
+use delta_txn_service::grpc::mapping::map_actions;
+use tempfile::TempDir;
+use tonic::Code;
+use url::Url;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn coordinator() -> (TempDir, Url) {
+    let dir = tempfile::tempdir().expect("failed to create tempdir for the coordinator log");
+    let url = Url::from_directory_path(dir.path()).unwrap();
+    (dir, url)
+}
+
+async fn start_with_coordinator(coordinator_uri: &Url) -> TestServer {
+    TestServer::start(TestServerConfig {
+        coordinator_uri: Some(coordinator_uri.clone()),
+        ..Default::default()
+    })
+    .await
+}
+
+async fn create_tables(client: &mut Client, table_uris: &[&str]) {
+    for table_uri in table_uris {
+        client
+            .commit(commit_request(
+                table_uri,
+                None,
+                create_table_actions("events"),
+            ))
+            .await
+            .expect("create commit should succeed");
+    }
+}
+
+fn part(table_uri: &str, expected_version: Option<i64>, path: &str) -> pb::TableCommit {
+    pb::TableCommit {
+        table_uri: table_uri.to_string(),
+        expected_version,
+        actions: vec![add_file_action(path, 10)],
+    }
+}
+
+async fn multi_table_commit(
+    client: &mut Client,
+    commits: Vec<pb::TableCommit>,
+    idempotency_key: &str,
+) -> Result<pb::MultiTableCommitResponse, tonic::Status> {
+    client
+        .multi_table_commit(pb::MultiTableCommitRequest {
+            commits,
+            idempotency_key: idempotency_key.to_string(),
+        })
+        .await
+        .map(|response| response.into_inner())
+}
+
+async fn pending(coordinator_uri: &Url) -> Vec<String> {
+    CoordinatorLog::open(coordinator_uri, Default::default())
+        .unwrap()
+        .pending()
+        .await
+        .unwrap()
+}
+
+async fn record(coordinator_uri: &Url, intent: &MultiTableIntent, ttl: Duration) {
+    CoordinatorLog::open(coordinator_uri, Default::default())
+        .unwrap()
+        .record(intent, ttl)
+        .await
+        .unwrap()
+        .expect("the key is new");
+}
+
+// Commits one of `intent`'s parts the way the server applying it would,
+// short of the fence only the lease holder can write.
+async fn land_part(intent: &MultiTableIntent, part: usize) {
+    let part = &intent.tables[part];
+    let table = open_table(&Url::parse(&part.table_uri).unwrap(), Default::default())
+        .await
+        .unwrap();
+    commit_actions(table, part.actions.clone(), intent.commit_metadata())
+        .await
+        .expect("the part should commit");
+}
+
+fn table_intent(table_uri: &str, read_version: u64, action: pb::Action) -> TableIntent {
+    TableIntent {
+        table_uri: deltalake::ensure_table_uri(table_uri).unwrap().to_string(),
+        read_version,
+        actions: map_actions(vec![action]).unwrap(),
+        restore: Vec::new(),
+    }
+}
+
+fn versions(response: &pb::MultiTableCommitResponse) -> Vec<(String, i64)> {
+    response
+        .results
+        .iter()
+        .map(|result| (result.table_uri.clone(), result.committed_version))
+        .collect()
+}
+
+#[tokio::test]
+async fn both_tables_commit_together() {
+    let (_dir, coordinator_uri) = coordinator();
+    let server = start_with_coordinator(&coordinator_uri).await;
+    let facts = server.new_table_uri("facts");
+    let dims = server.new_table_uri("dims");
+    let mut client = server.connect().await;
+    create_tables(&mut client, &[&facts, &dims]).await;
+    client
+        .commit(commit_request(
+            &facts,
+            Some(0),
+            vec![add_file_action("facts-0.parquet", 10)],
+        ))
+        .await
+        .expect("an earlier append should succeed");
+
+    // Results come back in request order, not the (sorted) lock order.
+    let commits = vec![
+        part(&facts, Some(1), "facts-1.parquet"),
+        part(&dims, Some(0), "dims-0.parquet"),
+    ];
+    let response = multi_table_commit(&mut client, commits.clone(), "load-1")
+        .await
+        .expect("the multi-table commit should succeed");
+    assert_eq!(versions(&response), [(facts.clone(), 2), (dims.clone(), 1)]);
+    assert!(!response.commit_id.is_empty());
+    assert!(!response.idempotent_replay);
+    assert!(pending(&coordinator_uri).await.is_empty());
+
+    let mut history = client
+        .get_history(pb::GetHistoryRequest {
+            table_uri: dims.clone(),
+            ..Default::default()
+        })
+        .await
+        .expect("GetHistory should succeed")
+        .into_inner();
+    let latest = history.message().await.unwrap().expect("a history entry");
+    assert_eq!(latest.commit_info.unwrap().idempotency_key, "load-1");
+
+    // The retry is answered from the tables' own logs.
+    let replay = multi_table_commit(&mut client, commits, "load-1")
+        .await
+        .expect("the retry should succeed");
+    assert_eq!(versions(&replay), versions(&response));
+    assert!(replay.idempotent_replay);
+    assert_eq!(table_version(&mut client, &facts).await, 2);
+    assert_eq!(table_version(&mut client, &dims).await, 1);
+}
+
+#[tokio::test]
+async fn a_commits_fence_expires_under_the_tables_retention() {
+    let (_dir, coordinator_uri) = coordinator();
+    let server = start_with_coordinator(&coordinator_uri).await;
+    let facts = server.new_table_uri("facts");
+    let dims = server.new_table_uri("dims");
+    let mut client = server.connect().await;
+    create_tables(&mut client, &[&dims]).await;
+    let mut create_facts = create_table_actions("facts");
+    if let Some(pb::action::Action::MetaData(metadata)) = &mut create_facts[1].action {
+        metadata.configuration.insert(
+            "delta.setTransactionRetentionDuration".to_string(),
+            "interval 1 second".to_string(),
+        );
+    }
+    client
+        .commit(commit_request(&facts, None, create_facts))
+        .await
+        .expect("create commit should succeed");
+
+    let response = multi_table_commit(
+        &mut client,
+        vec![
+            part(&facts, Some(0), "facts-0.parquet"),
+            part(&dims, Some(0), "dims-0.parquet"),
+        ],
+        "load-7",
+    )
+    .await
+    .expect("the multi-table commit should succeed");
+    let app_id = format!("multiTableCommit-{}", response.commit_id);
+    let fenced = |table_uri: &str| {
+        let mut client = client.clone();
+        let request = pb::GetTransactionVersionRequest {
+            table_uri: table_uri.to_string(),
+            app_id: app_id.clone(),
+        };
+        async move {
+            client
+                .get_transaction_version(request)
+                .await
+                .expect("GetTransactionVersion should succeed")
+                .into_inner()
+                .version
+                .is_some()
+        }
+    };
+    assert!(fenced(&facts).await);
+    assert!(fenced(&dims).await);
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(!fenced(&facts).await, "expired under the retention");
+    assert!(fenced(&dims).await, "kept without one");
+}
+
+#[tokio::test]
+async fn a_failing_part_commits_nothing_anywhere() {
+    let (_dir, coordinator_uri) = coordinator();
+    let server = start_with_coordinator(&coordinator_uri).await;
+    let facts = server.new_table_uri("facts");
+    let dims = server.new_table_uri("dims");
+    let mut client = server.connect().await;
+    create_tables(&mut client, &[&facts, &dims]).await;
+
+    let err = multi_table_commit(
+        &mut client,
+        vec![
+            part(&facts, Some(0), "facts-0.parquet"),
+            part(&dims, Some(7), "dims-0.parquet"),
+        ],
+        "",
+    )
+    .await
+    .expect_err("dims' expected_version is stale");
+    assert_eq!(err.code(), Code::Aborted);
+    assert!(err.message().contains(&dims), "{}", err.message());
+
+    let cases = [
+        (
+            "the same table twice",
+            vec![
+                part(&facts, None, "facts-0.parquet"),
+                part(&facts, None, "facts-1.parquet"),
+            ],
+            Code::InvalidArgument,
+        ),
+        ("no tables", Vec::new(), Code::InvalidArgument),
+        (
+            "a table that doesn't exist",
+            vec![
+                part(&facts, None, "facts-0.parquet"),
+                part(&server.new_table_uri("missing"), None, "part-0.parquet"),
+            ],
+            Code::FailedPrecondition,
+        ),
+        (
+            "a part carrying a TableMetadata",
+            vec![
+                part(&facts, None, "facts-0.parquet"),
+                pb::TableCommit {
+                    table_uri: dims.clone(),
+                    expected_version: None,
+                    actions: vec![pb::Action {
+                        action: Some(pb::action::Action::MetaData(sample_metadata("events"))),
+                    }],
+                },
+            ],
+            Code::InvalidArgument,
+        ),
+    ];
+    for (case, commits, code) in cases {
+        let err = multi_table_commit(&mut client, commits, "")
+            .await
+            .expect_err(case);
+        assert_eq!(err.code(), code, "{case}: {}", err.message());
+    }
+
+    assert_eq!(table_version(&mut client, &facts).await, 0);
+    assert_eq!(table_version(&mut client, &dims).await, 0);
+    assert!(pending(&coordinator_uri).await.is_empty());
+
+    // Without a coordinator log there's nowhere to record the decision.
+    let disabled = TestServer::start(Default::default()).await;
+    let mut client = disabled.connect().await;
+    let err = multi_table_commit(&mut client, vec![part(&facts, None, "facts-0.parquet")], "")
+        .await
+        .expect_err("MultiTableCommit is disabled");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn a_partly_applied_commit_is_rolled_forward_on_restart() {
+    let (dir, coordinator_uri) = coordinator();
+    let server = start_with_coordinator(&coordinator_uri).await;
+    let facts = server.new_table_uri("facts");
+    let dims = server.new_table_uri("dims");
+    let mut client = server.connect().await;
+    create_tables(&mut client, &[&facts, &dims]).await;
+
+    // What a server leaves behind when it stops right after committing
+    // the first table: the recorded decision, its lease lapsing, and that
+    // table's part landed.
+    let intent = MultiTableIntent {
+        id: "mtc-stopped".to_string(),
+        idempotency_key: "load-7".to_string(),
+        tables: vec![
+            table_intent(&dims, 0, add_file_action("dims-0.parquet", 10)),
+            table_intent(&facts, 0, add_file_action("facts-0.parquet", 10)),
+        ],
+    };
+    record(&coordinator_uri, &intent, Duration::ZERO).await;
+    land_part(&intent, 0).await;
+    // And a record nobody can read, which mustn't hold up the rest.
+    let broken = dir.path().join("commits").join("62726f6b656e");
+    std::fs::create_dir_all(&broken).unwrap();
+    std::fs::write(broken.join("intent.json"), "not json").unwrap();
+
+    let restarted = start_with_coordinator(&coordinator_uri).await;
+    let mut client = restarted.connect().await;
+    assert_eq!(pending(&coordinator_uri).await, ["broken"]);
+    // The missing part landed; the one already there wasn't applied twice.
+    assert_eq!(table_version(&mut client, &facts).await, 1);
+    assert_eq!(table_version(&mut client, &dims).await, 1);
+
+    // And a retry of the original request finds it finished.
+    let replay = multi_table_commit(
+        &mut client,
+        vec![
+            part(&facts, Some(0), "facts-0.parquet"),
+            part(&dims, Some(0), "dims-0.parquet"),
+        ],
+        "load-7",
+    )
+    .await
+    .expect("the retry should succeed");
+    assert!(replay.idempotent_replay);
+    assert_eq!(versions(&replay), [(facts, 1), (dims, 1)]);
+}
+
+#[tokio::test]
+async fn a_commit_another_server_holds_is_left_to_it() {
+    let (_dir, coordinator_uri) = coordinator();
+    let server = start_with_coordinator(&coordinator_uri).await;
+    let facts = server.new_table_uri("facts");
+    let dims = server.new_table_uri("dims");
+    let mut client = server.connect().await;
+    create_tables(&mut client, &[&facts, &dims]).await;
+
+    // Recorded by a replica sharing the log, which is still applying it
+    // and has committed dims' part so far.
+    let intent = MultiTableIntent {
+        id: "mtc-busy".to_string(),
+        idempotency_key: "load-8".to_string(),
+        tables: vec![
+            table_intent(&dims, 0, add_file_action("dims-0.parquet", 10)),
+            table_intent(&facts, 0, add_file_action("facts-0.parquet", 10)),
+        ],
+    };
+    record(&coordinator_uri, &intent, LEASE_TTL).await;
+    land_part(&intent, 0).await;
+
+    // Another replica starting up doesn't touch it...
+    let other = start_with_coordinator(&coordinator_uri).await;
+    let mut client = other.connect().await;
+    assert_eq!(pending(&coordinator_uri).await, ["load-8"]);
+    assert_eq!(table_version(&mut client, &facts).await, 0);
+    // ...readers see dims' part as soon as it lands...
+    assert_eq!(table_version(&mut client, &dims).await, 1);
+
+    // ...and a retry reaching it is told to come back.
+    let err = multi_table_commit(
+        &mut client,
+        vec![
+            part(&facts, Some(0), "facts-0.parquet"),
+            part(&dims, Some(0), "dims-0.parquet"),
+        ],
+        "load-8",
+    )
+    .await
+    .expect_err("another server holds the commit");
+    assert_eq!(err.code(), Code::Unavailable);
+    assert_eq!(table_version(&mut client, &facts).await, 0);
+}
+
+#[tokio::test]
+async fn a_commit_whose_first_part_can_never_land_is_withdrawn() {
+    let (_dir, coordinator_uri) = coordinator();
+    let server = start_with_coordinator(&coordinator_uri).await;
+    let facts = server.new_table_uri("facts");
+    let dims = server.new_table_uri("dims");
+    let mut client = server.connect().await;
+    create_tables(&mut client, &[&facts, &dims]).await;
+    client
+        .commit(commit_request(
+            &dims,
+            Some(0),
+            vec![add_file_action("dims-0.parquet", 10)],
+        ))
+        .await
+        .expect("an append should succeed");
+
+    // Decided with dims' part first, removing dims-0 as of version 1 --
+    // and another writer removes it before the part is applied.
+    let intent = MultiTableIntent {
+        id: "mtc-withdrawn".to_string(),
+        idempotency_key: "load-11".to_string(),
+        tables: vec![
+            table_intent(&dims, 1, remove_file_action("dims-0.parquet")),
+            table_intent(&facts, 0, add_file_action("facts-0.parquet", 10)),
+        ],
+    };
+    record(&coordinator_uri, &intent, Duration::ZERO).await;
+    client
+        .commit(commit_request(
+            &dims,
+            Some(1),
+            vec![remove_file_action("dims-0.parquet")],
+        ))
+        .await
+        .expect("the other writer's delete should succeed");
+
+    // Nothing of it landed anywhere, so recovery drops the record.
+    let restarted = start_with_coordinator(&coordinator_uri).await;
+    let mut client = restarted.connect().await;
+    assert!(pending(&coordinator_uri).await.is_empty());
+    assert_eq!(table_version(&mut client, &facts).await, 0);
+    assert_eq!(table_version(&mut client, &dims).await, 2);
+}
+
+#[tokio::test]
+async fn a_part_that_can_never_land_rolls_back_the_parts_that_landed() {
+    let (_dir, coordinator_uri) = coordinator();
+    let server = start_with_coordinator(&coordinator_uri).await;
+    let facts = server.new_table_uri("facts");
+    let dims = server.new_table_uri("dims");
+    let mut client = server.connect().await;
+    create_tables(&mut client, &[&facts, &dims]).await;
+    for (table_uri, path) in [(&facts, "facts-old.parquet"), (&dims, "dims-0.parquet")] {
+        client
+            .commit(commit_request(
+                table_uri,
+                Some(0),
+                vec![add_file_action(path, 10)],
+            ))
+            .await
+            .expect("an append should succeed");
+    }
+
+    // Decided with facts' part rewriting facts-old as facts-0 and dims'
+    // removing dims-0, both as of version 1 -- but before dims' part is
+    // applied, another writer removes dims-0 first.
+    let mut facts_part = table_intent(&facts, 1, remove_file_action("facts-old.parquet"));
+    facts_part
+        .actions
+        .extend(map_actions(vec![add_file_action("facts-0.parquet", 10)]).unwrap());
+    let facts_table = open_table(
+        &Url::parse(&facts_part.table_uri).unwrap(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    facts_part.restore = removed_files(&facts_table, &facts_part.actions).unwrap();
+    let intent = MultiTableIntent {
+        id: "mtc-doomed".to_string(),
+        idempotency_key: "load-9".to_string(),
+        tables: vec![
+            facts_part,
+            table_intent(&dims, 1, remove_file_action("dims-0.parquet")),
+        ],
+    };
+    record(&coordinator_uri, &intent, Duration::ZERO).await;
+    client
+        .commit(commit_request(
+            &dims,
+            Some(1),
+            vec![remove_file_action("dims-0.parquet")],
+        ))
+        .await
+        .expect("the other writer's delete should succeed");
+
+    // Recovery commits facts' part, finds dims' conflicting, and takes
+    // facts' part back out: facts-0 removed again, facts-old restored.
+    let restarted = start_with_coordinator(&coordinator_uri).await;
+    let mut client = restarted.connect().await;
+    assert_eq!(table_version(&mut client, &facts).await, 3);
+    assert_eq!(
+        active_paths(&mut client, &facts).await,
+        ["facts-old.parquet"]
+    );
+    assert_eq!(table_version(&mut client, &dims).await, 2);
+    let log = CoordinatorLog::open(&coordinator_uri, Default::default()).unwrap();
+    let failure = log
+        .failure("load-9")
+        .await
+        .unwrap()
+        .expect("the record is marked failed");
+    assert!(failure.contains(&dims), "{failure}");
+    assert!(log.is_rolled_back("load-9").await.unwrap());
+
+    // A retry is refused for good, naming what happened, and commits
+    // nothing -- not even a second rollback.
+    let err = multi_table_commit(
+        &mut client,
+        vec![
+            part(&facts, Some(1), "facts-0.parquet"),
+            part(&dims, Some(1), "dims-0.parquet"),
+        ],
+        "load-9",
+    )
+    .await
+    .expect_err("the commit was rolled back");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert!(err.message().contains("mtc-doomed"), "{}", err.message());
+    assert_eq!(table_version(&mut client, &facts).await, 3);
+    assert_eq!(pending(&coordinator_uri).await, ["load-9"]);
+}
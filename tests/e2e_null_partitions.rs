@@ -0,0 +1,152 @@
+//! Null partition values through AddFile.nullable_partition_values: written
+//! on Commit, read back null (not "") by ListActiveFiles and GetChanges,
+//! matched by an IS NULL filter -- and the map and the list together on
+//! one AddFile rejected.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_partitioned_table_actions, partitioned_add_file_action,
+    pb, TestServer,
+};
+use pb::action::Action as PbAction;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn value(column: &str, value: Option<&str>) -> pb::PartitionValue {
+    pb::PartitionValue {
+        column: column.to_string(),
+        value: value.map(str::to_string),
+    }
+}
+
+/// An add in partition `day=2026-10-18, hour=null, region=null`.
+fn null_partition_add(path: &str) -> pb::Action {
+    let mut action = add_file_action(path, 1);
+    if let Some(PbAction::Add(add)) = &mut action.action {
+        add.nullable_partition_values = vec![
+            value("region", None),
+            value("day", Some("2026-10-18")),
+            value("hour", None),
+        ];
+    }
+    action
+}
+
+async fn create(client: &mut Client, table_uri: &str) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_partitioned_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+}
+
+async fn listed_files(client: &mut Client, table_uri: &str) -> Vec<pb::AddFile> {
+    let mut stream = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri: table_uri.to_string(),
+            partition_filters: vec![pb::PartitionFilter {
+                column: "hour".to_string(),
+                predicate: Some(pb::partition_filter::Predicate::IsNull(true)),
+            }],
+            ..Default::default()
+        })
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+    let mut files = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::list_active_files_response::Payload::Batch(batch)) = msg.payload {
+            files.extend(batch.files);
+        }
+    }
+    files
+}
+
+#[tokio::test]
+async fn null_partition_values_round_trip() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create(&mut client, &table_uri).await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![
+                null_partition_add("null.parquet"),
+                partitioned_add_file_action("set.parquet", ("2026-10-18", "9", "eu")),
+            ],
+        ))
+        .await
+        .expect("an add with null partition values should be accepted");
+
+    // Only the null-hour file passes the IS NULL filter, and it comes back
+    // in partition-column order with its nulls intact.
+    let files = listed_files(&mut client, &table_uri).await;
+    assert_eq!(files.len(), 1, "{files:?}");
+    assert_eq!(files[0].path, "null.parquet");
+    assert_eq!(
+        files[0].nullable_partition_values,
+        vec![
+            value("day", Some("2026-10-18")),
+            value("hour", None),
+            value("region", None),
+        ]
+    );
+    assert_eq!(files[0].partition_values["hour"], "");
+
+    let mut stream = client
+        .get_changes(pb::GetChangesRequest {
+            table_uri: table_uri.clone(),
+            start_version: 1,
+            end_version: Some(1),
+        })
+        .await
+        .expect("GetChanges should succeed")
+        .into_inner();
+    let mut added = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::get_changes_response::Payload::Batch(batch)) = msg.payload {
+            added.extend(batch.actions.into_iter().filter_map(|a| match a.action {
+                Some(PbAction::Add(add)) => Some(add),
+                _ => None,
+            }));
+        }
+    }
+    let null_add = added
+        .iter()
+        .find(|add| add.path == "null.parquet")
+        .expect("GetChanges should report the add");
+    assert_eq!(
+        null_add.nullable_partition_values,
+        vec![
+            value("day", Some("2026-10-18")),
+            value("hour", None),
+            value("region", None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn both_partition_value_fields_on_one_add_are_rejected() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create(&mut client, &table_uri).await;
+
+    let mut action = partitioned_add_file_action("both.parquet", ("2026-10-18", "9", "eu"));
+    if let Some(PbAction::Add(add)) = &mut action.action {
+        add.nullable_partition_values = vec![value("hour", None)];
+    }
+    let err = client
+        .commit(commit_request(&table_uri, Some(0), vec![action]))
+        .await
+        .expect_err("an add setting both fields must be rejected");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(err.message().contains("not both"), "{}", err.message());
+}
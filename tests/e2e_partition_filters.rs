@@ -0,0 +1,211 @@
+//! Partition-filtered ListActiveFiles: equality, IN, range and IS NULL
+//! filters on partition columns, applied server-side with typed
+//! comparisons, ANDed together -- plus the two ways a filter is rejected
+//! (malformed on its own, up front; not fitting the table, on the stream).
+
+mod common;
+
+use common::{create_partitioned_table, listed_paths, one_file_per_partition, pb, TestServer};
+use pb::partition_filter::Predicate;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn filter(column: &str, predicate: Predicate) -> pb::PartitionFilter {
+    pb::PartitionFilter {
+        column: column.to_string(),
+        predicate: Some(predicate),
+    }
+}
+
+fn equals(column: &str, value: &str) -> pb::PartitionFilter {
+    filter(column, Predicate::Equals(value.to_string()))
+}
+
+fn filtered_request(
+    table_uri: &str,
+    filters: Vec<pb::PartitionFilter>,
+) -> pb::ListActiveFilesRequest {
+    pb::ListActiveFilesRequest {
+        table_uri: table_uri.to_string(),
+        partition_filters: filters,
+        ..Default::default()
+    }
+}
+
+async fn filtered_paths(
+    client: &mut Client,
+    table_uri: &str,
+    filters: Vec<pb::PartitionFilter>,
+) -> Vec<String> {
+    listed_paths(client, filtered_request(table_uri, filters)).await
+}
+
+#[tokio::test]
+async fn an_equality_filter_streams_only_that_partitions_files() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    assert_eq!(
+        filtered_paths(&mut client, &table_uri, vec![equals("day", "2026-10-17")]).await,
+        vec![
+            "d17-h10-eu.parquet",
+            "d17-h23-null.parquet",
+            "d17-h9-us.parquet"
+        ]
+    );
+    assert_eq!(
+        filtered_paths(&mut client, &table_uri, vec![]).await.len(),
+        5,
+        "no filters means every active file"
+    );
+}
+
+#[tokio::test]
+async fn in_list_and_range_filters_compare_by_the_columns_type() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    let in_list = filter(
+        "region",
+        Predicate::InList(pb::PartitionValueList {
+            values: vec!["us".to_string(), "apac".to_string()],
+        }),
+    );
+    assert_eq!(
+        filtered_paths(&mut client, &table_uri, vec![in_list]).await,
+        vec!["d17-h9-us.parquet", "d18-h0-us.parquet"]
+    );
+
+    // As strings "10" and "23" sort before "9"; as integers they don't.
+    let hours_after_9 = filter(
+        "hour",
+        Predicate::Range(pb::PartitionRange {
+            lower: Some("9".to_string()),
+            lower_inclusive: false,
+            ..Default::default()
+        }),
+    );
+    assert_eq!(
+        filtered_paths(&mut client, &table_uri, vec![hours_after_9]).await,
+        vec!["d17-h10-eu.parquet", "d17-h23-null.parquet"]
+    );
+
+    let days_through_17th = filter(
+        "day",
+        Predicate::Range(pb::PartitionRange {
+            lower: Some("2026-10-16".to_string()),
+            lower_inclusive: true,
+            upper: Some("2026-10-17".to_string()),
+            upper_inclusive: true,
+        }),
+    );
+    assert_eq!(
+        filtered_paths(&mut client, &table_uri, vec![days_through_17th])
+            .await
+            .len(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn is_null_matches_only_null_partition_values() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    assert_eq!(
+        filtered_paths(
+            &mut client,
+            &table_uri,
+            vec![filter("region", Predicate::IsNull(true))]
+        )
+        .await,
+        vec!["d17-h23-null.parquet"]
+    );
+    assert_eq!(
+        filtered_paths(
+            &mut client,
+            &table_uri,
+            vec![filter("region", Predicate::IsNull(false))]
+        )
+        .await
+        .len(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn several_filters_must_all_match() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    assert_eq!(
+        filtered_paths(
+            &mut client,
+            &table_uri,
+            vec![equals("day", "2026-10-17"), equals("region", "eu")]
+        )
+        .await,
+        vec!["d17-h10-eu.parquet"]
+    );
+}
+
+#[tokio::test]
+async fn malformed_filters_fail_the_call_itself() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    let malformed = [
+        pb::PartitionFilter {
+            column: "day".to_string(),
+            predicate: None,
+        },
+        equals("", "2026-10-17"),
+        filter("region", Predicate::InList(Default::default())),
+        filter("hour", Predicate::Range(Default::default())),
+    ];
+    for bad in malformed {
+        let err = client
+            .list_active_files(filtered_request(&table_uri, vec![bad.clone()]))
+            .await
+            .expect_err("a malformed filter must fail the RPC call itself");
+        assert_eq!(err.code(), Code::InvalidArgument, "filter: {bad:?}");
+    }
+}
+
+#[tokio::test]
+async fn filters_that_dont_fit_the_table_fail_the_stream() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    for (bad, expected) in [
+        (equals("country", "us"), "does not exist"),
+        (equals("id", "1"), "is not a partition column"),
+        (equals("hour", "noon"), "not a valid"),
+        (equals("day", ""), "is_null"),
+    ] {
+        let mut stream = client
+            .list_active_files(filtered_request(&table_uri, vec![bad.clone()]))
+            .await
+            .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
+            .into_inner();
+        let err = stream
+            .message()
+            .await
+            .expect_err("the stream's first (and only) item should be an error");
+        assert_eq!(err.code(), Code::InvalidArgument, "filter: {bad:?}");
+        assert!(err.message().contains(expected), "got: {}", err.message());
+    }
+}
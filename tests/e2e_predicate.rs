@@ -0,0 +1,176 @@
+//! ListActiveFiles with a SQL data-skipping predicate: files pruned on
+//! their min/max stats and on partition values, kept whenever the stats
+//! can't rule them out, combined with partition filters -- plus the ways a
+//! predicate is rejected.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_partitioned_table, create_table_actions, listed_paths,
+    one_file_per_partition, pb, TestServer,
+};
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+/// Three files whose `id` stats run 1..=10, 1..=100 and 1..=1000.
+async fn table_with_id_stats(client: &mut Client, table_uri: &str) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .commit(commit_request(
+            table_uri,
+            Some(0),
+            vec![
+                add_file_action("small.parquet", 10),
+                add_file_action("medium.parquet", 100),
+                add_file_action("large.parquet", 1000),
+            ],
+        ))
+        .await
+        .expect("append should succeed");
+}
+
+fn predicate_request(table_uri: &str, predicate: &str) -> pb::ListActiveFilesRequest {
+    pb::ListActiveFilesRequest {
+        table_uri: table_uri.to_string(),
+        predicate: predicate.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn files_whose_stats_rule_out_the_predicate_are_skipped() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_id_stats(&mut client, &table_uri).await;
+
+    assert_eq!(
+        listed_paths(&mut client, predicate_request(&table_uri, "id > 50")).await,
+        vec!["large.parquet", "medium.parquet"]
+    );
+    assert_eq!(
+        listed_paths(
+            &mut client,
+            predicate_request(&table_uri, "id BETWEEN 200 AND 300")
+        )
+        .await,
+        vec!["large.parquet"]
+    );
+    assert!(
+        listed_paths(&mut client, predicate_request(&table_uri, "id < 1"))
+            .await
+            .is_empty(),
+        "every file's min id is 1"
+    );
+}
+
+#[tokio::test]
+async fn files_without_stats_for_a_column_are_always_kept() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_id_stats(&mut client, &table_uri).await;
+
+    // No file recorded stats for amount, so nothing can be ruled out.
+    assert_eq!(
+        listed_paths(&mut client, predicate_request(&table_uri, "amount > 1e9"))
+            .await
+            .len(),
+        3
+    );
+    // Blank is no predicate at all.
+    assert_eq!(
+        listed_paths(&mut client, predicate_request(&table_uri, "  "))
+            .await
+            .len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn a_predicate_prunes_on_partition_values_too() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    assert_eq!(
+        listed_paths(
+            &mut client,
+            predicate_request(&table_uri, "region = 'us' AND hour < 5")
+        )
+        .await,
+        vec!["d18-h0-us.parquet"]
+    );
+    assert_eq!(
+        listed_paths(
+            &mut client,
+            predicate_request(&table_uri, "day >= '2026-10-17'")
+        )
+        .await,
+        vec![
+            "d17-h10-eu.parquet",
+            "d17-h23-null.parquet",
+            "d17-h9-us.parquet",
+            "d18-h0-us.parquet"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn a_predicate_and_partition_filters_must_both_match() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_partitioned_table(&mut client, &table_uri, one_file_per_partition()).await;
+
+    let req = pb::ListActiveFilesRequest {
+        partition_filters: vec![pb::PartitionFilter {
+            column: "day".to_string(),
+            predicate: Some(pb::partition_filter::Predicate::Equals(
+                "2026-10-17".to_string(),
+            )),
+        }],
+        ..predicate_request(&table_uri, "region = 'eu'")
+    };
+    // Pruning is conservative: the null region isn't ruled out by
+    // `region = 'eu'`, only by a reader applying it to the data.
+    assert_eq!(
+        listed_paths(&mut client, req).await,
+        vec!["d17-h10-eu.parquet", "d17-h23-null.parquet"]
+    );
+}
+
+#[tokio::test]
+async fn predicates_that_dont_fit_the_table_fail_the_stream() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_id_stats(&mut client, &table_uri).await;
+
+    for bad in ["country = 'us'", "id >", "id + 1"] {
+        let mut stream = client
+            .list_active_files(predicate_request(&table_uri, bad))
+            .await
+            .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
+            .into_inner();
+        let err = stream
+            .message()
+            .await
+            .expect_err("the stream's first (and only) item should be an error");
+        assert_eq!(err.code(), Code::InvalidArgument, "predicate: {bad}");
+        assert!(
+            err.message().contains("invalid predicate"),
+            "got: {}",
+            err.message()
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! ListActiveFiles projection: leaving stats and/or partition values out of
+//! each AddFile, or narrowing stats to a few columns -- plus the
+//! contradictory projections rejected up front.
+
+mod common;
+
+use common::{
+    commit_request, create_partitioned_table_actions, partitioned_add_file_action, pb, TestServer,
+};
+use pb::list_active_files_response::Payload;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+/// One file with stats for both `id` and `amount`.
+async fn table_with_one_file(client: &mut Client, table_uri: &str) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_partitioned_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let mut action = partitioned_add_file_action("a.parquet", ("2026-10-17", "9", "eu"));
+    if let Some(pb::action::Action::Add(add)) = &mut action.action {
+        add.stats.as_mut().unwrap().columns.insert(
+            "amount".to_string(),
+            pb::ColumnStats {
+                min_value: Some(pb::column_stats::MinValue::MinDouble(0.5)),
+                max_value: Some(pb::column_stats::MaxValue::MaxDouble(9.5)),
+                null_count: 0,
+            },
+        );
+    }
+    client
+        .commit(commit_request(table_uri, Some(0), vec![action]))
+        .await
+        .expect("append should succeed");
+}
+
+fn projected_request(
+    table_uri: &str,
+    projection: pb::FileProjection,
+) -> pb::ListActiveFilesRequest {
+    pb::ListActiveFilesRequest {
+        table_uri: table_uri.to_string(),
+        projection: Some(projection),
+        ..Default::default()
+    }
+}
+
+/// The table's one file, as the listing sends it.
+async fn listed_file(client: &mut Client, req: pb::ListActiveFilesRequest) -> pb::AddFile {
+    let mut stream = client
+        .list_active_files(req)
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+    let mut files = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(Payload::Batch(batch)) = msg.payload {
+            files.extend(batch.files);
+        }
+    }
+    assert_eq!(files.len(), 1);
+    files.remove(0)
+}
+
+#[tokio::test]
+async fn an_unset_projection_sends_the_whole_add_file() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_one_file(&mut client, &table_uri).await;
+
+    let file = listed_file(
+        &mut client,
+        projected_request(&table_uri, Default::default()),
+    )
+    .await;
+    assert_eq!(file.partition_values["region"], "eu");
+    let mut columns: Vec<_> = file.stats.expect("stats").columns.into_keys().collect();
+    columns.sort();
+    assert_eq!(columns, vec!["amount", "id"]);
+}
+
+#[tokio::test]
+async fn stats_and_partition_values_can_each_be_left_out() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_one_file(&mut client, &table_uri).await;
+
+    let file = listed_file(
+        &mut client,
+        projected_request(
+            &table_uri,
+            pb::FileProjection {
+                include_stats: Some(false),
+                ..Default::default()
+            },
+        ),
+    )
+    .await;
+    assert_eq!(file.path, "a.parquet");
+    assert!(file.size > 0);
+    assert!(file.stats.is_none());
+    assert_eq!(file.partition_values["day"], "2026-10-17");
+
+    let file = listed_file(
+        &mut client,
+        projected_request(
+            &table_uri,
+            pb::FileProjection {
+                include_partition_values: Some(false),
+                ..Default::default()
+            },
+        ),
+    )
+    .await;
+    assert!(file.partition_values.is_empty());
+    assert!(file.stats.is_some());
+}
+
+#[tokio::test]
+async fn stats_columns_narrows_the_stats_to_those_columns() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_one_file(&mut client, &table_uri).await;
+
+    let file = listed_file(
+        &mut client,
+        projected_request(
+            &table_uri,
+            pb::FileProjection {
+                stats_columns: vec!["amount".to_string(), "no_such_column".to_string()],
+                ..Default::default()
+            },
+        ),
+    )
+    .await;
+    let stats = file.stats.expect("stats");
+    assert_eq!(stats.num_records, 1, "num_records is always included");
+    assert_eq!(
+        stats.columns.into_keys().collect::<Vec<_>>(),
+        vec!["amount"]
+    );
+}
+
+#[tokio::test]
+async fn contradictory_projections_fail_the_call_itself() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    table_with_one_file(&mut client, &table_uri).await;
+
+    for bad in [
+        pb::FileProjection {
+            include_stats: Some(false),
+            stats_columns: vec!["id".to_string()],
+            ..Default::default()
+        },
+        pb::FileProjection {
+            stats_columns: vec![String::new()],
+            ..Default::default()
+        },
+    ] {
+        let err = client
+            .list_active_files(projected_request(&table_uri, bad.clone()))
+            .await
+            .expect_err("a contradictory projection must fail the RPC call itself");
+        assert_eq!(err.code(), Code::InvalidArgument, "projection: {bad:?}");
+    }
+}
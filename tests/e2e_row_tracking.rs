@@ -0,0 +1,319 @@
+//! Row tracking fields on AddFile: refused on a table without the
+//! rowTracking feature, reported by GetChanges for a row-tracking table
+//! written elsewhere, and assigned by the service on a Commit to one --
+//! from the table's row-ID high-water mark, past another writer's append
+//! since a staged transaction's Begin too, and from 0 on a table that
+//! just had the feature enabled.
+
+mod common;
+
+use common::{add_file_action, commit_request, create_table_actions, pb, TestServer};
+use delta_txn_service::delta::row_tracking::row_id_high_water_mark;
+use pb::action::Action as PbAction;
+use tonic::Code;
+
+/// A table with the rowTracking (and its required domainMetadata) writer
+/// feature, with one file already assigned row IDs 0..=9 -- written
+/// straight into its `_delta_log`, as another Delta writer would have.
+fn create_row_tracking_table(server: &TestServer, label: &str) -> String {
+    let log_dir = server.table_path(label).join("_delta_log");
+    std::fs::create_dir_all(&log_dir).unwrap();
+    let schema_string = serde_json::json!({
+        "type": "struct",
+        "fields": [
+            {"name": "id", "type": "long", "nullable": false, "metadata": {}},
+            {"name": "amount", "type": "double", "nullable": true, "metadata": {}}
+        ]
+    })
+    .to_string();
+    let commits = [
+        vec![
+            serde_json::json!({"protocol": {
+                "minReaderVersion": 1,
+                "minWriterVersion": 7,
+                "writerFeatures": ["domainMetadata", "rowTracking"]
+            }}),
+            serde_json::json!({"metaData": {
+                "id": format!("test-table-{label}"),
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema_string,
+                "partitionColumns": [],
+                "configuration": {"delta.enableRowTracking": "true"},
+                "createdTime": 1_700_000_000_000_i64
+            }}),
+        ],
+        vec![
+            serde_json::json!({"add": {
+                "path": "part-0.parquet",
+                "partitionValues": {},
+                "size": 1024,
+                "modificationTime": 1_700_000_000_000_i64,
+                "dataChange": true,
+                "stats": "{\"numRecords\":10}",
+                "baseRowId": 0,
+                "defaultRowCommitVersion": 1
+            }}),
+            serde_json::json!({"domainMetadata": {
+                "domain": "delta.rowTracking",
+                "configuration": "{\"rowIdHighWaterMark\":9}",
+                "removed": false
+            }}),
+        ],
+    ];
+    for (version, actions) in commits.iter().enumerate() {
+        write_commit(&log_dir, version, actions);
+    }
+    server.new_table_uri(label)
+}
+
+fn write_commit(log_dir: &std::path::Path, version: usize, actions: &[serde_json::Value]) {
+    let body = actions
+        .iter()
+        .map(|action| action.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(log_dir.join(format!("{version:020}.json")), body).unwrap();
+}
+
+#[tokio::test]
+async fn row_tracking_fields_on_a_table_without_the_feature_are_refused() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let mut action = add_file_action("part-0.parquet", 10);
+    if let Some(PbAction::Add(add)) = &mut action.action {
+        add.base_row_id = Some(0);
+    }
+    let err = client
+        .commit(commit_request(&table_uri, Some(0), vec![action]))
+        .await
+        .expect_err("row tracking fields need the rowTracking feature");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert!(err.message().contains("'rowTracking'"), "{}", err.message());
+}
+
+#[tokio::test]
+async fn get_changes_reports_assigned_row_ids() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = create_row_tracking_table(&server, "events");
+    let mut client = server.connect().await;
+
+    let mut stream = client
+        .get_changes(pb::GetChangesRequest {
+            table_uri,
+            start_version: 1,
+            end_version: Some(1),
+        })
+        .await
+        .expect("GetChanges should succeed")
+        .into_inner();
+    let mut added = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::get_changes_response::Payload::Batch(batch)) = msg.payload {
+            added.extend(batch.actions.into_iter().filter_map(|a| match a.action {
+                Some(PbAction::Add(add)) => Some(add),
+                _ => None,
+            }));
+        }
+    }
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].base_row_id, Some(0));
+    assert_eq!(added[0].default_row_commit_version, Some(1));
+}
+
+/// Version `version`'s add (for `path`) and row-tracking domain metadata,
+/// straight out of its commit file.
+fn committed_row_tracking(
+    server: &TestServer,
+    label: &str,
+    version: i64,
+    path: &str,
+) -> (serde_json::Value, serde_json::Value) {
+    let commit = std::fs::read_to_string(
+        server
+            .table_path(label)
+            .join("_delta_log")
+            .join(format!("{version:020}.json")),
+    )
+    .expect("commit file should exist");
+    let actions: Vec<serde_json::Value> = commit
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let add = actions
+        .iter()
+        .find_map(|action| action.get("add").filter(|add| add["path"] == path))
+        .expect("the commit should add the file")
+        .clone();
+    let domain = actions
+        .iter()
+        .find_map(|action| action.get("domainMetadata"))
+        .expect("the commit should record the high-water mark")
+        .clone();
+    (add, domain)
+}
+
+#[tokio::test]
+async fn a_commit_to_a_row_tracking_table_is_assigned_row_ids() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = create_row_tracking_table(&server, "events");
+    let mut client = server.connect().await;
+
+    // No stats means no row count to hand out row IDs by.
+    let mut no_stats = add_file_action("part-1.parquet", 5);
+    if let Some(PbAction::Add(add)) = &mut no_stats.action {
+        add.stats = None;
+    }
+    let err = client
+        .commit(commit_request(&table_uri, Some(1), vec![no_stats]))
+        .await
+        .expect_err("an add without a row count can't be assigned row IDs");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(err.message().contains("num_records"), "{}", err.message());
+
+    let request = commit_request(
+        &table_uri,
+        Some(1),
+        vec![add_file_action("part-1.parquet", 5)],
+    );
+    let validated = client
+        .commit(pb::CommitRequest {
+            validate_only: true,
+            ..request.clone()
+        })
+        .await
+        .expect("a validate_only commit should pass")
+        .into_inner();
+    assert_eq!(validated.committed_version, 2);
+
+    let committed = client
+        .commit(request)
+        .await
+        .expect("a commit to a rowTracking table should succeed")
+        .into_inner();
+    assert_eq!(committed.committed_version, 2);
+
+    // Rows 10..=14, continuing from the mark of 9 the table was created
+    // with.
+    let (add, domain) = committed_row_tracking(&server, "events", 2, "part-1.parquet");
+    assert_eq!(add["baseRowId"], 10);
+    assert_eq!(add["defaultRowCommitVersion"], 2);
+    assert_eq!(domain["domain"], "delta.rowTracking");
+    assert_eq!(domain["configuration"], r#"{"rowIdHighWaterMark":14}"#);
+}
+
+#[tokio::test]
+async fn a_staged_commit_is_assigned_row_ids_past_an_append_since_begin() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = create_row_tracking_table(&server, "events");
+    let mut client = server.connect().await;
+
+    let txn = client
+        .begin_transaction(pb::BeginTransactionRequest {
+            table_uri: table_uri.clone(),
+        })
+        .await
+        .expect("BeginTransaction should succeed")
+        .into_inner();
+    client
+        .stage_actions(pb::StageActionsRequest {
+            txn_id: txn.txn_id.clone(),
+            actions: vec![add_file_action("part-1.parquet", 5)],
+        })
+        .await
+        .expect("StageActions should succeed");
+
+    // Another writer appends, taking row IDs 10..=14.
+    write_commit(
+        &server.table_path("events").join("_delta_log"),
+        2,
+        &[
+            serde_json::json!({"add": {
+                "path": "part-2.parquet",
+                "partitionValues": {},
+                "size": 1024,
+                "modificationTime": 1_700_000_000_000_i64,
+                "dataChange": true,
+                "stats": "{\"numRecords\":5}",
+                "baseRowId": 10,
+                "defaultRowCommitVersion": 2
+            }}),
+            serde_json::json!({"domainMetadata": {
+                "domain": "delta.rowTracking",
+                "configuration": "{\"rowIdHighWaterMark\":14}",
+                "removed": false
+            }}),
+        ],
+    );
+
+    // Not ABORTED: the append doesn't conflict, and row IDs are assigned
+    // against the version the commit lands on top of, not Begin's.
+    let committed = client
+        .commit_transaction(pb::CommitTransactionRequest { txn_id: txn.txn_id })
+        .await
+        .expect("the staged commit should land past the append")
+        .into_inner();
+    assert_eq!(committed.committed_version, 3);
+    let (add, domain) = committed_row_tracking(&server, "events", 3, "part-1.parquet");
+    assert_eq!(add["baseRowId"], 15);
+    assert_eq!(add["defaultRowCommitVersion"], 3);
+    assert_eq!(domain["configuration"], r#"{"rowIdHighWaterMark":19}"#);
+}
+
+#[tokio::test]
+async fn enabling_row_tracking_starts_row_ids_at_zero() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .enable_table_feature(pb::EnableTableFeatureRequest {
+            table_uri: table_uri.clone(),
+            feature: "rowTracking".to_string(),
+            expected_version: None,
+        })
+        .await
+        .expect("rowTracking should be enabled");
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(1),
+            vec![add_file_action("part-0.parquet", 3)],
+        ))
+        .await
+        .expect("a commit after enabling rowTracking should succeed");
+    let (add, domain) = committed_row_tracking(&server, "events", 2, "part-0.parquet");
+    assert_eq!(add["baseRowId"], 0);
+    assert_eq!(add["defaultRowCommitVersion"], 2);
+    assert_eq!(domain["configuration"], r#"{"rowIdHighWaterMark":2}"#);
+}
+
+#[tokio::test]
+async fn the_high_water_mark_is_read_from_the_row_tracking_domain() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = create_row_tracking_table(&server, "events");
+    let table = deltalake::open_table(url::Url::parse(&table_uri).unwrap())
+        .await
+        .expect("the table should open");
+    assert_eq!(
+        row_id_high_water_mark(&table).await.expect("readable mark"),
+        Some(9)
+    );
+}
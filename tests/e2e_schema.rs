@@ -0,0 +1,253 @@
+//! TableMetadata's structured `schema`: reported alongside schema_string by
+//! GetTable, ListActiveFiles' header and DescribeDetail, and accepted on
+//! Commit in place of schema_string -- nested structs, arrays, maps,
+//! decimals and field metadata included -- plus the malformed cases.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::{commit_request, create_table_actions, pb, sample_metadata, sample_protocol};
+use pb::list_active_files_response::Payload;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn primitive(primitive: pb::PrimitiveType) -> Option<pb::DataType> {
+    Some(pb::DataType {
+        kind: Some(pb::data_type::Kind::Primitive(primitive as i32)),
+    })
+}
+
+fn field(name: &str, data_type: Option<pb::DataType>, nullable: bool) -> pb::StructField {
+    pb::StructField {
+        name: name.to_string(),
+        data_type,
+        nullable,
+        metadata: HashMap::new(),
+    }
+}
+
+/// One of everything: a decimal, an array of structs, a map, a nested
+/// struct, and field metadata of each value shape.
+fn rich_schema() -> pb::Schema {
+    use pb::data_type::Kind;
+    use pb::field_metadata_value::Value;
+
+    let line_item = pb::Schema {
+        fields: vec![
+            field("sku", primitive(pb::PrimitiveType::String), false),
+            field("quantity", primitive(pb::PrimitiveType::Integer), true),
+        ],
+    };
+    let mut id = field("id", primitive(pb::PrimitiveType::Long), false);
+    id.metadata = HashMap::from([
+        (
+            "comment".to_string(),
+            pb::FieldMetadataValue {
+                value: Some(Value::StringValue("order id".to_string())),
+            },
+        ),
+        (
+            "max_length".to_string(),
+            pb::FieldMetadataValue {
+                value: Some(Value::NumberValue(1)),
+            },
+        ),
+        (
+            "pii".to_string(),
+            pb::FieldMetadataValue {
+                value: Some(Value::BoolValue(false)),
+            },
+        ),
+        (
+            "tags".to_string(),
+            pb::FieldMetadataValue {
+                value: Some(Value::JsonValue(r#"["a","b"]"#.to_string())),
+            },
+        ),
+    ]);
+
+    pb::Schema {
+        fields: vec![
+            id,
+            field(
+                "amount",
+                Some(pb::DataType {
+                    kind: Some(Kind::Decimal(pb::DecimalType {
+                        precision: 10,
+                        scale: 2,
+                    })),
+                }),
+                true,
+            ),
+            field(
+                "items",
+                Some(pb::DataType {
+                    kind: Some(Kind::Array(Box::new(pb::ArrayType {
+                        element_type: Some(Box::new(pb::DataType {
+                            kind: Some(Kind::StructType(line_item)),
+                        })),
+                        contains_null: false,
+                    }))),
+                }),
+                true,
+            ),
+            field(
+                "attributes",
+                Some(pb::DataType {
+                    kind: Some(Kind::Map(Box::new(pb::MapType {
+                        key_type: primitive(pb::PrimitiveType::String).map(Box::new),
+                        value_type: primitive(pb::PrimitiveType::Timestamp).map(Box::new),
+                        value_contains_null: true,
+                    }))),
+                }),
+                true,
+            ),
+        ],
+    }
+}
+
+fn create_with_schema(schema: pb::Schema, schema_string: &str) -> Vec<pb::Action> {
+    vec![
+        pb::Action {
+            action: Some(pb::action::Action::Protocol(sample_protocol())),
+        },
+        pb::Action {
+            action: Some(pb::action::Action::MetaData(pb::TableMetadata {
+                schema_string: schema_string.to_string(),
+                schema: Some(schema),
+                ..sample_metadata("orders")
+            })),
+        },
+    ]
+}
+
+async fn get_metadata(client: &mut Client, table_uri: &str) -> pb::TableMetadata {
+    client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect("GetTable should succeed")
+        .into_inner()
+        .metadata
+        .expect("metadata")
+}
+
+#[tokio::test]
+async fn reads_report_the_schema_string_as_a_structured_schema_too() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let expected = pb::Schema {
+        fields: vec![
+            field("id", primitive(pb::PrimitiveType::Long), false),
+            field("amount", primitive(pb::PrimitiveType::Double), true),
+        ],
+    };
+    let metadata = get_metadata(&mut client, &table_uri).await;
+    assert!(!metadata.schema_string.is_empty());
+    assert_eq!(metadata.schema, Some(expected.clone()));
+
+    let mut stream = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri: table_uri.clone(),
+            ..Default::default()
+        })
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+    let header = match stream.message().await.unwrap().unwrap().payload {
+        Some(Payload::Header(h)) => h,
+        other => panic!("expected header as the first message, got {other:?}"),
+    };
+    assert_eq!(header.metadata.expect("metadata").schema, Some(expected));
+}
+
+#[tokio::test]
+async fn a_structured_schema_on_commit_round_trips() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_with_schema(rich_schema(), ""),
+        ))
+        .await
+        .expect("a create with only a structured schema should succeed");
+
+    let metadata = get_metadata(&mut client, &table_uri).await;
+    assert_eq!(metadata.schema, Some(rich_schema()));
+    // Recorded in the log as ordinary schema JSON, for every other reader.
+    let recorded: serde_json::Value = serde_json::from_str(&metadata.schema_string).unwrap();
+    assert_eq!(recorded["fields"][1]["type"], "decimal(10,2)");
+    assert_eq!(recorded["fields"][2]["type"]["type"], "array");
+}
+
+#[tokio::test]
+async fn a_malformed_structured_schema_is_rejected() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+
+    let mut missing_type = rich_schema();
+    if let Some(pb::data_type::Kind::Array(array)) = missing_type.fields[2]
+        .data_type
+        .as_mut()
+        .and_then(|t| t.kind.as_mut())
+    {
+        if let Some(pb::data_type::Kind::StructType(item)) =
+            array.element_type.as_mut().and_then(|t| t.kind.as_mut())
+        {
+            item.fields[1].data_type = None;
+        }
+    }
+    let mut bad_decimal = rich_schema();
+    bad_decimal.fields[1].data_type = Some(pb::DataType {
+        kind: Some(pb::data_type::Kind::Decimal(pb::DecimalType {
+            precision: 40,
+            scale: 2,
+        })),
+    });
+    let mut duplicate = rich_schema();
+    duplicate
+        .fields
+        .push(field("ID", primitive(pb::PrimitiveType::Long), true));
+
+    for (actions, expected) in [
+        (
+            create_with_schema(missing_type, ""),
+            "`items.element.quantity` has no data_type",
+        ),
+        (create_with_schema(bad_decimal, ""), "`amount`"),
+        (create_with_schema(duplicate, ""), "Duplicate field name"),
+        (
+            create_with_schema(rich_schema(), r#"{"type":"struct","fields":[]}"#),
+            "not both",
+        ),
+    ] {
+        let err = client
+            .commit(commit_request(&table_uri, None, actions))
+            .await
+            .expect_err("a malformed schema must be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert!(
+            err.message().contains(expected),
+            "expected {expected:?} in: {}",
+            err.message()
+        );
+    }
+}
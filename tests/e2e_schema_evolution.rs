@@ -0,0 +1,151 @@
+//! Schema evolution on Commit: a TableMetadata action for an existing table
+//! checked against its current schema under the configured policy --
+//! additive changes through by default, drops and type changes rejected
+//! naming the field, widening and anything-goes when configured.
+
+mod common;
+
+use common::{commit_request, create_table_actions, pb, sample_metadata, TestServer};
+use delta_txn_service::delta::schema_evolution::SchemaEvolutionPolicy;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+/// common's sample schema is `id: long not null, amount: double`.
+fn metadata_action(fields: serde_json::Value) -> pb::Action {
+    pb::Action {
+        action: Some(pb::action::Action::MetaData(pb::TableMetadata {
+            schema_string: serde_json::json!({"type": "struct", "fields": fields}).to_string(),
+            ..sample_metadata("orders")
+        })),
+    }
+}
+
+async fn start(policy: SchemaEvolutionPolicy) -> (TestServer, Client, String) {
+    let server = TestServer::start(common::TestServerConfig {
+        schema_evolution: policy,
+        ..Default::default()
+    })
+    .await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    (server, client, table_uri)
+}
+
+#[tokio::test]
+async fn the_default_policy_accepts_a_new_nullable_column() {
+    let (_server, mut client, table_uri) = start(Default::default()).await;
+
+    let response = client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![metadata_action(serde_json::json!([
+                {"name": "id", "type": "long", "nullable": false, "metadata": {}},
+                {"name": "amount", "type": "double", "nullable": true, "metadata": {}},
+                {"name": "note", "type": "string", "nullable": true, "metadata": {}}
+            ]))],
+        ))
+        .await
+        .expect("an additive change should be accepted")
+        .into_inner();
+    assert_eq!(response.committed_version, 1);
+}
+
+#[tokio::test]
+async fn the_default_policy_rejects_drops_type_changes_and_required_columns() {
+    let (_server, mut client, table_uri) = start(Default::default()).await;
+
+    for (fields, expected) in [
+        (
+            serde_json::json!([
+                {"name": "id", "type": "long", "nullable": false, "metadata": {}}
+            ]),
+            "`amount` was dropped",
+        ),
+        (
+            serde_json::json!([
+                {"name": "id", "type": "string", "nullable": false, "metadata": {}},
+                {"name": "amount", "type": "double", "nullable": true, "metadata": {}}
+            ]),
+            "`id` changes type",
+        ),
+        (
+            serde_json::json!([
+                {"name": "id", "type": "long", "nullable": false, "metadata": {}},
+                {"name": "amount", "type": "double", "nullable": true, "metadata": {}},
+                {"name": "note", "type": "string", "nullable": false, "metadata": {}}
+            ]),
+            "`note` must be nullable",
+        ),
+    ] {
+        let err = client
+            .commit(commit_request(
+                &table_uri,
+                Some(0),
+                vec![metadata_action(fields)],
+            ))
+            .await
+            .expect_err("an incompatible schema change must be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert!(
+            err.message().contains(expected),
+            "expected {expected:?} in: {}",
+            err.message()
+        );
+    }
+}
+
+#[tokio::test]
+async fn the_widening_policy_accepts_a_wider_type_only() {
+    let (_server, mut client, table_uri) = start(SchemaEvolutionPolicy::Widening).await;
+
+    let err = client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![metadata_action(serde_json::json!([
+                {"name": "id", "type": "integer", "nullable": false, "metadata": {}},
+                {"name": "amount", "type": "double", "nullable": true, "metadata": {}}
+            ]))],
+        ))
+        .await
+        .expect_err("long -> integer narrows");
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![metadata_action(serde_json::json!([
+                {"name": "id", "type": "decimal(25,2)", "nullable": false, "metadata": {}},
+                {"name": "amount", "type": "double", "nullable": true, "metadata": {}}
+            ]))],
+        ))
+        .await
+        .expect("long -> decimal(25,2) widens");
+}
+
+#[tokio::test]
+async fn the_any_policy_accepts_a_dropped_column() {
+    let (_server, mut client, table_uri) = start(SchemaEvolutionPolicy::Any).await;
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![metadata_action(serde_json::json!([
+                {"name": "id", "type": "long", "nullable": false, "metadata": {}}
+            ]))],
+        ))
+        .await
+        .expect("the any policy checks nothing");
+}
@@ -0,0 +1,175 @@
+//! SetTransaction (txn) actions: an exactly-once writer's retry of a
+//! commit that already landed rejected as a duplicate, the recorded
+//! version read back by GetTransactionVersion and reported by GetChanges,
+//! and malformed txn actions rejected.
+
+mod common;
+
+use common::{add_file_action, commit_request, create_table_actions, pb, TestServer};
+use pb::action::Action as PbAction;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+fn txn_action(app_id: &str, version: i64) -> pb::Action {
+    pb::Action {
+        action: Some(PbAction::Txn(pb::SetTransaction {
+            app_id: app_id.to_string(),
+            version,
+            last_updated: Some(1_700_000_000_000),
+        })),
+    }
+}
+
+async fn create(client: &mut Client, table_uri: &str) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+}
+
+async fn transaction_version(
+    client: &mut Client,
+    table_uri: &str,
+    app_id: &str,
+) -> pb::GetTransactionVersionResponse {
+    client
+        .get_transaction_version(pb::GetTransactionVersionRequest {
+            table_uri: table_uri.to_string(),
+            app_id: app_id.to_string(),
+        })
+        .await
+        .expect("GetTransactionVersion should succeed")
+        .into_inner()
+}
+
+#[tokio::test]
+async fn a_retried_micro_batch_is_rejected_as_a_duplicate() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create(&mut client, &table_uri).await;
+
+    let batch = |n: i64| {
+        vec![
+            add_file_action(&format!("batch-{n}.parquet"), 10),
+            txn_action("ingest", n),
+        ]
+    };
+    client
+        .commit(commit_request(&table_uri, None, batch(1)))
+        .await
+        .expect("the first micro-batch should commit");
+    assert_eq!(
+        transaction_version(&mut client, &table_uri, "ingest").await,
+        pb::GetTransactionVersionResponse {
+            version: Some(1),
+            table_version: 1,
+        }
+    );
+
+    // The ingester crashed before seeing the response and replays batch 1.
+    let err = client
+        .commit(commit_request(&table_uri, None, batch(1)))
+        .await
+        .expect_err("the replayed micro-batch must be rejected");
+    assert_eq!(err.code(), Code::AlreadyExists);
+    assert!(err.message().contains("'ingest'"), "{}", err.message());
+    assert_eq!(
+        transaction_version(&mut client, &table_uri, "ingest")
+            .await
+            .table_version,
+        1,
+        "nothing should have been written"
+    );
+
+    client
+        .commit(commit_request(&table_uri, Some(1), batch(2)))
+        .await
+        .expect("the next micro-batch should commit");
+    assert_eq!(
+        transaction_version(&mut client, &table_uri, "ingest")
+            .await
+            .version,
+        Some(2)
+    );
+    // Another application's progress is its own.
+    assert_eq!(
+        transaction_version(&mut client, &table_uri, "other")
+            .await
+            .version,
+        None
+    );
+
+    let mut stream = client
+        .get_changes(pb::GetChangesRequest {
+            table_uri: table_uri.clone(),
+            start_version: 2,
+            end_version: Some(2),
+        })
+        .await
+        .expect("GetChanges should succeed")
+        .into_inner();
+    let mut txns = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::get_changes_response::Payload::Batch(batch)) = msg.payload {
+            txns.extend(batch.actions.into_iter().filter_map(|a| match a.action {
+                Some(PbAction::Txn(txn)) => Some(txn),
+                _ => None,
+            }));
+        }
+    }
+    assert_eq!(txns.len(), 1);
+    assert_eq!((txns[0].app_id.as_str(), txns[0].version), ("ingest", 2));
+}
+
+#[tokio::test]
+async fn malformed_transactions_are_rejected() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create(&mut client, &table_uri).await;
+
+    let cases = [
+        ("an empty app_id", vec![txn_action("", 1)], "app_id"),
+        (
+            "two txns for one app_id",
+            vec![txn_action("ingest", 1), txn_action("ingest", 2)],
+            "more than one",
+        ),
+    ];
+    for (case, actions, expected) in cases {
+        let err = client
+            .commit(commit_request(&table_uri, Some(0), actions))
+            .await
+            .expect_err(case);
+        assert_eq!(err.code(), Code::InvalidArgument, "{case}");
+        assert!(
+            err.message().contains(expected),
+            "{case}: {}",
+            err.message()
+        );
+    }
+
+    let err = client
+        .get_transaction_version(pb::GetTransactionVersionRequest {
+            table_uri: table_uri.clone(),
+            app_id: String::new(),
+        })
+        .await
+        .expect_err("GetTransactionVersion needs an app_id");
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    let err = client
+        .get_transaction_version(pb::GetTransactionVersionRequest {
+            table_uri: server.new_table_uri("never-created"),
+            app_id: "ingest".to_string(),
+        })
+        .await
+        .expect_err("a missing table is NOT_FOUND");
+    assert_eq!(err.code(), Code::NotFound);
+}
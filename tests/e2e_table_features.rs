@@ -0,0 +1,326 @@
+//! Reader/writer table features on Protocol: set on a creating Commit,
+//! reported back by GetTable and the ListActiveFiles header, and feature
+//! lists the spec doesn't allow rejected before anything is written --
+//! plus EnableTableFeature upgrading a table's protocol (and refusing
+//! features delta-rs can't write), and Commit refusing a Protocol action
+//! that would downgrade one.
+
+mod common;
+
+use common::{commit_request, pb, sample_metadata, sample_protocol};
+use tonic::Code;
+
+fn features(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn create_actions(protocol: pb::Protocol) -> Vec<pb::Action> {
+    vec![
+        pb::Action {
+            action: Some(pb::action::Action::Protocol(protocol)),
+        },
+        pb::Action {
+            action: Some(pb::action::Action::MetaData(sample_metadata("events"))),
+        },
+    ]
+}
+
+/// deletionVectors is a reader-writer feature, so it's in both lists;
+/// appendOnly is writer-only.
+fn table_features_protocol() -> pb::Protocol {
+    pb::Protocol {
+        min_reader_version: 3,
+        min_writer_version: 7,
+        reader_features: features(&["deletionVectors"]),
+        writer_features: features(&["appendOnly", "deletionVectors"]),
+    }
+}
+
+#[tokio::test]
+async fn feature_lists_round_trip_through_get_table_and_list_active_files() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_actions(table_features_protocol()),
+        ))
+        .await
+        .expect("creating a table-features table should succeed");
+
+    let protocol = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            ..Default::default()
+        })
+        .await
+        .expect("GetTable should succeed")
+        .into_inner()
+        .protocol
+        .expect("expected protocol");
+    assert_eq!(protocol, table_features_protocol());
+
+    let mut stream = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri,
+            ..Default::default()
+        })
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+    let header = match stream.message().await.unwrap().unwrap().payload {
+        Some(pb::list_active_files_response::Payload::Header(h)) => h,
+        other => panic!("expected header as the first message, got {other:?}"),
+    };
+    assert_eq!(header.protocol, Some(table_features_protocol()));
+}
+
+#[tokio::test]
+async fn a_legacy_table_reports_no_features() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            common::create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let protocol = client
+        .get_table(pb::GetTableRequest {
+            table_uri,
+            ..Default::default()
+        })
+        .await
+        .expect("GetTable should succeed")
+        .into_inner()
+        .protocol
+        .expect("expected protocol");
+    assert!(protocol.reader_features.is_empty());
+    assert!(protocol.writer_features.is_empty());
+}
+
+#[tokio::test]
+async fn feature_lists_the_spec_disallows_are_rejected() {
+    let server = common::TestServer::start(Default::default()).await;
+    let mut client = server.connect().await;
+
+    let cases = [
+        (
+            "reader features below reader version 3",
+            pb::Protocol {
+                min_reader_version: 1,
+                ..table_features_protocol()
+            },
+            "min_reader_version 3",
+        ),
+        (
+            "writer features below writer version 7",
+            pb::Protocol {
+                min_reader_version: 1,
+                min_writer_version: 2,
+                reader_features: Vec::new(),
+                writer_features: features(&["appendOnly"]),
+            },
+            "min_writer_version 7",
+        ),
+        (
+            "a reader feature missing from the writer list",
+            pb::Protocol {
+                writer_features: features(&["appendOnly"]),
+                ..table_features_protocol()
+            },
+            "'deletionVectors'",
+        ),
+    ];
+    for (i, (case, protocol, expected)) in cases.into_iter().enumerate() {
+        let table_uri = server.new_table_uri(&format!("events-{i}"));
+        let err = client
+            .commit(commit_request(&table_uri, None, create_actions(protocol)))
+            .await
+            .expect_err(case);
+        assert_eq!(err.code(), Code::InvalidArgument, "{case}");
+        assert!(
+            err.message().contains(expected),
+            "{case}: {}",
+            err.message()
+        );
+    }
+}
+
+fn enable_request(table_uri: &str, feature: &str) -> pb::EnableTableFeatureRequest {
+    pb::EnableTableFeatureRequest {
+        table_uri: table_uri.to_string(),
+        feature: feature.to_string(),
+        expected_version: None,
+    }
+}
+
+#[tokio::test]
+async fn enable_table_feature_upgrades_a_legacy_protocol() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            common::create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let response = client
+        .enable_table_feature(pb::EnableTableFeatureRequest {
+            expected_version: Some(0),
+            ..enable_request(&table_uri, "deletionVectors")
+        })
+        .await
+        .expect("enabling deletionVectors should succeed")
+        .into_inner();
+    assert_eq!(response.committed_version, Some(1));
+    // Writer version 2's implied appendOnly and invariants stay supported,
+    // now listed.
+    let upgraded = pb::Protocol {
+        min_reader_version: 3,
+        min_writer_version: 7,
+        reader_features: features(&["deletionVectors"]),
+        writer_features: features(&["appendOnly", "invariants", "deletionVectors"]),
+    };
+    assert_eq!(response.protocol, Some(upgraded.clone()));
+
+    let mut stream = client
+        .get_history(pb::GetHistoryRequest {
+            table_uri: table_uri.clone(),
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await
+        .expect("GetHistory should succeed")
+        .into_inner();
+    let latest = stream.message().await.unwrap().expect("one entry");
+    assert_eq!(latest.version, 1);
+    assert_eq!(
+        latest.commit_info.expect("a CommitInfo").operation,
+        pb::CommitOperation::AddFeature as i32
+    );
+
+    // Already supported: nothing more to commit.
+    let again = client
+        .enable_table_feature(enable_request(&table_uri, "deletionVectors"))
+        .await
+        .expect("enabling a supported feature should succeed")
+        .into_inner();
+    assert_eq!(again.committed_version, None);
+    assert_eq!(again.protocol, Some(upgraded));
+}
+
+#[tokio::test]
+async fn enable_table_feature_rejects_bad_requests() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+
+    let err = client
+        .enable_table_feature(enable_request(&table_uri, "appendOnly"))
+        .await
+        .expect_err("the table doesn't exist yet");
+    assert_eq!(err.code(), Code::NotFound);
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            common::create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    let err = client
+        .enable_table_feature(enable_request(&table_uri, "noSuchFeature"))
+        .await
+        .expect_err("an unknown feature must be rejected");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(err.message().contains("noSuchFeature"), "{}", err.message());
+
+    let err = client
+        .enable_table_feature(pb::EnableTableFeatureRequest {
+            expected_version: Some(5),
+            ..enable_request(&table_uri, "changeDataFeed")
+        })
+        .await
+        .expect_err("a stale expected_version must be rejected");
+    assert_eq!(err.code(), Code::Aborted);
+}
+
+#[tokio::test]
+async fn enable_table_feature_refuses_features_the_writer_cant_support() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            common::create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    for feature in ["columnMapping", "identityColumns"] {
+        let err = client
+            .enable_table_feature(enable_request(&table_uri, feature))
+            .await
+            .expect_err("delta-rs can't commit to a table with this feature");
+        assert_eq!(err.code(), Code::FailedPrecondition, "{feature}");
+        assert!(err.message().contains(feature), "{}", err.message());
+    }
+
+    // Refused before anything was committed.
+    let table = client
+        .get_table(pb::GetTableRequest {
+            table_uri,
+            ..Default::default()
+        })
+        .await
+        .expect("GetTable should succeed")
+        .into_inner();
+    assert_eq!(table.version, 0);
+    assert_eq!(table.protocol, Some(sample_protocol()));
+}
+
+#[tokio::test]
+async fn a_commit_cannot_downgrade_the_protocol() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_actions(table_features_protocol()),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let err = client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![pb::Action {
+                action: Some(pb::action::Action::Protocol(sample_protocol())),
+            }],
+        ))
+        .await
+        .expect_err("going back to reader 1/writer 2 is a downgrade");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(
+        err.message().contains("protocol change refused"),
+        "{}",
+        err.message()
+    );
+}
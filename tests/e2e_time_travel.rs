@@ -0,0 +1,464 @@
+//! Time-travel reads: GetTable/ListActiveFiles pinned to an older version
+//! via their `as_of` selector (an explicit version, or a timestamp resolved
+//! to one), rather than whatever snapshot is latest -- including the two
+//! distinct ways a pinned version can be unreadable (never existed yet vs.
+//! already cleaned up by log retention), which deliberately surface as
+//! different gRPC status codes -- plus GetVersionAtTimestamp, the
+//! timestamp resolution on its own.
+
+mod common;
+
+use common::{add_file_action, commit_request, create_table_actions, pb, TestServer};
+use pb::get_table_request::AsOf as GetTableAsOf;
+use pb::list_active_files_request::AsOf as ListAsOf;
+use tonic::Code;
+
+/// Creates a table and lands `appends` single-file append commits on it,
+/// leaving it at version `appends` -- file `part-{n}.parquet` first
+/// becomes active at version `n + 1`.
+async fn table_with_appends(
+    client: &mut pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>,
+    table_uri: &str,
+    appends: i64,
+) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    for n in 0..appends {
+        client
+            .commit(commit_request(
+                table_uri,
+                Some(n),
+                vec![add_file_action(&format!("part-{n}.parquet"), 1)],
+            ))
+            .await
+            .expect("append should succeed");
+    }
+}
+
+async fn list_paths_at(
+    client: &mut pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>,
+    table_uri: &str,
+    as_of: Option<ListAsOf>,
+) -> (i64, Vec<String>) {
+    let mut stream = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri: table_uri.to_string(),
+            as_of,
+            ..Default::default()
+        })
+        .await
+        .expect("ListActiveFiles should succeed")
+        .into_inner();
+
+    let header = match stream.message().await.unwrap().unwrap().payload {
+        Some(pb::list_active_files_response::Payload::Header(h)) => h,
+        other => panic!("expected header as the first message, got {other:?}"),
+    };
+    let mut paths = Vec::new();
+    while let Some(msg) = stream.message().await.expect("stream should not error") {
+        if let Some(pb::list_active_files_response::Payload::Batch(batch)) = msg.payload {
+            paths.extend(batch.files.into_iter().map(|f| f.path));
+        }
+    }
+    paths.sort();
+    (header.version, paths)
+}
+
+#[tokio::test]
+async fn get_table_reads_an_older_version_when_one_is_requested() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 2).await;
+
+    let latest = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: None,
+        })
+        .await
+        .expect("GetTable at latest should succeed")
+        .into_inner();
+    assert_eq!(latest.version, 2);
+
+    let pinned = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Version(1)),
+        })
+        .await
+        .expect("GetTable at an existing older version should succeed")
+        .into_inner();
+    assert_eq!(pinned.version, 1);
+    assert_eq!(pinned.metadata.expect("expected metadata").name, "orders");
+}
+
+#[tokio::test]
+async fn list_active_files_streams_the_file_set_as_of_the_requested_version() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 3).await;
+
+    let (version, paths) = list_paths_at(&mut client, &table_uri, Some(ListAsOf::Version(1))).await;
+    assert_eq!(version, 1);
+    assert_eq!(paths, vec!["part-0.parquet".to_string()]);
+
+    let (version, paths) = list_paths_at(&mut client, &table_uri, Some(ListAsOf::Version(0))).await;
+    assert_eq!(version, 0);
+    assert!(paths.is_empty(), "version 0 predates every append");
+
+    let (version, paths) = list_paths_at(&mut client, &table_uri, None).await;
+    assert_eq!(version, 3);
+    assert_eq!(paths.len(), 3);
+}
+
+#[tokio::test]
+async fn a_version_newer_than_the_latest_is_not_found() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 1).await;
+
+    let err = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Version(5)),
+        })
+        .await
+        .expect_err("a version the table hasn't reached yet must be rejected");
+    assert_eq!(err.code(), Code::NotFound);
+    assert!(
+        err.message().contains("latest version is 1"),
+        "message should name the table's actual latest version, got: {}",
+        err.message()
+    );
+
+    let mut stream = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(ListAsOf::Version(5)),
+            ..Default::default()
+        })
+        .await
+        .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
+        .into_inner();
+    let err = stream
+        .message()
+        .await
+        .expect_err("the stream's first (and only) item should be an error");
+    assert_eq!(err.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn a_negative_version_is_an_invalid_argument() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 0).await;
+
+    let err = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Version(-1)),
+        })
+        .await
+        .expect_err("a negative version must be rejected");
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    // Rejected synchronously, before the stream even starts.
+    let err = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(ListAsOf::Version(-1)),
+            ..Default::default()
+        })
+        .await
+        .expect_err("a negative version must fail the RPC call itself");
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn a_version_cleaned_up_by_log_retention_is_a_failed_precondition() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 2).await;
+
+    // Simulates what log retention cleanup leaves behind: a checkpoint at
+    // the latest version (2), with every commit file before it deleted.
+    // Version 2 (and anything after) stays readable off the checkpoint;
+    // versions 0 and 1 no longer have anything to be reconstructed from.
+    let table = deltalake::open_table(url::Url::parse(&table_uri).unwrap())
+        .await
+        .expect("test should be able to open its own table directly");
+    deltalake::checkpoints::create_checkpoint(&table, None)
+        .await
+        .expect("checkpoint should succeed");
+    let log_dir = server.table_path("orders").join("_delta_log");
+    for version in 0..2 {
+        std::fs::remove_file(log_dir.join(format!("{version:020}.json")))
+            .expect("commit file should exist before cleanup");
+    }
+
+    let err = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Version(1)),
+        })
+        .await
+        .expect_err("a cleaned-up version must be rejected");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+
+    let still_readable = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Version(2)),
+        })
+        .await
+        .expect("the checkpointed version itself should remain readable")
+        .into_inner();
+    assert_eq!(still_readable.version, 2);
+}
+
+#[tokio::test]
+async fn an_unreadable_checkpoint_is_an_internal_error_not_a_cleaned_up_version() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 2).await;
+
+    // A checkpoint at version 2 that won't parse: version 2 is still there
+    // to be read, the load just fails -- which says nothing about log
+    // retention, and must not be reported as if it did.
+    let table = deltalake::open_table(url::Url::parse(&table_uri).unwrap())
+        .await
+        .expect("test should be able to open its own table directly");
+    deltalake::checkpoints::create_checkpoint(&table, None)
+        .await
+        .expect("checkpoint should succeed");
+    let log_dir = server.table_path("orders").join("_delta_log");
+    std::fs::write(
+        log_dir.join(format!("{:020}.checkpoint.parquet", 2)),
+        b"not a parquet file",
+    )
+    .expect("checkpoint file should be writable");
+
+    let err = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Version(2)),
+        })
+        .await
+        .expect_err("an unreadable checkpoint must fail the read");
+    assert_eq!(err.code(), Code::Internal, "{}", err.message());
+}
+
+/// Reads version `version`'s commitInfo timestamp straight out of its
+/// commit file -- the ground truth the server's timestamp resolution should
+/// agree with.
+fn commit_timestamp(server: &TestServer, label: &str, version: i64) -> i64 {
+    let path = server
+        .table_path(label)
+        .join("_delta_log")
+        .join(format!("{version:020}.json"));
+    let contents = std::fs::read_to_string(path).expect("commit file should exist");
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find_map(|action| action.get("commitInfo")?.get("timestamp")?.as_i64())
+        .expect("every commit this service writes should carry a commitInfo timestamp")
+}
+
+/// `table_with_appends`, with a pause between commits so every version
+/// gets a distinct millisecond commit timestamp -- the timestamp tests
+/// below need to aim *between* two commits.
+async fn table_with_spaced_appends(
+    client: &mut pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>,
+    table_uri: &str,
+    appends: i64,
+) {
+    table_with_appends(client, table_uri, 0).await;
+    for n in 0..appends {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        client
+            .commit(commit_request(
+                table_uri,
+                Some(n),
+                vec![add_file_action(&format!("part-{n}.parquet"), 1)],
+            ))
+            .await
+            .expect("append should succeed");
+    }
+}
+
+async fn version_at(
+    client: &mut pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>,
+    table_uri: &str,
+    timestamp: i64,
+) -> Result<pb::GetVersionAtTimestampResponse, tonic::Status> {
+    client
+        .get_version_at_timestamp(pb::GetVersionAtTimestampRequest {
+            table_uri: table_uri.to_string(),
+            timestamp,
+        })
+        .await
+        .map(|r| r.into_inner())
+}
+
+#[tokio::test]
+async fn get_version_at_timestamp_resolves_to_the_newest_version_committed_at_or_before_it() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_spaced_appends(&mut client, &table_uri, 2).await;
+    let ts: Vec<i64> = (0..=2)
+        .map(|v| commit_timestamp(&server, "orders", v))
+        .collect();
+
+    // Exactly on a commit's timestamp: that commit.
+    let resolved = version_at(&mut client, &table_uri, ts[1]).await.unwrap();
+    assert_eq!(resolved.version, 1);
+    assert_eq!(resolved.commit_timestamp, ts[1]);
+
+    // Between two commits: the earlier one.
+    let resolved = version_at(&mut client, &table_uri, ts[2] - 1)
+        .await
+        .unwrap();
+    assert_eq!(resolved.version, 1);
+
+    let resolved = version_at(&mut client, &table_uri, ts[0]).await.unwrap();
+    assert_eq!(resolved.version, 0);
+
+    // After the latest commit: the latest version.
+    let resolved = version_at(&mut client, &table_uri, ts[2] + 60_000)
+        .await
+        .unwrap();
+    assert_eq!(resolved.version, 2);
+    assert_eq!(resolved.commit_timestamp, ts[2]);
+}
+
+#[tokio::test]
+async fn get_table_and_list_active_files_read_as_of_a_timestamp() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_spaced_appends(&mut client, &table_uri, 2).await;
+    let between_1_and_2 = commit_timestamp(&server, "orders", 2) - 1;
+
+    let table = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Timestamp(between_1_and_2)),
+        })
+        .await
+        .expect("GetTable as of a timestamp should succeed")
+        .into_inner();
+    assert_eq!(table.version, 1);
+
+    let (version, paths) = list_paths_at(
+        &mut client,
+        &table_uri,
+        Some(ListAsOf::Timestamp(between_1_and_2)),
+    )
+    .await;
+    assert_eq!(version, 1);
+    assert_eq!(paths, vec!["part-0.parquet".to_string()]);
+}
+
+#[tokio::test]
+async fn a_timestamp_before_the_table_existed_is_a_failed_precondition() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_spaced_appends(&mut client, &table_uri, 1).await;
+    let created_at = commit_timestamp(&server, "orders", 0);
+
+    let err = version_at(&mut client, &table_uri, created_at - 1)
+        .await
+        .expect_err("a timestamp before version 0 has no version to resolve to");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert!(
+        err.message().contains("earliest available version 0"),
+        "message should name the earliest version, got: {}",
+        err.message()
+    );
+
+    let err = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(GetTableAsOf::Timestamp(created_at - 1)),
+        })
+        .await
+        .expect_err("GetTable resolves timestamps the same way");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn a_timestamp_before_the_oldest_retained_version_is_a_failed_precondition() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_spaced_appends(&mut client, &table_uri, 3).await;
+    let ts: Vec<i64> = (0..=3)
+        .map(|v| commit_timestamp(&server, "orders", v))
+        .collect();
+
+    // Same simulated retention cleanup as the version-based test above,
+    // checkpointing at version 3 and keeping only commit files 2 and 3 --
+    // version 2's commit file survives as a straggler, but with no
+    // checkpoint at or below it, 3 is the oldest version still loadable.
+    let table = deltalake::open_table(url::Url::parse(&table_uri).unwrap())
+        .await
+        .expect("test should be able to open its own table directly");
+    deltalake::checkpoints::create_checkpoint(&table, None)
+        .await
+        .expect("checkpoint should succeed");
+    let log_dir = server.table_path("orders").join("_delta_log");
+    for version in 0..2 {
+        std::fs::remove_file(log_dir.join(format!("{version:020}.json")))
+            .expect("commit file should exist before cleanup");
+    }
+
+    let err = version_at(&mut client, &table_uri, ts[2])
+        .await
+        .expect_err("version 2 can no longer be loaded");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert!(
+        err.message().contains("earliest available version 3"),
+        "message should name the earliest loadable version, got: {}",
+        err.message()
+    );
+
+    let resolved = version_at(&mut client, &table_uri, ts[3]).await.unwrap();
+    assert_eq!(resolved.version, 3);
+}
+
+#[tokio::test]
+async fn a_negative_timestamp_is_an_invalid_argument() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    table_with_appends(&mut client, &table_uri, 0).await;
+
+    let err = version_at(&mut client, &table_uri, -1)
+        .await
+        .expect_err("a negative timestamp must be rejected");
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    let err = client
+        .list_active_files(pb::ListActiveFilesRequest {
+            table_uri: table_uri.clone(),
+            as_of: Some(ListAsOf::Timestamp(-1)),
+            ..Default::default()
+        })
+        .await
+        .expect_err("a negative timestamp must fail the RPC call itself");
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
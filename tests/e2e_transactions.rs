@@ -0,0 +1,304 @@
+//! Staged transactions: actions staged over several calls committed as one
+//! version, an aborted transaction committing nothing, the commit
+//! conflict-checked against every version since Begin (a concurrent
+//! remove of the same file conflicts, a concurrent append doesn't), and
+//! the server's byte limit and idle TTL.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{
+    active_paths, add_file_action, commit_request, create_table_actions, pb, remove_file_action,
+    TestServer, TestServerConfig,
+};
+use delta_txn_service::grpc::staging::StagingLimits;
+use tonic::Code;
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+async fn create_table(client: &mut Client, table_uri: &str, files: &[&str]) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    if !files.is_empty() {
+        let adds = files.iter().map(|path| add_file_action(path, 10)).collect();
+        client
+            .commit(commit_request(table_uri, Some(0), adds))
+            .await
+            .expect("seeding files should succeed");
+    }
+}
+
+async fn begin(client: &mut Client, table_uri: &str) -> pb::BeginTransactionResponse {
+    client
+        .begin_transaction(pb::BeginTransactionRequest {
+            table_uri: table_uri.to_string(),
+        })
+        .await
+        .expect("BeginTransaction should succeed")
+        .into_inner()
+}
+
+async fn stage(
+    client: &mut Client,
+    txn_id: &str,
+    actions: Vec<pb::Action>,
+) -> Result<i64, tonic::Status> {
+    client
+        .stage_actions(pb::StageActionsRequest {
+            txn_id: txn_id.to_string(),
+            actions,
+        })
+        .await
+        .map(|response| response.into_inner().staged_action_count)
+}
+
+async fn commit_transaction(
+    client: &mut Client,
+    txn_id: &str,
+) -> Result<pb::CommitResponse, tonic::Status> {
+    client
+        .commit_transaction(pb::CommitTransactionRequest {
+            txn_id: txn_id.to_string(),
+        })
+        .await
+        .map(|response| response.into_inner())
+}
+
+#[tokio::test]
+async fn staged_actions_commit_as_one_version() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri, &[]).await;
+
+    let txn = begin(&mut client, &table_uri).await;
+    assert_eq!(txn.read_version, 0);
+    assert_eq!(txn.idle_ttl_seconds, 30 * 60);
+    for (i, expected) in (0..3).zip(1..) {
+        let path = format!("part-{i}.parquet");
+        let staged = stage(&mut client, &txn.txn_id, vec![add_file_action(&path, 10)])
+            .await
+            .expect("staging should succeed");
+        assert_eq!(staged, expected);
+    }
+    // Nothing is visible until the commit.
+    assert!(active_paths(&mut client, &table_uri).await.is_empty());
+
+    let response = commit_transaction(&mut client, &txn.txn_id)
+        .await
+        .expect("the transaction should commit");
+    assert_eq!(
+        response,
+        pb::CommitResponse {
+            committed_version: 1,
+            idempotent_replay: false,
+        }
+    );
+    assert_eq!(
+        active_paths(&mut client, &table_uri).await,
+        ["part-0.parquet", "part-1.parquet", "part-2.parquet"]
+    );
+
+    // A retry whose response was lost gets the original's version back;
+    // nothing more can be staged.
+    let response = commit_transaction(&mut client, &txn.txn_id)
+        .await
+        .expect("a retried commit should be answered as a replay");
+    assert_eq!(
+        response,
+        pb::CommitResponse {
+            committed_version: 1,
+            idempotent_replay: true,
+        }
+    );
+    let err = stage(&mut client, &txn.txn_id, Vec::new())
+        .await
+        .expect_err("a committed transaction can't stage more");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn an_aborted_transaction_commits_nothing() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri, &[]).await;
+
+    let txn = begin(&mut client, &table_uri).await;
+    stage(
+        &mut client,
+        &txn.txn_id,
+        vec![add_file_action("part-0.parquet", 10)],
+    )
+    .await
+    .expect("staging should succeed");
+    client
+        .abort_transaction(pb::AbortTransactionRequest {
+            txn_id: txn.txn_id.clone(),
+        })
+        .await
+        .expect("AbortTransaction should succeed");
+
+    let err = commit_transaction(&mut client, &txn.txn_id)
+        .await
+        .expect_err("an aborted transaction can't commit");
+    assert_eq!(err.code(), Code::NotFound);
+    assert!(active_paths(&mut client, &table_uri).await.is_empty());
+
+    let err = client
+        .begin_transaction(pb::BeginTransactionRequest {
+            table_uri: server.new_table_uri("missing"),
+        })
+        .await
+        .expect_err("transactions are begun on existing tables");
+    assert_eq!(err.code(), Code::NotFound);
+    let err = stage(&mut client, "no-such-txn", Vec::new())
+        .await
+        .expect_err("an unknown txn_id");
+    assert_eq!(err.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn a_staged_commit_isnt_failed_by_an_append_since_begin() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri, &["part-0.parquet"]).await;
+
+    let txn = begin(&mut client, &table_uri).await;
+    stage(
+        &mut client,
+        &txn.txn_id,
+        vec![add_file_action("part-1.parquet", 10)],
+    )
+    .await
+    .expect("staging should succeed");
+
+    // Another writer's append since Begin doesn't touch anything the
+    // staged append read.
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(txn.read_version),
+            vec![add_file_action("part-2.parquet", 10)],
+        ))
+        .await
+        .expect("the concurrent append should succeed");
+    let response = commit_transaction(&mut client, &txn.txn_id)
+        .await
+        .expect("an append doesn't conflict with another append");
+    assert_eq!(response.committed_version, txn.read_version + 2);
+    assert_eq!(
+        active_paths(&mut client, &table_uri).await,
+        ["part-0.parquet", "part-1.parquet", "part-2.parquet"]
+    );
+}
+
+#[tokio::test]
+async fn a_staged_commit_conflicting_with_a_commit_since_begin_is_aborted() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_table(
+        &mut client,
+        &table_uri,
+        &["part-0.parquet", "part-1.parquet"],
+    )
+    .await;
+
+    let txn = begin(&mut client, &table_uri).await;
+    stage(
+        &mut client,
+        &txn.txn_id,
+        vec![
+            remove_file_action("part-0.parquet"),
+            add_file_action("part-0-rewritten.parquet", 10),
+        ],
+    )
+    .await
+    .expect("staging should succeed");
+
+    // A delete of the file the rewrite removes conflicts, even though the
+    // table's latest version has moved past it by the time of the commit.
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(txn.read_version),
+            vec![remove_file_action("part-0.parquet")],
+        ))
+        .await
+        .expect("the concurrent delete should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(txn.read_version + 1),
+            vec![add_file_action("part-2.parquet", 10)],
+        ))
+        .await
+        .expect("a later append should succeed");
+    let err = commit_transaction(&mut client, &txn.txn_id)
+        .await
+        .expect_err("part-0 was removed since Begin");
+    assert_eq!(err.code(), Code::Aborted);
+    assert!(err.message().contains("deleted"), "{}", err.message());
+    // A conflict would fail the same way on retry, so it closes the
+    // transaction.
+    let err = commit_transaction(&mut client, &txn.txn_id)
+        .await
+        .expect_err("the failed commit closed it");
+    assert_eq!(err.code(), Code::NotFound);
+    assert_eq!(
+        active_paths(&mut client, &table_uri).await,
+        ["part-1.parquet", "part-2.parquet"]
+    );
+}
+
+#[tokio::test]
+async fn staged_data_is_bounded_and_idle_transactions_expire() {
+    let server = TestServer::start(TestServerConfig {
+        staging_limits: StagingLimits {
+            idle_ttl: Duration::from_secs(1),
+            max_bytes: 4096,
+        },
+        ..Default::default()
+    })
+    .await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri, &[]).await;
+
+    let txn = begin(&mut client, &table_uri).await;
+    assert_eq!(txn.idle_ttl_seconds, 1);
+    let too_many = (0..100)
+        .map(|i| add_file_action(&format!("part-{i}.parquet"), 10))
+        .collect();
+    let err = stage(&mut client, &txn.txn_id, too_many)
+        .await
+        .expect_err("more than the server holds");
+    assert_eq!(err.code(), Code::ResourceExhausted);
+    // Refused whole; the transaction carries on as it was.
+    assert_eq!(
+        stage(
+            &mut client,
+            &txn.txn_id,
+            vec![add_file_action("part-0.parquet", 10)]
+        )
+        .await
+        .expect("a smaller batch still fits"),
+        1
+    );
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let err = commit_transaction(&mut client, &txn.txn_id)
+        .await
+        .expect_err("the transaction expired while idle");
+    assert_eq!(err.code(), Code::NotFound);
+    assert!(active_paths(&mut client, &table_uri).await.is_empty());
+}
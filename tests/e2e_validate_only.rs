@@ -0,0 +1,200 @@
+//! validate_only Commits: a dry-run create and append answering with the
+//! version they'd land at while leaving the table (or the lack of one)
+//! untouched, a request the real Commit would reject failing the dry
+//! run the same way, and a dry run built on an older snapshot
+//! conflict-checked against the versions since.
+
+mod common;
+
+use common::{
+    add_file_action, commit_request, create_table_actions, pb, remove_file_action, table_version,
+    TestServer,
+};
+use std::collections::HashMap;
+
+use delta_txn_service::delta::commit::validate_actions;
+use delta_txn_service::delta::errors::DeltaTxnError;
+use delta_txn_service::delta::table::open_table_at_version;
+use delta_txn_service::grpc::mapping::map_actions;
+use tonic::Code;
+use url::Url;
+
+fn dry_run(request: pb::CommitRequest) -> pb::CommitRequest {
+    pb::CommitRequest {
+        validate_only: true,
+        ..request
+    }
+}
+
+#[tokio::test]
+async fn a_dry_run_reports_the_version_without_writing() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+
+    let create = commit_request(&table_uri, None, create_table_actions("events"));
+    let response = client
+        .commit(dry_run(create.clone()))
+        .await
+        .expect("a dry-run create should validate")
+        .into_inner();
+    assert_eq!(response.committed_version, 0);
+    assert!(
+        !server.table_path("events").join("_delta_log").exists(),
+        "a dry run must not create the table"
+    );
+    let err = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            ..Default::default()
+        })
+        .await
+        .expect_err("the table still doesn't exist");
+    assert_eq!(err.code(), Code::NotFound);
+
+    client
+        .commit(create)
+        .await
+        .expect("the real create should succeed");
+    let append = commit_request(
+        &table_uri,
+        Some(0),
+        vec![add_file_action("part-0.parquet", 10)],
+    );
+    for _ in 0..2 {
+        let response = client
+            .commit(dry_run(append.clone()))
+            .await
+            .expect("a dry-run append should validate")
+            .into_inner();
+        assert_eq!(response.committed_version, 1);
+        assert_eq!(table_version(&mut client, &table_uri).await, 0);
+    }
+
+    let response = client
+        .commit(append)
+        .await
+        .expect("the real append should succeed")
+        .into_inner();
+    assert_eq!(response.committed_version, 1);
+}
+
+#[tokio::test]
+async fn a_dry_run_fails_the_way_the_commit_would() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+
+    let err = client
+        .commit(dry_run(commit_request(
+            &table_uri,
+            None,
+            vec![add_file_action("part-0.parquet", 10)],
+        )))
+        .await
+        .expect_err("creating a table needs a Protocol and TableMetadata");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let mut unspecified = add_file_action("part-0.parquet", 10);
+    if let Some(pb::action::Action::Add(add)) = &mut unspecified.action {
+        add.data_change = pb::DataChange::Unspecified as i32;
+    }
+    let cases = [
+        (
+            "a stale expected_version",
+            commit_request(
+                &table_uri,
+                Some(7),
+                vec![add_file_action("part-0.parquet", 10)],
+            ),
+            Code::Aborted,
+        ),
+        (
+            "an unconvertible action",
+            commit_request(&table_uri, Some(0), vec![unspecified]),
+            Code::InvalidArgument,
+        ),
+    ];
+    for (case, request, code) in cases {
+        let real = client.commit(request.clone()).await.expect_err(case);
+        let dry = client.commit(dry_run(request)).await.expect_err(case);
+        assert_eq!(dry.code(), code, "{case}");
+        assert_eq!(
+            (dry.code(), dry.message()),
+            (real.code(), real.message()),
+            "{case}"
+        );
+    }
+    assert_eq!(table_version(&mut client, &table_uri).await, 0);
+}
+
+#[tokio::test]
+async fn a_dry_run_is_conflict_checked_against_the_versions_since_its_snapshot() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("events"),
+        ))
+        .await
+        .expect("create should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![add_file_action("part-0.parquet", 10)],
+        ))
+        .await
+        .expect("append should succeed");
+    let snapshot = open_table_at_version(&Url::parse(&table_uri).unwrap(), HashMap::new(), 1)
+        .await
+        .unwrap();
+
+    // Since version 1: part-0 removed, then an unrelated append.
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(1),
+            vec![remove_file_action("part-0.parquet")],
+        ))
+        .await
+        .expect("delete should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(2),
+            vec![add_file_action("part-1.parquet", 10)],
+        ))
+        .await
+        .expect("append should succeed");
+
+    let remove = map_actions(vec![remove_file_action("part-0.parquet")]).unwrap();
+    let err = validate_actions(&snapshot, remove, HashMap::new())
+        .await
+        .expect_err("part-0 was removed since version 1");
+    assert!(matches!(err, DeltaTxnError::CommitConflict(_)), "{err}");
+
+    // Since version 2, only the append.
+    let snapshot = open_table_at_version(&Url::parse(&table_uri).unwrap(), HashMap::new(), 2)
+        .await
+        .unwrap();
+    let append = map_actions(vec![add_file_action("part-2.parquet", 10)]).unwrap();
+    let (version, handed_back) = validate_actions(&snapshot, append.clone(), HashMap::new())
+        .await
+        .expect("an append doesn't conflict with an append");
+    assert_eq!(version, 4);
+    assert_eq!(handed_back, append, "the actions come back as they went in");
+    assert_eq!(table_version(&mut client, &table_uri).await, 3);
+}
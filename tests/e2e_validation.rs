@@ -6,8 +6,12 @@
 
 mod common;
 
-use common::{add_file_action, commit_request, create_table_actions, pb};
+use common::{
+    add_file_action, commit_request, create_partitioned_table_actions, create_table_actions,
+    partitioned_add_file_action, pb,
+};
 use tonic::Code;
+use tonic_types::StatusExt;
 
 #[tokio::test]
 async fn get_table_on_a_table_uri_that_does_not_exist_returns_not_found() {
@@ -18,6 +22,7 @@ async fn get_table_on_a_table_uri_that_does_not_exist_returns_not_found() {
     let err = client
         .get_table(pb::GetTableRequest {
             table_uri: table_uri.clone(),
+            ..Default::default()
         })
         .await
         .expect_err("GetTable on a nonexistent table_uri must fail");
@@ -34,6 +39,7 @@ async fn list_active_files_on_a_table_uri_that_does_not_exist_returns_not_found(
     let mut stream = client
         .list_active_files(pb::ListActiveFilesRequest {
             table_uri: table_uri.clone(),
+            ..Default::default()
         })
         .await
         .expect("the RPC call itself succeeds; the error arrives as the stream's first item")
@@ -131,6 +137,10 @@ async fn commit_rejects_an_action_with_unspecified_data_change() {
             data_change: pb::DataChange::Unspecified as i32,
             stats: None,
             tags: Default::default(),
+            nullable_partition_values: Vec::new(),
+            deletion_vector: None,
+            base_row_id: None,
+            default_row_commit_version: None,
         })),
     };
 
@@ -140,3 +150,133 @@ async fn commit_rejects_an_action_with_unspecified_data_change() {
         .expect_err("an AddFile with DATA_CHANGE_UNSPECIFIED must be rejected");
     assert_eq!(err.code(), Code::InvalidArgument);
 }
+
+#[tokio::test]
+async fn commit_rejects_adds_whose_partition_values_dont_fit_the_table() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("events");
+    let mut client = server.connect().await;
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_partitioned_table_actions("events"),
+        ))
+        .await
+        .expect("create commit should succeed");
+
+    let mut misspelled = partitioned_add_file_action("b.parquet", ("2026-10-18", "9", "eu"));
+    if let Some(pb::action::Action::Add(add)) = &mut misspelled.action {
+        let region = add.partition_values.remove("region").unwrap();
+        add.partition_values.insert("regoin".to_string(), region);
+    }
+    let err = client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![
+                partitioned_add_file_action("a.parquet", ("2026-10-18", "9", "eu")),
+                misspelled,
+                partitioned_add_file_action("c.parquet", ("2026-10-18", "nine", "eu")),
+            ],
+        ))
+        .await
+        .expect_err("adds with bad partition values must be rejected");
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    let bad_request = err
+        .get_details_bad_request()
+        .expect("a BadRequest detail listing each violation");
+    let fields: Vec<_> = bad_request
+        .field_violations
+        .iter()
+        .map(|v| v.field.as_str())
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            "actions[1].add.partition_values",
+            "actions[1].add.partition_values.regoin",
+            "actions[2].add.partition_values.hour",
+        ]
+    );
+    assert!(bad_request.field_violations[2]
+        .description
+        .contains("'c.parquet'"));
+
+    // A commit with more violations than fit in a trailer gets the first
+    // few in the detail and the full count in the message.
+    let bad_adds = (0..250)
+        .map(|i| partitioned_add_file_action(&format!("{i}.parquet"), ("2026-10-18", "nine", "eu")))
+        .collect();
+    let err = client
+        .commit(commit_request(&table_uri, Some(0), bad_adds))
+        .await
+        .expect_err("adds with bad partition values must be rejected");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    let bad_request = err
+        .get_details_bad_request()
+        .expect("a BadRequest detail listing the first violations");
+    let reported = bad_request.field_violations.len();
+    assert!((1..=100).contains(&reported), "{reported}");
+    assert!(
+        err.message().starts_with(&format!(
+            "250 invalid AddFile partition value(s) (details list the first {reported})"
+        )),
+        "{}",
+        err.message()
+    );
+
+    // Nothing was committed: the valid add went nowhere either.
+    let table = client
+        .get_table(pb::GetTableRequest {
+            table_uri: table_uri.clone(),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(table.version, 0);
+}
+
+#[tokio::test]
+async fn commit_rejects_partition_values_on_an_unpartitioned_table() {
+    let server = common::TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+
+    // Checked on the create commit too, against its own metadata.
+    let mut actions = create_table_actions("orders");
+    actions.push(partitioned_add_file_action(
+        "a.parquet",
+        ("2026-10-18", "9", "eu"),
+    ));
+    let err = client
+        .commit(commit_request(&table_uri, None, actions))
+        .await
+        .expect_err("an unpartitioned table's adds take no partition values");
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(
+        err.message().contains("not partitioned"),
+        "got: {}",
+        err.message()
+    );
+
+    client
+        .commit(commit_request(
+            &table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+    client
+        .commit(commit_request(
+            &table_uri,
+            Some(0),
+            vec![add_file_action("a.parquet", 1)],
+        ))
+        .await
+        .expect("an add with no partition values is fine");
+}
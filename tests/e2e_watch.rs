@@ -0,0 +1,199 @@
+//! WatchTable: a long-lived stream announcing each new version -- for
+//! commits through this server (published straight from Commit), for
+//! commits by some other writer (picked up by the table's log-tail poller),
+//! and for a consumer resuming from an older version -- plus the ways a
+//! watch is rejected.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{add_file_action, commit_request, create_table_actions, pb, TestServer};
+use delta_txn_service::delta::{commit::commit_actions, table::open_table};
+use delta_txn_service::grpc::mapping::map_actions;
+use tonic::{Code, Streaming};
+
+type Client = pb::delta_txn_service_client::DeltaTxnServiceClient<tonic::transport::Channel>;
+
+// Comfortably longer than the server's poll interval (one second), so an
+// external commit is always picked up well inside it.
+const ANNOUNCEMENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn watch_request(table_uri: &str, from_version: Option<i64>) -> pb::WatchTableRequest {
+    pb::WatchTableRequest {
+        table_uri: table_uri.to_string(),
+        from_version,
+    }
+}
+
+async fn watch(
+    client: &mut Client,
+    req: pb::WatchTableRequest,
+) -> Streaming<pb::WatchTableResponse> {
+    client
+        .watch_table(req)
+        .await
+        .expect("WatchTable should succeed")
+        .into_inner()
+}
+
+/// The next version the stream announces, failing the test if none arrives
+/// in time.
+async fn next_version(stream: &mut Streaming<pb::WatchTableResponse>) -> i64 {
+    tokio::time::timeout(ANNOUNCEMENT_TIMEOUT, stream.message())
+        .await
+        .expect("timed out waiting for a version announcement")
+        .expect("stream should not error")
+        .expect("a watch stream never ends on its own")
+        .version
+}
+
+async fn create_table(client: &mut Client, table_uri: &str) {
+    client
+        .commit(commit_request(
+            table_uri,
+            None,
+            create_table_actions("orders"),
+        ))
+        .await
+        .expect("create commit should succeed");
+}
+
+async fn append(client: &mut Client, table_uri: &str, expected_version: i64) {
+    client
+        .commit(commit_request(
+            table_uri,
+            Some(expected_version),
+            vec![add_file_action(
+                &format!("part-{expected_version}.parquet"),
+                1,
+            )],
+        ))
+        .await
+        .expect("append should succeed");
+}
+
+#[tokio::test]
+async fn a_watch_starts_at_the_current_version_and_announces_each_new_commit() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri).await;
+    append(&mut client, &table_uri, 0).await;
+
+    let mut stream = watch(&mut client, watch_request(&table_uri, None)).await;
+    assert_eq!(next_version(&mut stream).await, 1);
+
+    append(&mut client, &table_uri, 1).await;
+    assert_eq!(next_version(&mut stream).await, 2);
+    append(&mut client, &table_uri, 2).await;
+    append(&mut client, &table_uri, 3).await;
+    // Back-to-back commits are each announced.
+    assert_eq!(next_version(&mut stream).await, 3);
+    assert_eq!(next_version(&mut stream).await, 4);
+}
+
+#[tokio::test]
+async fn a_watch_from_an_older_version_replays_what_it_missed_first() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri).await;
+    append(&mut client, &table_uri, 0).await;
+    append(&mut client, &table_uri, 1).await;
+
+    let mut stream = watch(&mut client, watch_request(&table_uri, Some(0))).await;
+    assert_eq!(next_version(&mut stream).await, 1);
+    assert_eq!(next_version(&mut stream).await, 2);
+
+    append(&mut client, &table_uri, 2).await;
+    assert_eq!(next_version(&mut stream).await, 3);
+}
+
+#[tokio::test]
+async fn a_watch_from_a_future_version_waits_for_the_table_to_catch_up() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri).await;
+
+    let mut stream = watch(&mut client, watch_request(&table_uri, Some(1))).await;
+    append(&mut client, &table_uri, 0).await;
+    append(&mut client, &table_uri, 1).await;
+    assert_eq!(
+        next_version(&mut stream).await,
+        2,
+        "versions up to from_version are not announced"
+    );
+}
+
+#[tokio::test]
+async fn commits_by_other_writers_are_picked_up_by_polling() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri).await;
+
+    let mut stream = watch(&mut client, watch_request(&table_uri, None)).await;
+    assert_eq!(next_version(&mut stream).await, 0);
+
+    // Straight through delta-rs, bypassing the server's Commit handler --
+    // the same thing any other Delta writer appending to this table does.
+    let url = url::Url::parse(&table_uri).unwrap();
+    let table = open_table(&url, Default::default()).await.unwrap();
+    let actions = map_actions(vec![add_file_action("external.parquet", 1)]).unwrap();
+    commit_actions(table, actions, Default::default())
+        .await
+        .expect("external commit should succeed");
+
+    assert_eq!(next_version(&mut stream).await, 1);
+}
+
+#[tokio::test]
+async fn several_watchers_of_one_table_each_see_every_version() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri).await;
+
+    let mut first = watch(&mut client, watch_request(&table_uri, Some(0))).await;
+    let mut second = watch(&mut client, watch_request(&table_uri, Some(0))).await;
+    append(&mut client, &table_uri, 0).await;
+
+    assert_eq!(next_version(&mut first).await, 1);
+    assert_eq!(next_version(&mut second).await, 1);
+
+    // One watcher leaving doesn't disturb the other.
+    drop(first);
+    append(&mut client, &table_uri, 1).await;
+    assert_eq!(next_version(&mut second).await, 2);
+}
+
+#[tokio::test]
+async fn a_negative_from_version_is_an_invalid_argument() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("orders");
+    let mut client = server.connect().await;
+    create_table(&mut client, &table_uri).await;
+
+    // Rejected synchronously, before the stream even starts.
+    let err = client
+        .watch_table(watch_request(&table_uri, Some(-1)))
+        .await
+        .expect_err("a negative from_version must fail the RPC call itself");
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn watching_a_missing_table_is_not_found() {
+    let server = TestServer::start(Default::default()).await;
+    let table_uri = server.new_table_uri("never-created");
+    let mut client = server.connect().await;
+
+    let mut stream = watch(&mut client, watch_request(&table_uri, None)).await;
+    let err = stream
+        .message()
+        .await
+        .expect_err("the stream's first (and only) item should be an error");
+    assert_eq!(err.code(), Code::NotFound);
+}